@@ -1,139 +0,0 @@
-/*!40101 SET @OLD_CHARACTER_SET_CLIENT=@@CHARACTER_SET_CLIENT */;
-/*!40101 SET @OLD_CHARACTER_SET_RESULTS=@@CHARACTER_SET_RESULTS */;
-/*!40101 SET @OLD_COLLATION_CONNECTION=@@COLLATION_CONNECTION */;
-/*!40101 SET NAMES utf8 */;
-SET NAMES utf8mb4;
-/*!40014 SET @OLD_FOREIGN_KEY_CHECKS=@@FOREIGN_KEY_CHECKS, FOREIGN_KEY_CHECKS=0 */;
-/*!40101 SET @OLD_SQL_MODE=@@SQL_MODE, SQL_MODE='NO_AUTO_VALUE_ON_ZERO' */;
-/*!40111 SET @OLD_SQL_NOTES=@@SQL_NOTES, SQL_NOTES=0 */;
-
-
-# Dump of table function_conf
-# ------------------------------------------------------------
-
-DROP TABLE IF EXISTS `function_conf`;
-
-CREATE TABLE `function_conf` (
-  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
-  `function_id` int(11) unsigned NOT NULL,
-  `name` varchar(128) NOT NULL DEFAULT '',
-  `value` varchar(512) NOT NULL DEFAULT '',
-  `conf_type` varchar(16) NOT NULL DEFAULT '',
-  `status` varchar(16) NOT NULL DEFAULT 'active',
-  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `deleted_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`id`),
-  KEY `function_id` (`function_id`),
-  KEY `conf_type` (`conf_type`),
-  CONSTRAINT `fn_info_id` FOREIGN KEY (`id`) REFERENCES `function_info` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
-
-
-
-# Dump of table function_info
-# ------------------------------------------------------------
-
-DROP TABLE IF EXISTS `function_info`;
-
-CREATE TABLE `function_info` (
-  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
-  `user_id` int(11) unsigned NOT NULL,
-  `uuid` varchar(64) NOT NULL DEFAULT '',
-  `name` varchar(32) NOT NULL DEFAULT '',
-  `resource` int(11) unsigned NOT NULL,
-  `function_type` varchar(16) NOT NULL DEFAULT '',
-  `storage_path` varchar(128) NOT NULL DEFAULT '',
-  `storage_size` int(11) NOT NULL,
-  `storage_md5` varchar(40) NOT NULL DEFAULT '',
-  `status` varchar(16) NOT NULL DEFAULT '',
-  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `deleted_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`id`),
-  UNIQUE KEY `uuid` (`uuid`),
-  KEY `status` (`status`),
-  KEY `fn_info_resource` (`resource`),
-  KEY `fn_info_user` (`user_id`),
-  CONSTRAINT `fn_info_resource` FOREIGN KEY (`resource`) REFERENCES `function_resource` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION,
-  CONSTRAINT `fn_info_user` FOREIGN KEY (`user_id`) REFERENCES `user_info` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
-
-
-
-# Dump of table function_resource
-# ------------------------------------------------------------
-
-DROP TABLE IF EXISTS `function_resource`;
-
-CREATE TABLE `function_resource` (
-  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
-  `name` varchar(32) NOT NULL DEFAULT '',
-  `cpu_time` int(11) NOT NULL DEFAULT '1000',
-  `memory_usage` int(11) NOT NULL DEFAULT '128',
-  `wall_time` int(11) NOT NULL DEFAULT '30000',
-  `fetch_counts` int(11) NOT NULL DEFAULT '5',
-  `fetch_remote_list` varchar(256) NOT NULL DEFAULT '*',
-  `status` varchar(12) NOT NULL DEFAULT 'active',
-  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`id`),
-  UNIQUE KEY `name` (`name`),
-  KEY `status` (`status`)
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
-
-
-
-# Dump of table user_info
-# ------------------------------------------------------------
-
-DROP TABLE IF EXISTS `user_info`;
-
-CREATE TABLE `user_info` (
-  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
-  `uuid` varchar(32) NOT NULL,
-  `username` varchar(32) NOT NULL,
-  `nickname` varchar(32) NOT NULL,
-  `email` varchar(256) NOT NULL,
-  `avatar` varchar(256) NOT NULL,
-  `phone` varchar(32) NOT NULL,
-  `status` varchar(12) NOT NULL,
-  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`id`),
-  UNIQUE KEY `email` (`email`),
-  KEY `uuid` (`uuid`),
-  KEY `status` (`status`)
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
-
-
-
-# Dump of table user_token
-# ------------------------------------------------------------
-
-DROP TABLE IF EXISTS `user_token`;
-
-CREATE TABLE `user_token` (
-  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
-  `user_id` int(11) unsigned NOT NULL,
-  `access_token` varchar(128) NOT NULL,
-  `secret_token` varchar(128) NOT NULL,
-  `from` varchar(32) NOT NULL,
-  `status` varchar(12) NOT NULL,
-  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  `expired_at` int(12) NOT NULL DEFAULT '0',
-  PRIMARY KEY (`id`),
-  KEY `user-id` (`user_id`),
-  KEY `token-from` (`access_token`,`from`),
-  KEY `status` (`status`),
-  CONSTRAINT `user_token_user_info` FOREIGN KEY (`user_id`) REFERENCES `user_info` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
-
-
-
-
-/*!40111 SET SQL_NOTES=@OLD_SQL_NOTES */;
-/*!40101 SET SQL_MODE=@OLD_SQL_MODE */;
-/*!40014 SET FOREIGN_KEY_CHECKS=@OLD_FOREIGN_KEY_CHECKS */;
-/*!40101 SET CHARACTER_SET_CLIENT=@OLD_CHARACTER_SET_CLIENT */;
-/*!40101 SET CHARACTER_SET_RESULTS=@OLD_CHARACTER_SET_RESULTS */;
-/*!40101 SET COLLATION_CONNECTION=@OLD_COLLATION_CONNECTION */;
@@ -0,0 +1,297 @@
+use hyper::http::{header, HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use hyper::Body;
+use moss_lib::metadata::MetadataStatic;
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// DEFAULT_INDEX is the file served for directory paths if index is not set
+const DEFAULT_INDEX: &str = "index.html";
+
+/// ByteRange is the parsed Range header of a file
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    /// Partial is the inclusive start and end of range
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// parse_range parses Range header, multiple ranges and invalid values are served as full file
+fn parse_range(range: &str, len: u64) -> ByteRange {
+    let spec = match range.trim().strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(pair) => pair,
+        None => return ByteRange::Full,
+    };
+    // suffix range is the last bytes of file
+    if start.is_empty() {
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial(len.saturating_sub(suffix), len - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+    let start = match start.parse::<u64>() {
+        Ok(start) => start,
+        Err(_) => return ByteRange::Full,
+    };
+    let end = if end.is_empty() {
+        u64::MAX
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end,
+            _ => return ByteRange::Full,
+        }
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end.min(len - 1))
+}
+
+/// decode_path decodes percent-encoded request path
+fn decode_path(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// StaticAssets serves files of a directory with ETags, range requests and MIME types
+pub struct StaticAssets {
+    dir: PathBuf,
+    prefix: String,
+    index: String,
+}
+
+impl StaticAssets {
+    pub fn new(config: &MetadataStatic) -> Self {
+        let prefix = config.prefix.clone().unwrap_or_default();
+        Self {
+            dir: PathBuf::from(&config.dir),
+            prefix: prefix.trim_end_matches('/').to_string(),
+            index: config
+                .index
+                .clone()
+                .unwrap_or_else(|| DEFAULT_INDEX.to_string()),
+        }
+    }
+
+    /// resolve returns file path of request path, None if path is out of prefix or directory
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let rest = path.strip_prefix(self.prefix.as_str())?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        let mut file = self.dir.clone();
+        for segment in decode_path(rest)?.split('/') {
+            match segment {
+                "" | "." => continue,
+                ".." => return None,
+                s if s.contains('\\') => return None,
+                s => file.push(s),
+            }
+        }
+        Some(file)
+    }
+
+    /// serve returns response of asset file, None if request is not for an existing file
+    pub async fn serve(&self, req: &Request<Body>) -> Option<Response<Body>> {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return None;
+        }
+        let mut file = self.resolve(req.uri().path())?;
+        let mut metadata = tokio::fs::metadata(&file).await.ok()?;
+        if metadata.is_dir() {
+            file.push(&self.index);
+            metadata = tokio::fs::metadata(&file).await.ok()?;
+        }
+        if !metadata.is_file() {
+            return None;
+        }
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default()
+            .as_secs();
+        let etag = format!("\"{modified:x}-{:x}\"", metadata.len());
+        let last_modified = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(modified));
+
+        let builder = Response::builder()
+            .header(header::ETAG, &etag)
+            .header(header::LAST_MODIFIED, &last_modified)
+            .header(header::ACCEPT_RANGES, "bytes");
+        if is_not_modified(req.headers(), &etag, modified) {
+            return builder
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Body::empty())
+                .ok();
+        }
+
+        let content_type = mime_guess::from_path(&file).first_or_octet_stream();
+        let builder = builder.header(header::CONTENT_TYPE, content_type.as_ref());
+        // range is checked against read data in case file is changed after metadata
+        let data = tokio::fs::read(&file).await.ok()?;
+        let len = data.len() as u64;
+        let range = match header_str(req.headers(), header::RANGE) {
+            Some(range) if is_range_fresh(req.headers(), &etag, &last_modified) => {
+                parse_range(range, len)
+            }
+            _ => ByteRange::Full,
+        };
+        let resp = match range {
+            ByteRange::Full => builder.body(Body::from(data)),
+            ByteRange::Partial(start, end) => builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                .body(Body::from(data[start as usize..=end as usize].to_vec())),
+            ByteRange::Unsatisfiable => builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+                .body(Body::empty()),
+        };
+        resp.ok()
+    }
+}
+
+fn header_str(headers: &HeaderMap<HeaderValue>, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// is_not_modified checks If-None-Match, or If-Modified-Since if If-None-Match is not sent
+fn is_not_modified(headers: &HeaderMap<HeaderValue>, etag: &str, modified: u64) -> bool {
+    if let Some(tags) = header_str(headers, header::IF_NONE_MATCH) {
+        return tags.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*" || tag.trim_start_matches("W/") == etag
+        });
+    }
+    match header_str(headers, header::IF_MODIFIED_SINCE).map(httpdate::parse_http_date) {
+        Some(Ok(since)) => UNIX_EPOCH + Duration::from_secs(modified) <= since,
+        _ => false,
+    }
+}
+
+/// is_range_fresh checks If-Range, range is ignored if file is changed
+fn is_range_fresh(headers: &HeaderMap<HeaderValue>, etag: &str, last_modified: &str) -> bool {
+    match header_str(headers, header::IF_RANGE) {
+        Some(value) => value == etag || value == last_modified,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_header() {
+        assert_eq!(parse_range("bytes=0-4", 10), ByteRange::Partial(0, 4));
+        assert_eq!(parse_range("bytes=5-", 10), ByteRange::Partial(5, 9));
+        assert_eq!(parse_range("bytes=5-100", 10), ByteRange::Partial(5, 9));
+        assert_eq!(parse_range("bytes=-3", 10), ByteRange::Partial(7, 9));
+        assert_eq!(parse_range("bytes=-30", 10), ByteRange::Partial(0, 9));
+        assert_eq!(parse_range("bytes=10-", 10), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 10), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,3-4", 10), ByteRange::Full);
+        assert_eq!(parse_range("bytes=4-1", 10), ByteRange::Full);
+        assert_eq!(parse_range("items=0-1", 10), ByteRange::Full);
+    }
+
+    #[tokio::test]
+    async fn serve_assets() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("index.html"), "<h1>moss</h1>").unwrap();
+        std::fs::write(dir.join("docs/a b.css"), "body{}").unwrap();
+        let assets = StaticAssets::new(&MetadataStatic {
+            dir: dir.to_str().unwrap().to_string(),
+            prefix: Some("/static/".to_string()),
+            index: None,
+        });
+        let request = |method: Method, path: &str, headers: &[(header::HeaderName, &str)]| {
+            let mut builder = Request::builder().method(method).uri(path);
+            for (key, value) in headers {
+                builder = builder.header(key, *value);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+        let serve = |req: Request<Body>| {
+            let assets = &assets;
+            async move { assets.serve(&req).await }
+        };
+
+        let resp = serve(request(Method::GET, "/static/", &[])).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "text/html");
+        let etag = resp.headers()[header::ETAG].to_str().unwrap().to_string();
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(body, "<h1>moss</h1>");
+
+        let resp = serve(request(Method::GET, "/static/docs/a%20b.css", &[]))
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "text/css");
+
+        // conditional and range requests
+        let headers = [(header::IF_NONE_MATCH, etag.as_str())];
+        let resp = serve(request(Method::GET, "/static/index.html", &headers))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        let modified = std::fs::metadata(dir.join("index.html"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        let since = httpdate::fmt_http_date(modified);
+        let headers = [(header::IF_MODIFIED_SINCE, since.as_str())];
+        let resp = serve(request(Method::HEAD, "/static/index.html", &headers))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let headers = [(header::RANGE, "bytes=1-2")];
+        let resp = serve(request(Method::GET, "/static/index.html", &headers))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(resp.headers()[header::CONTENT_RANGE], "bytes 1-2/13");
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(body, "h1");
+        let headers = [(header::RANGE, "bytes=1-2"), (header::IF_RANGE, "\"old\"")];
+        let resp = serve(request(Method::GET, "/static/index.html", &headers))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // requests fall through to function
+        let name = dir.file_name().unwrap().to_str().unwrap();
+        let traversal = format!("/static/../{name}/index.html");
+        for (method, path) in [
+            (Method::POST, "/static/index.html"),
+            (Method::GET, "/static/missing.js"),
+            (Method::GET, traversal.as_str()),
+            (Method::GET, "/staticx/index.html"),
+            (Method::GET, "/index.html"),
+        ] {
+            assert!(serve(request(method, path, &[])).await.is_none());
+        }
+    }
+}
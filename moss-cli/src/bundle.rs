@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use md5::{Digest, Md5};
 use moss_lib::metadata::{Metadata, MetadataEnv, DEFAULT_METADATA_FILE};
 use moss_rpc_service::BundleUploadRequest;
@@ -15,7 +15,13 @@ pub struct Bundle {
     pub content: Vec<u8>,
 }
 
-pub fn build(output: &str, metadata: &str, src_dir: &str) -> Result<Bundle> {
+/// build creates bundle of component, metadata, src directory and static assets directory
+pub fn build(
+    output: &str,
+    metadata: &str,
+    src_dir: &str,
+    static_dir: Option<&str>,
+) -> Result<Bundle> {
     let output_path = Path::new(output);
     let output_path = output_path.file_name().unwrap().to_str().unwrap();
     let bundle_file = output_path.replace(".wasm", ".zip");
@@ -34,17 +40,14 @@ pub fn build(output: &str, metadata: &str, src_dir: &str) -> Result<Bundle> {
     zip.write_all(&std::fs::read(metadata)?)?;
 
     // add src directory
-    let src_dir = Path::new(src_dir);
-    let walkdir = WalkDir::new(src_dir);
-    for entry in walkdir.into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.is_dir() {
-            continue;
+    add_dir(&mut zip, src_dir, "src")?;
+
+    // add static assets directory
+    if let Some(static_dir) = static_dir {
+        if !Path::new(static_dir).is_dir() {
+            bail!("static directory {static_dir} not found");
         }
-        let path = path.to_str().unwrap();
-        debug!("[bundle] add src file: {}", path);
-        zip.start_file(path, Default::default())?;
-        zip.write_all(&std::fs::read(path)?)?;
+        add_dir(&mut zip, static_dir, "static")?;
     }
 
     zip.flush().expect("flush zip file");
@@ -69,6 +72,21 @@ pub fn build(output: &str, metadata: &str, src_dir: &str) -> Result<Bundle> {
     })
 }
 
+fn add_dir(zip: &mut zip::ZipWriter<std::fs::File>, dir: &str, kind: &str) -> Result<()> {
+    let walkdir = WalkDir::new(Path::new(dir));
+    for entry in walkdir.into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            continue;
+        }
+        let path = path.to_str().unwrap();
+        debug!("[bundle] add {} file: {}", kind, path);
+        zip.start_file(path, Default::default())?;
+        zip.write_all(&std::fs::read(path)?)?;
+    }
+    Ok(())
+}
+
 pub async fn deploy(env: &MetadataEnv, meta: &Metadata, bundle: &Bundle) -> Result<()> {
     // create client
     let client = moss_rpc_service::Client::new(
@@ -83,6 +101,7 @@ pub async fn deploy(env: &MetadataEnv, meta: &Metadata, bundle: &Bundle) -> Resu
         bundle_size: bundle.size as i64,
         bundle_md5: bundle.md5.clone(),
         content: bundle.content.clone(),
+        resource: meta.get_resource(),
     };
     match client.upload_function(req).await {
         Ok(response) => response,
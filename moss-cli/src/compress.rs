@@ -0,0 +1,280 @@
+use anyhow::{bail, Result};
+use std::io::{Read, Write};
+
+/// MIN_SIZE is the minimum body size to compress, smaller bodies are sent as is
+pub const MIN_SIZE: usize = 1024;
+
+/// BLOCKING_SIZE is the minimum body size to compress on blocking threads instead of async executor
+pub const BLOCKING_SIZE: usize = 64 * 1024;
+
+/// BodyTooLarge is the error of decompressed body exceeding max size
+#[derive(Debug)]
+pub struct BodyTooLarge(pub usize);
+
+impl std::fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "decompressed body is larger than {} bytes", self.0)
+    }
+}
+
+impl std::error::Error for BodyTooLarge {}
+
+/// Encoding is the content coding of compressed bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Zstd,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// ENCODINGS of responses in order of preference if client accepts them equally
+    const ENCODINGS: [Encoding; 3] = [Encoding::Brotli, Encoding::Zstd, Encoding::Gzip];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "br" => Some(Encoding::Brotli),
+            "zstd" => Some(Encoding::Zstd),
+            "gzip" | "x-gzip" => Some(Encoding::Gzip),
+            "deflate" => Some(Encoding::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// negotiate chooses response encoding by Accept-Encoding, None if client accepts no supported encoding
+pub fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let mut wildcard = None;
+    let mut accepted: Vec<(Encoding, f32)> = vec![];
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if name == "*" {
+            wildcard = Some(quality);
+        } else if let Some(encoding) = Encoding::from_name(name) {
+            accepted.push((encoding, quality));
+        }
+    }
+
+    let quality = |encoding: Encoding| {
+        accepted
+            .iter()
+            .find(|(e, _)| *e == encoding)
+            .map(|(_, q)| *q)
+            .or(wildcard)
+            .unwrap_or(0.0)
+    };
+    let (mut best, mut best_quality) = (None, 0.0);
+    for encoding in Encoding::ENCODINGS {
+        let q = quality(encoding);
+        if q > best_quality {
+            best = Some(encoding);
+            best_quality = q;
+        }
+    }
+    best
+}
+
+/// is_compressible returns whether body of content type benefits from compression
+pub fn is_compressible(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    if mime.starts_with("text/") {
+        return true;
+    }
+    matches!(
+        mime.as_str(),
+        "application/json"
+            | "application/javascript"
+            | "application/xml"
+            | "application/wasm"
+            | "image/svg+xml"
+    ) || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+}
+
+/// add_vary adds accept-encoding to vary header, merging it into the value set by function
+pub fn add_vary(headers: &mut Vec<(String, String)>) {
+    let vary = headers
+        .iter_mut()
+        .find(|(k, _)| k.eq_ignore_ascii_case("vary"));
+    match vary {
+        Some((_, value)) => {
+            let varied = value.split(',').any(|name| {
+                let name = name.trim();
+                name == "*" || name.eq_ignore_ascii_case("accept-encoding")
+            });
+            if !varied {
+                if value.trim().is_empty() {
+                    *value = "accept-encoding".to_string();
+                } else {
+                    value.push_str(", accept-encoding");
+                }
+            }
+        }
+        None => headers.push(("vary".to_string(), "accept-encoding".to_string())),
+    }
+}
+
+/// compress encodes body by encoding
+pub fn compress(encoding: Encoding, body: &[u8]) -> Result<Vec<u8>> {
+    let data = match encoding {
+        Encoding::Brotli => {
+            let mut data = vec![];
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut data, 4096, 5, 22);
+                writer.write_all(body)?;
+            }
+            data
+        }
+        Encoding::Zstd => zstd::encode_all(body, 0)?,
+        Encoding::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()?
+        }
+        Encoding::Deflate => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(vec![], flate2::Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()?
+        }
+    };
+    Ok(data)
+}
+
+/// decompress decodes body by Content-Encoding, codings are applied in order they are listed.
+/// Decoding stops with BodyTooLarge once output exceeds max_size, in case of compression bombs.
+pub fn decompress(content_encoding: &str, body: Vec<u8>, max_size: usize) -> Result<Vec<u8>> {
+    let mut data = body;
+    for name in content_encoding.rsplit(',') {
+        if name.trim().eq_ignore_ascii_case("identity") {
+            continue;
+        }
+        let encoding = match Encoding::from_name(name) {
+            Some(encoding) => encoding,
+            None => bail!("content encoding '{}' is not supported", name.trim()),
+        };
+        let reader: Box<dyn Read + '_> = match encoding {
+            Encoding::Brotli => Box::new(brotli::Decompressor::new(data.as_slice(), 4096)),
+            Encoding::Zstd => Box::new(zstd::Decoder::new(data.as_slice())?),
+            Encoding::Gzip => Box::new(flate2::read::GzDecoder::new(data.as_slice())),
+            Encoding::Deflate => Box::new(flate2::read::ZlibDecoder::new(data.as_slice())),
+        };
+        // read one more byte than limit to know whether body is over it
+        let mut decoded = vec![];
+        reader.take(max_size as u64 + 1).read_to_end(&mut decoded)?;
+        if decoded.len() > max_size {
+            return Err(BodyTooLarge(max_size).into());
+        }
+        data = decoded;
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: usize = 1024 * 1024;
+
+    #[test]
+    fn negotiate_encoding() {
+        assert_eq!(negotiate("gzip, deflate, br"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("gzip;q=1.0, br;q=0.5"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("zstd, gzip"), Some(Encoding::Zstd));
+        assert_eq!(negotiate("*"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("*;q=0.5, br;q=0"), Some(Encoding::Zstd));
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate("deflate"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[test]
+    fn compressible_content_type() {
+        assert!(is_compressible("text/html; charset=utf-8"));
+        assert!(is_compressible("application/json"));
+        assert!(is_compressible("application/problem+json"));
+        assert!(!is_compressible("image/png"));
+        assert!(!is_compressible(""));
+    }
+
+    #[test]
+    fn vary_accept_encoding() {
+        let vary = |headers: &[(&str, &str)]| {
+            let mut headers: Vec<(String, String)> = headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            add_vary(&mut headers);
+            headers
+                .into_iter()
+                .filter(|(k, _)| k.eq_ignore_ascii_case("vary"))
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vary(&[]), vec!["accept-encoding"]);
+        assert_eq!(vary(&[("Vary", "Origin")]), vec!["Origin, accept-encoding"]);
+        assert_eq!(
+            vary(&[("vary", "origin, Accept-Encoding")]),
+            vec!["origin, Accept-Encoding"]
+        );
+        assert_eq!(vary(&[("vary", "*")]), vec!["*"]);
+    }
+
+    #[test]
+    fn compress_roundtrip() {
+        let body = "hello moss ".repeat(200).into_bytes();
+        for encoding in [
+            Encoding::Brotli,
+            Encoding::Zstd,
+            Encoding::Gzip,
+            Encoding::Deflate,
+        ] {
+            let data = compress(encoding, &body).unwrap();
+            assert!(data.len() < body.len());
+            assert_eq!(decompress(encoding.as_str(), data, MAX).unwrap(), body);
+        }
+
+        let data = compress(Encoding::Gzip, &compress(Encoding::Zstd, &body).unwrap()).unwrap();
+        assert_eq!(decompress("zstd, gzip", data, MAX).unwrap(), body);
+        assert_eq!(decompress("identity", body.clone(), MAX).unwrap(), body);
+        assert!(decompress("compress", body.clone(), MAX).is_err());
+        assert!(decompress("gzip", body, MAX).is_err());
+    }
+
+    #[test]
+    fn decompress_bomb() {
+        // 64 MiB of zeros compresses to a few KB
+        let body = vec![0u8; 64 * 1024 * 1024];
+        for encoding in [Encoding::Brotli, Encoding::Zstd, Encoding::Gzip] {
+            let bomb = compress(encoding, &body).unwrap();
+            assert!(bomb.len() < 100 * 1024);
+            let err = decompress(encoding.as_str(), bomb, MAX).unwrap_err();
+            assert!(err.is::<BodyTooLarge>());
+        }
+        let data = compress(Encoding::Gzip, &[0u8; 1024]).unwrap();
+        assert_eq!(decompress("gzip", data.clone(), 1024).unwrap().len(), 1024);
+        assert!(decompress("gzip", data, 1023)
+            .unwrap_err()
+            .is::<BodyTooLarge>());
+    }
+}
@@ -0,0 +1,247 @@
+use anyhow::{anyhow, Result};
+use hyper::http::{header, Response, StatusCode};
+use hyper::Body;
+use moss_runtime::pool::PoolError;
+use moss_runtime::worker::ErrorKind;
+use std::collections::HashMap;
+
+/// DEFAULT_PAGE is the html page of errors without custom page
+const DEFAULT_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>{{status}}</title></head>
+<body>
+<h1>{{status}}</h1>
+<p>{{message}}</p>
+<p>request id: {{request_id}}</p>
+</body>
+</html>
+"#;
+
+/// ErrorCode is the category of errors responded by gateway,
+/// details of errors are only logged and never sent to clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    RouteMismatch,
+    MethodNotAllowed,
+    BadRequest,
+    PayloadTooLarge,
+    PoolExhausted,
+    Trap,
+    Timeout,
+    LimitExceeded,
+    Internal,
+}
+
+impl ErrorCode {
+    const CODES: [ErrorCode; 9] = [
+        ErrorCode::RouteMismatch,
+        ErrorCode::MethodNotAllowed,
+        ErrorCode::BadRequest,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::PoolExhausted,
+        ErrorCode::Trap,
+        ErrorCode::Timeout,
+        ErrorCode::LimitExceeded,
+        ErrorCode::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::RouteMismatch => "route_mismatch",
+            ErrorCode::MethodNotAllowed => "method_not_allowed",
+            ErrorCode::BadRequest => "bad_request",
+            ErrorCode::PayloadTooLarge => "payload_too_large",
+            ErrorCode::PoolExhausted => "pool_exhausted",
+            ErrorCode::Trap => "trap",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::LimitExceeded => "limit_exceeded",
+            ErrorCode::Internal => "internal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::CODES.into_iter().find(|code| code.as_str() == name)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::RouteMismatch => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::PoolExhausted => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Trap | ErrorCode::LimitExceeded | ErrorCode::Internal => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// message is the client facing description of error
+    pub fn message(&self) -> &'static str {
+        match self {
+            ErrorCode::RouteMismatch => "no route matches the request",
+            ErrorCode::MethodNotAllowed => "method is not allowed by the route",
+            ErrorCode::BadRequest => "request body can not be decoded",
+            ErrorCode::PayloadTooLarge => "request body is too large",
+            ErrorCode::PoolExhausted => "no worker is available for the function",
+            ErrorCode::Trap => "function execution failed",
+            ErrorCode::Timeout => "function execution timed out",
+            ErrorCode::LimitExceeded => "function execution exceeded resource limits",
+            ErrorCode::Internal => "internal server error",
+        }
+    }
+}
+
+impl From<ErrorKind> for ErrorCode {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::Trap => ErrorCode::Trap,
+            ErrorKind::Timeout => ErrorCode::Timeout,
+            ErrorKind::LimitExceeded => ErrorCode::LimitExceeded,
+        }
+    }
+}
+
+impl From<&PoolError> for ErrorCode {
+    fn from(err: &PoolError) -> Self {
+        match err {
+            PoolError::Timeout(_) => ErrorCode::PoolExhausted,
+            _ => ErrorCode::Internal,
+        }
+    }
+}
+
+/// accepts_html returns whether client prefers html to json by Accept header, json is the default
+fn accepts_html(accept: &str) -> bool {
+    let (mut html, mut json) = (0.0, 0.0);
+    for item in accept.split(',') {
+        let mut parts = item.split(';');
+        let mime = parts.next().unwrap_or_default().trim().to_lowercase();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match mime.as_str() {
+            "text/html" => html = quality,
+            "application/json" => json = quality,
+            "text/*" if html == 0.0 => html = quality,
+            "application/*" if json == 0.0 => json = quality,
+            "*/*" => {
+                html = if html == 0.0 { quality } else { html };
+                json = if json == 0.0 { quality } else { json };
+            }
+            _ => {}
+        }
+    }
+    html > json
+}
+
+/// ErrorPages creates error responses of a function, with its custom html pages
+#[derive(Debug, Default)]
+pub struct ErrorPages {
+    pages: HashMap<ErrorCode, String>,
+}
+
+impl ErrorPages {
+    /// load reads custom pages of pairs of error code and page file
+    pub fn load(pages: &[(String, String)]) -> Result<Self> {
+        let mut loaded = HashMap::new();
+        for (name, file) in pages {
+            let code =
+                ErrorCode::from_name(name).ok_or_else(|| anyhow!("unknown error code '{name}'"))?;
+            let page = std::fs::read_to_string(file)
+                .map_err(|e| anyhow!("read error page {file} failed: {e}"))?;
+            loaded.insert(code, page);
+        }
+        Ok(Self { pages: loaded })
+    }
+
+    /// response creates error response in json or html by Accept header of request.
+    /// Custom pages can use {{status}}, {{code}}, {{message}} and {{request_id}} placeholders.
+    pub fn response(&self, code: ErrorCode, accept: &str, req_id: u64) -> Response<Body> {
+        let status = code.status();
+        let (content_type, body) = if accepts_html(accept) {
+            let page = self
+                .pages
+                .get(&code)
+                .map_or(DEFAULT_PAGE, |page| page.as_str());
+            let body = page
+                .replace("{{status}}", status.as_str())
+                .replace("{{code}}", code.as_str())
+                .replace("{{message}}", code.message())
+                .replace("{{request_id}}", &req_id.to_string());
+            ("text/html; charset=utf-8", body)
+        } else {
+            // code and message are fixed strings without characters to escape
+            let body = format!(
+                r#"{{"error":{{"status":{},"code":"{}","message":"{}","request_id":"{req_id}"}}}}"#,
+                status.as_u16(),
+                code.as_str(),
+                code.message()
+            );
+            ("application/json", body)
+        };
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_accept() {
+        assert!(accepts_html(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        ));
+        assert!(accepts_html("text/*"));
+        assert!(!accepts_html("application/json, text/html;q=0.5"));
+        assert!(!accepts_html("*/*"));
+        assert!(!accepts_html(""));
+        assert!(!accepts_html("text/html;q=0"));
+    }
+
+    #[tokio::test]
+    async fn error_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("500.html");
+        std::fs::write(&file, "<p>{{code}} {{status}} #{{request_id}}</p>").unwrap();
+        let pages =
+            ErrorPages::load(&[("trap".to_string(), file.to_str().unwrap().to_string())]).unwrap();
+        let body = |resp: Response<Body>| async move {
+            let data = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+            String::from_utf8(data.to_vec()).unwrap()
+        };
+
+        let resp = pages.response(ErrorCode::Trap, "text/html", 7);
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(body(resp).await, "<p>trap 500 #7</p>");
+
+        let resp = pages.response(ErrorCode::Timeout, "application/json", 8);
+        assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(
+            body(resp).await,
+            r#"{"error":{"status":504,"code":"timeout","message":"function execution timed out","request_id":"8"}}"#
+        );
+
+        let resp = pages.response(ErrorCode::RouteMismatch, "text/html", 9);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert!(body(resp)
+            .await
+            .contains("<p>no route matches the request</p>"));
+
+        let unknown = [("oops".to_string(), "404.html".to_string())];
+        assert!(ErrorPages::load(&unknown).is_err());
+        let missing = [("trap".to_string(), "missing.html".to_string())];
+        assert!(ErrorPages::load(&missing).is_err());
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{bundle, embed};
-use clap::Args;
-use moss_lib::metadata::{Metadata, DEFAULT_METADATA_FILE};
+use crate::{bundle, embed, tls};
+use clap::{Args, Subcommand};
+use moss_host_call::fetch_impl::{self, ClientConfig};
+use moss_lib::credential::{CredentialStore, DEFAULT_PROFILE};
+use moss_lib::metadata::{Metadata, MetadataEnv, DEFAULT_METADATA_FILE};
 use moss_runtime::compiler;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tracing::{debug, debug_span, error, info, Instrument};
 
 #[derive(Args, Debug)]
@@ -150,6 +152,33 @@ pub struct Serve {
     /// The port to listen on
     #[clap(long, default_value("127.0.0.1:8678"))]
     pub addr: Option<SocketAddr>,
+    /// Path to expose Prometheus metrics, empty to disable
+    #[clap(long, default_value("/metrics"))]
+    pub metrics_path: String,
+    /// Serve HTTPS, with self-signed certificate of localhost if --tls-cert and --tls-key are not set
+    #[clap(long)]
+    pub tls: bool,
+    /// Path of PEM encoded certificate chain to serve HTTPS
+    #[clap(long, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+    /// Path of PEM encoded private key to serve HTTPS
+    #[clap(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+    /// Proxy url of outbound fetch requests, system proxy settings are used if not set
+    #[clap(long)]
+    pub fetch_proxy: Option<String>,
+    /// User-agent of outbound fetch requests not setting it
+    #[clap(long)]
+    pub fetch_user_agent: Option<String>,
+    /// Max idle connections kept alive for each host of outbound fetch requests
+    #[clap(long, default_value_t = fetch_impl::DEFAULT_MAX_IDLE_PER_HOST)]
+    pub fetch_max_idle: usize,
+    /// Max outbound requests of a guest fetch-all call in flight at once
+    #[clap(long, default_value_t = fetch_impl::DEFAULT_FETCH_ALL_CONCURRENCY)]
+    pub fetch_concurrency: usize,
+    /// Seconds to wait in-flight requests finished after Ctrl-C
+    #[clap(long, default_value_t = moss_lib::shutdown::DEFAULT_TIMEOUT)]
+    pub shutdown_timeout: u64,
 }
 
 impl Serve {
@@ -160,40 +189,73 @@ impl Serve {
             Metadata::from_file(DEFAULT_METADATA_FILE).expect("Project metadata.toml not found");
         debug!("Metadata: {meta:?}");
 
-        let output = meta.get_output();
-        if !Path::new(&output).exists() {
-            panic!("Component {} not found, please build first", &output);
-        }
-        info!("Serve component: {}", &output);
-
         if meta.is_wasi() {
             info!("Enable wasm32-wasi");
         }
 
-        crate::server::start(self.addr.unwrap(), meta)
-            .instrument(debug_span!("[Http]"))
-            .await;
+        // functions of other projects are served together, their own functions table is ignored
+        let functions = meta.get_functions();
+        let mut metas = vec![meta];
+        for path in functions {
+            let function = Metadata::from_dir(Path::new(&path))
+                .unwrap_or_else(|e| panic!("Function metadata.toml in {path} is invalid: {e}"));
+            debug!("Metadata: {function:?}");
+            metas.push(function);
+        }
+        for meta in &metas {
+            let output = meta.get_output();
+            if !Path::new(&output).exists() {
+                panic!("Component {} not found, please build first", &output);
+            }
+            info!("Serve component: {}, function: {}", &output, meta.name);
+        }
+
+        // clients of outbound fetch are shared by all requests
+        let mut fetch_config = ClientConfig {
+            max_idle_per_host: self.fetch_max_idle,
+            fetch_all_concurrency: self.fetch_concurrency,
+            proxy: self.fetch_proxy.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Some(user_agent) = &self.fetch_user_agent {
+            fetch_config.user_agent = user_agent.clone();
+        }
+        fetch_impl::init_client_pool(fetch_config).expect("Invalid fetch proxy");
+
+        let tls = match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => {
+                Some(tls::load_config(cert, key).expect("Load TLS certificate failed"))
+            }
+            _ if self.tls => {
+                info!("Serve with self-signed certificate of localhost");
+                Some(tls::self_signed_config().unwrap())
+            }
+            _ => None,
+        };
+
+        crate::server::start(
+            self.addr.unwrap(),
+            metas,
+            self.metrics_path.clone(),
+            tls,
+            Duration::from_secs(self.shutdown_timeout),
+        )
+        .instrument(debug_span!("[Http]"))
+        .await;
     }
 }
 
 #[derive(Args, Debug)]
-pub struct Deploy {}
+pub struct Deploy {
+    #[clap(flatten)]
+    pub profile: ProfileArgs,
+}
 
 impl Deploy {
     pub async fn run(&self) {
         debug!("Deploy: {self:?}");
 
-        let env_file = moss_lib::metadata::get_metadata_env_file();
-        debug!("Env file: {:?}", env_file);
-        let env = match moss_lib::metadata::MetadataEnv::from_file(&env_file) {
-            Ok(env) => env,
-            Err(e) => {
-                debug!("Load env file failed: {:?}", e);
-                error!("You are not logged. Run 'moss-cli auth <your_token>'");
-                std::process::exit(1);
-            }
-        };
-        debug!("Env: {:?}", env);
+        let env = load_metadata_env(&self.profile).await;
 
         let meta =
             Metadata::from_file(DEFAULT_METADATA_FILE).expect("Project metadata.toml not found");
@@ -207,63 +269,751 @@ impl Deploy {
         info!("Find component: {}", &output);
 
         // generate an bundled file
-        let bundle_object =
-            bundle::build(&output, DEFAULT_METADATA_FILE, &meta.get_src_dir()).unwrap();
+        let static_dir = meta.get_static().map(|assets| assets.dir);
+        let bundle_object = bundle::build(
+            &output,
+            DEFAULT_METADATA_FILE,
+            &meta.get_src_dir(),
+            static_dir.as_deref(),
+        )
+        .unwrap();
 
         // upload bundle
         bundle::deploy(&env, &meta, &bundle_object).await.unwrap();
     }
 }
 
+/// ProfileArgs selects the credential profile saved by `moss-cli auth`
+#[derive(Args)]
+pub struct ProfileArgs {
+    /// The credential profile
+    #[clap(
+        long,
+        global = true,
+        env = "MOSS_PROFILE",
+        default_value(DEFAULT_PROFILE)
+    )]
+    pub profile: String,
+    /// The passphrase of encrypted credential, prompted if not set
+    #[clap(
+        long,
+        global = true,
+        env = "MOSS_CLI_PASSPHRASE",
+        hide_env_values = true
+    )]
+    pub passphrase: Option<String>,
+}
+
+impl std::fmt::Debug for ProfileArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfileArgs")
+            .field("profile", &self.profile)
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "******"))
+            .finish()
+    }
+}
+
+impl ProfileArgs {
+    /// get_passphrase returns passphrase from args, or prompts it if profile is encrypted
+    fn get_passphrase(&self, store: &CredentialStore) -> Option<String> {
+        if self.passphrase.is_some() {
+            return self.passphrase.clone();
+        }
+        if store.exists(&self.profile).unwrap_or(false)
+            && store.is_encrypted(&self.profile).unwrap_or(false)
+        {
+            return Some(prompt_passphrase());
+        }
+        None
+    }
+
+    /// save saves env to profile, keeps encrypted if profile is encrypted
+    fn save(&self, env: &MetadataEnv) {
+        let store = CredentialStore::default();
+        let passphrase = self.get_passphrase(&store);
+        if let Err(e) = store.save(&self.profile, env, passphrase.as_deref()) {
+            error!("Save credential failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn prompt_passphrase() -> String {
+    prompt_password("Passphrase: ")
+}
+
+fn prompt_password(prompt: &str) -> String {
+    match rpassword::prompt_password(prompt) {
+        Ok(password) => password,
+        Err(e) => {
+            error!("Read password failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// load_metadata_env loads the logged env of profile and logins again if it is expired,
+/// exit if not logged or login fails
+async fn load_metadata_env(args: &ProfileArgs) -> MetadataEnv {
+    let store = CredentialStore::default();
+    let passphrase = args.get_passphrase(&store);
+    let env = match store.load(&args.profile, passphrase.as_deref()) {
+        Ok(env) => env,
+        Err(e) => {
+            debug!("Load credential failed: {:?}", e);
+            error!(
+                "You are not logged in profile '{}'. Run 'moss-cli auth login'",
+                args.profile
+            );
+            std::process::exit(1);
+        }
+    };
+    debug!("Env: {:?}", env);
+    if !env.is_expired() {
+        return env;
+    }
+
+    // expired access token can not be exchanged again, login by key or password of profile
+    info!(
+        "Credential of profile '{}' is expired, login again",
+        args.profile
+    );
+    let env = match relogin(&env).await {
+        Ok(env) => env,
+        Err(e) => {
+            error!(
+                "Login again failed: {}. Run 'moss-cli auth login' to login",
+                e
+            );
+            std::process::exit(1);
+        }
+    };
+    // passphrase of encrypted profile is already known, save without prompting again
+    if let Err(e) = store.save(&args.profile, &env, passphrase.as_deref()) {
+        error!("Save credential failed: {}", e);
+        std::process::exit(1);
+    }
+    info!("Credential of profile '{}' is renewed", args.profile);
+    env
+}
+
+/// relogin logins by login key of env, or by its username with prompted password
+async fn relogin(env: &MetadataEnv) -> Result<MetadataEnv, Box<dyn std::error::Error>> {
+    let client =
+        moss_rpc_service::Client::new(env.api_host.clone(), String::from(""), String::from(""));
+    let response = if let Some((key, secret)) = parse_login_key(&env.login_key) {
+        client
+            .login_with_key(key.to_string(), secret.to_string())
+            .await?
+    } else if !env.username.is_empty() {
+        let password = prompt_password(&format!("Password of {}: ", env.username));
+        client.login(env.username.clone(), password).await?
+    } else {
+        return Err("profile is not logged by password or login key".into());
+    };
+    let mut new_env = to_metadata_env(&env.api_host, response)?;
+    new_env.login_key = env.login_key.clone();
+    new_env.username = env.username.clone();
+    Ok(new_env)
+}
+
+/// parse_login_key splits login key into access token and secret token
+fn parse_login_key(key: &str) -> Option<(&str, &str)> {
+    match key.trim().split_once(':') {
+        Some((key, secret)) if !key.is_empty() && !secret.is_empty() => Some((key, secret)),
+        _ => None,
+    }
+}
+
+/// create_metadata_env acquires secret of user token from cloud
+async fn create_metadata_env(
+    api_host: &str,
+    user_token: &str,
+    bootstrap_secret: &str,
+) -> Result<MetadataEnv, Box<dyn std::error::Error>> {
+    let client =
+        moss_rpc_service::Client::new(api_host.to_string(), String::from(""), String::from(""));
+    let response = client
+        .auth_token(user_token.to_string(), bootstrap_secret.to_string())
+        .await?;
+    to_metadata_env(api_host, response)
+}
+
+/// to_metadata_env converts token response to env, the access token is used as api key
+fn to_metadata_env(
+    api_host: &str,
+    response: moss_rpc_service::TokenResponse,
+) -> Result<MetadataEnv, Box<dyn std::error::Error>> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let env = MetadataEnv {
+        api_host: api_host.to_string(),
+        api_key: response.access_token,
+        api_secret: response.secret_token,
+        api_secret_expires: response.expiration.max(0) as u64,
+        created_at: now,
+        ..Default::default()
+    };
+    // if expiration > 0, need to check expiration
+    if env.is_expired() {
+        return Err("token expired".into());
+    }
+    Ok(env)
+}
+
+#[derive(Args, Debug)]
+pub struct Functions {
+    #[clap(flatten)]
+    pub profile: ProfileArgs,
+    #[clap(subcommand)]
+    pub cmd: FunctionsCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FunctionsCommands {
+    /// List deployed functions
+    Ls,
+    /// Show function information
+    Info {
+        /// The name of the function
+        name: String,
+    },
+    /// Delete a function
+    Rm {
+        /// The name of the function
+        name: String,
+    },
+    /// Disable a function, it stops serving requests
+    Disable {
+        /// The name of the function
+        name: String,
+    },
+    /// Enable a disabled function
+    Enable {
+        /// The name of the function
+        name: String,
+    },
+}
+
+impl Functions {
+    pub async fn run(&self) {
+        debug!("Functions: {self:?}");
+
+        let env = load_metadata_env(&self.profile).await;
+        let client = moss_rpc_service::Client::new(
+            env.api_host.clone(),
+            env.api_key.clone(),
+            env.api_secret.clone(),
+        );
+
+        match &self.cmd {
+            FunctionsCommands::Ls => match client.list_functions().await {
+                Ok(functions) => {
+                    println!(
+                        "{:<32} {:<8} {:<10} {:>10}  {:<20}",
+                        "NAME", "TYPE", "STATUS", "SIZE", "CREATED"
+                    );
+                    for function in functions {
+                        println!(
+                            "{:<32} {:<8} {:<10} {:>10}  {:<20}",
+                            function.name,
+                            function.function_type,
+                            function.status,
+                            function.storage_size,
+                            format_timestamp(function.created_at),
+                        );
+                    }
+                }
+                Err(e) => error!("List functions failed: {}", e),
+            },
+            FunctionsCommands::Info { name } => match client.get_function(name.clone()).await {
+                Ok(function) => print_function(&function),
+                Err(e) => error!("Get function failed: {}", e),
+            },
+            FunctionsCommands::Rm { name } => match client.delete_function(name.clone()).await {
+                Ok(_) => info!("Function {} deleted", name),
+                Err(e) => error!("Delete function failed: {}", e),
+            },
+            FunctionsCommands::Disable { name } => {
+                match client
+                    .set_function_status(name.clone(), "disabled".to_string())
+                    .await
+                {
+                    Ok(_) => info!("Function {} disabled", name),
+                    Err(e) => error!("Disable function failed: {}", e),
+                }
+            }
+            FunctionsCommands::Enable { name } => {
+                match client
+                    .set_function_status(name.clone(), "active".to_string())
+                    .await
+                {
+                    Ok(_) => info!("Function {} enabled", name),
+                    Err(e) => error!("Enable function failed: {}", e),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct Env {
+    /// The name of the function, default is the name in metadata.toml
+    #[clap(long)]
+    pub function: Option<String>,
+    #[clap(flatten)]
+    pub profile: ProfileArgs,
+    #[clap(subcommand)]
+    pub cmd: EnvCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnvCommands {
+    /// Set an environment variable
+    Set {
+        /// The name of the environment variable
+        name: String,
+        /// The value of the environment variable
+        value: String,
+        /// Encrypt the value at rest and hide it in listing
+        #[clap(long)]
+        secret: bool,
+    },
+    /// Unset an environment variable
+    Unset {
+        /// The name of the environment variable
+        name: String,
+    },
+    /// List environment variables
+    Ls,
+}
+
+impl Env {
+    pub async fn run(&self) {
+        debug!("Env: {self:?}");
+
+        let function = match &self.function {
+            Some(name) => name.clone(),
+            None => {
+                Metadata::from_file(DEFAULT_METADATA_FILE)
+                    .expect("Project metadata.toml not found, or use --function")
+                    .name
+            }
+        };
+
+        let env = load_metadata_env(&self.profile).await;
+        let client = moss_rpc_service::Client::new(
+            env.api_host.clone(),
+            env.api_key.clone(),
+            env.api_secret.clone(),
+        );
+
+        match &self.cmd {
+            EnvCommands::Set {
+                name,
+                value,
+                secret,
+            } => {
+                let req = moss_rpc_service::SetFunctionEnvRequest {
+                    function: function.clone(),
+                    name: name.clone(),
+                    value: value.clone(),
+                    secret: *secret,
+                };
+                match client.set_function_env(req).await {
+                    Ok(_) => info!("Env {} is set to function {}", name, function),
+                    Err(e) => error!("Set env failed: {}", e),
+                }
+            }
+            EnvCommands::Unset { name } => {
+                match client
+                    .unset_function_env(function.clone(), name.clone())
+                    .await
+                {
+                    Ok(_) => info!("Env {} is unset from function {}", name, function),
+                    Err(e) => error!("Unset env failed: {}", e),
+                }
+            }
+            EnvCommands::Ls => match client.list_function_env(function).await {
+                Ok(envs) => {
+                    for env in envs {
+                        println!("{}={}", env.name, env.value);
+                    }
+                }
+                Err(e) => error!("List env failed: {}", e),
+            },
+        }
+    }
+}
+
+/// LOGIN_KEY_USAGE is the token usage exchanged by 'moss-cli auth login --key'
+const LOGIN_KEY_USAGE: &str = "login-key";
+
+#[derive(Args, Debug)]
+pub struct Token {
+    #[clap(flatten)]
+    pub profile: ProfileArgs,
+    #[clap(subcommand)]
+    pub cmd: TokenCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokenCommands {
+    /// List tokens of current user
+    Ls,
+    /// Create a new token
+    Create {
+        /// The usage of the token
+        #[clap(long, default_value("moss-cli"))]
+        usage: String,
+        /// Days before the token expires, 0 means never expired
+        #[clap(long, default_value("0"))]
+        expires_days: i64,
+    },
+    /// Revoke a token
+    Revoke {
+        /// The access token
+        access_token: String,
+    },
+    /// Rotate the secret of a token
+    Rotate {
+        /// The access token
+        access_token: String,
+    },
+}
+
+impl Token {
+    pub async fn run(&self) {
+        debug!("Token: {self:?}");
+
+        let mut env = load_metadata_env(&self.profile).await;
+        let client = moss_rpc_service::Client::new(
+            env.api_host.clone(),
+            env.api_key.clone(),
+            env.api_secret.clone(),
+        );
+
+        match &self.cmd {
+            TokenCommands::Ls => match client.list_tokens().await {
+                Ok(tokens) => {
+                    println!(
+                        "{:<34} {:<10} {:<10} {:<20} {:<20}",
+                        "ACCESS_TOKEN", "USAGE", "STATUS", "CREATED", "EXPIRES"
+                    );
+                    for token in tokens {
+                        let current = if token.access_token == env.api_key {
+                            " *"
+                        } else {
+                            ""
+                        };
+                        println!(
+                            "{:<34} {:<10} {:<10} {:<20} {:<20}",
+                            token.access_token + current,
+                            token.usage,
+                            token.status,
+                            format_timestamp(token.created_at),
+                            format_expiration(token.expired_at),
+                        );
+                    }
+                }
+                Err(e) => error!("List tokens failed: {}", e),
+            },
+            TokenCommands::Create {
+                usage,
+                expires_days,
+            } => {
+                let expires_in = match expires_days.checked_mul(24 * 3600) {
+                    Some(expires_in) => expires_in,
+                    None => {
+                        error!("Create token failed: expires days {expires_days} is too large");
+                        return;
+                    }
+                };
+                match client.issue_token(usage.clone(), expires_in).await {
+                    Ok(token) => {
+                        println!("Access token:  {}", token.access_token);
+                        println!("Secret token:  {}", token.secret_token);
+                        println!("Expires:       {}", format_expiration(token.expired_at));
+                        if token.usage == LOGIN_KEY_USAGE {
+                            println!(
+                                "Login key:     {}:{}",
+                                token.access_token, token.secret_token
+                            );
+                        }
+                        info!("The secret token is shown only once, please keep it safe");
+                    }
+                    Err(e) => error!("Create token failed: {}", e),
+                }
+            }
+            TokenCommands::Revoke { access_token } => {
+                match client.revoke_token(access_token.clone()).await {
+                    Ok(_) => {
+                        info!("Token {} revoked", access_token);
+                        if access_token == &env.api_key {
+                            info!("Current token is revoked. Run 'moss-cli auth login'");
+                        }
+                    }
+                    Err(e) => error!("Revoke token failed: {}", e),
+                }
+            }
+            TokenCommands::Rotate { access_token } => {
+                match client.rotate_token(access_token.clone()).await {
+                    Ok(token) => {
+                        // current token is rotated, save new secret to env file
+                        if access_token == &env.api_key {
+                            env.api_secret = token.secret_token;
+                            self.profile.save(&env);
+                            info!("Token {} rotated, env file is updated", access_token);
+                        } else {
+                            println!("Secret token:  {}", token.secret_token);
+                            info!("Token {} rotated", access_token);
+                        }
+                    }
+                    Err(e) => error!("Rotate token failed: {}", e),
+                }
+            }
+        }
+    }
+}
+
+fn print_function(function: &moss_rpc_service::FunctionInfo) {
+    println!("Name:     {}", function.name);
+    println!("Uuid:     {}", function.uuid);
+    println!("Type:     {}", function.function_type);
+    println!("Status:   {}", function.status);
+    println!("Size:     {}", function.storage_size);
+    println!("Md5:      {}", function.storage_md5);
+    println!("Created:  {}", format_timestamp(function.created_at));
+    if let Some(resource) = &function.resource {
+        println!("Resource: {}", resource.name);
+        println!("  CPU time:     {} ms", resource.cpu_time);
+        println!("  Memory:       {} MB", resource.memory_usage);
+        println!("  Wall time:    {} ms", resource.wall_time);
+        println!("  Fetch counts: {}", resource.fetch_counts);
+        println!("  Fetch remote: {}", resource.fetch_remote_list);
+    }
+}
+
+fn format_timestamp(ts: i64) -> String {
+    match chrono::NaiveDateTime::from_timestamp_opt(ts, 0) {
+        Some(t) => t.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => ts.to_string(),
+    }
+}
+
+fn format_expiration(ts: i64) -> String {
+    if ts > 0 {
+        format_timestamp(ts)
+    } else {
+        "never".to_string()
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct Auth {
-    /// The user token
-    pub user_token: String,
+    #[clap(subcommand)]
+    pub cmd: Option<AuthCommands>,
+    /// The user token to login
+    pub user_token: Option<String>,
     /// Cloud api address
-    #[clap(long, default_value("http://127.0.0.1:8679"))]
+    #[clap(long, global = true, default_value("http://127.0.0.1:8679"))]
     pub cloud_api: Option<String>,
+    /// Bootstrap secret of the cloud to exchange user token, login does not need it
+    #[clap(
+        long,
+        global = true,
+        env = "MOSS_BOOTSTRAP_SECRET",
+        hide_env_values = true
+    )]
+    pub bootstrap_secret: Option<String>,
+    /// Encrypt the credential with passphrase
+    #[clap(long, global = true)]
+    pub encrypt: bool,
+    #[clap(flatten)]
+    pub profile: ProfileArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommands {
+    /// Login with username or email and password, or with a login key
+    Login {
+        /// The username or email, password is prompted
+        #[clap(long, required_unless_present("key"))]
+        username: Option<String>,
+        /// The login key '<access_token>:<secret_token>' issued by
+        /// 'moss-cli token create --usage login-key'
+        #[clap(long, env = "MOSS_LOGIN_KEY", hide_env_values = true)]
+        key: Option<String>,
+    },
+    /// Remove the credential of profile
+    Logout,
+    /// Show the credential status of profile
+    Status,
 }
 
 impl Auth {
     pub async fn run(&self) {
-        debug!("Auth: {self:?}");
+        debug!("Auth: {:?}, {:?}", self.cmd, self.profile);
+
+        match &self.cmd {
+            Some(AuthCommands::Login { username, key }) => {
+                self.login_with_password(username.as_deref(), key.as_deref())
+                    .await
+            }
+            Some(AuthCommands::Logout) => self.logout(),
+            Some(AuthCommands::Status) => self.status(),
+            None => self.login().await,
+        }
+    }
 
+    fn get_bootstrap_secret(&self) -> Option<&str> {
+        if self.bootstrap_secret.is_none() {
+            error!("Bootstrap secret is required, set --bootstrap-secret or MOSS_BOOTSTRAP_SECRET");
+        }
+        self.bootstrap_secret.as_deref()
+    }
+
+    fn get_cloud_api(&self) -> String {
         let cloud_api = self.cloud_api.as_ref().unwrap().clone();
         info!("Connect to {}", cloud_api);
+        cloud_api
+    }
 
-        let client =
-            moss_rpc_service::Client::new(cloud_api.clone(), String::from(""), String::from(""));
-        let response = match client.auth_token(self.user_token.clone()).await {
-            Ok(response) => response,
-            Err(e) => {
-                error!("Auth failed: {}", e);
+    fn create_client(cloud_api: &str) -> moss_rpc_service::Client {
+        moss_rpc_service::Client::new(cloud_api.to_string(), String::from(""), String::from(""))
+    }
+
+    async fn login(&self) {
+        let user_token = match &self.user_token {
+            Some(token) => token,
+            None => {
+                error!("User token is required. Run 'moss-cli auth login' to login with password");
                 return;
             }
         };
-        // if expiration > 0, need to check expiration
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        if response.expiration > 0 && now > response.expiration.try_into().unwrap() {
-            error!("Auth failed: token expired");
-            return;
+        let bootstrap_secret = match self.get_bootstrap_secret() {
+            Some(secret) => secret,
+            None => return,
+        };
+        let cloud_api = self.get_cloud_api();
+
+        match create_metadata_env(&cloud_api, user_token, bootstrap_secret).await {
+            Ok(env) => self.save_env(&env),
+            Err(e) => error!("Auth failed: {}", e),
         }
+    }
 
-        // get env file
-        let env_file = moss_lib::metadata::get_metadata_env_file();
-        debug!("Env file: {:?}", env_file);
-
-        // write env file
-        let env_data = moss_lib::metadata::MetadataEnv {
-            api_host: cloud_api,
-            api_key: self.user_token.clone(),
-            api_secret: response.secret_token,
-            api_secret_expires: response.expiration.try_into().unwrap(),
-            created_at: now,
+    async fn login_with_password(&self, username: Option<&str>, key: Option<&str>) {
+        let cloud_api = self.get_cloud_api();
+        let client = Self::create_client(&cloud_api);
+        let response = match key {
+            Some(key) => match parse_login_key(key) {
+                Some((key, secret)) => {
+                    client
+                        .login_with_key(key.to_string(), secret.to_string())
+                        .await
+                }
+                None => {
+                    error!("Login key must be '<access_token>:<secret_token>'");
+                    return;
+                }
+            },
+            None => {
+                let password = prompt_password("Password: ");
+                client
+                    .login(username.unwrap_or_default().to_string(), password)
+                    .await
+            }
         };
-        env_data.to_file(&env_file).unwrap();
+        match response.and_then(|response| to_metadata_env(&cloud_api, response)) {
+            Ok(mut env) => {
+                // kept in profile to login again when the token expires
+                env.login_key = key.unwrap_or_default().trim().to_string();
+                env.username = username.unwrap_or_default().to_string();
+                self.save_env(&env)
+            }
+            Err(e) => error!("Login failed: {}", e),
+        }
+    }
+
+    /// save_env saves env to profile, encrypted if passphrase is given or --encrypt is set
+    fn save_env(&self, env: &MetadataEnv) {
+        let store = CredentialStore::default();
+        let passphrase = match &self.profile.passphrase {
+            Some(passphrase) => Some(passphrase.clone()),
+            None if self.encrypt => Some(prompt_passphrase()),
+            None => None,
+        };
+        if let Err(e) = store.save(&self.profile.profile, env, passphrase.as_deref()) {
+            error!("Save credential failed: {}", e);
+            return;
+        }
+
+        info!("Auth success! Profile: {}", self.profile.profile);
+    }
 
-        info!("Auth success!");
+    fn logout(&self) {
+        let store = CredentialStore::default();
+        match store.remove(&self.profile.profile) {
+            Ok(true) => info!("Logout profile {}", self.profile.profile),
+            Ok(false) => info!("Profile {} is not logged", self.profile.profile),
+            Err(e) => error!("Logout failed: {}", e),
+        }
+    }
+
+    fn status(&self) {
+        let store = CredentialStore::default();
+        let profiles = match store.list() {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                error!("List profiles failed: {}", e);
+                return;
+            }
+        };
+        if !profiles.contains(&self.profile.profile) {
+            info!("Profile {} is not logged", self.profile.profile);
+        }
+        println!(
+            "{:<16} {:<32} {:<34} {:<10} {:<20}",
+            "PROFILE", "API_HOST", "ACCESS_TOKEN", "ENCRYPTED", "EXPIRES"
+        );
+        for profile in profiles {
+            let encrypted = store.is_encrypted(&profile).unwrap_or(false);
+            // encrypted profile is shown without decrypting unless it is selected
+            let env = if !encrypted {
+                store.load(&profile, None).ok()
+            } else if profile == self.profile.profile {
+                let passphrase = self.profile.get_passphrase(&store);
+                store.load(&profile, passphrase.as_deref()).ok()
+            } else {
+                None
+            };
+            let current = if profile == self.profile.profile {
+                " *"
+            } else {
+                ""
+            };
+            let (api_host, api_key, expires) = match env {
+                Some(env) => {
+                    let expires = if env.is_expired() {
+                        "expired".to_string()
+                    } else {
+                        format_expiration(env.api_secret_expires as i64)
+                    };
+                    (env.api_host, env.api_key, expires)
+                }
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            println!(
+                "{:<16} {:<32} {:<34} {:<10} {:<20}",
+                profile + current,
+                api_host,
+                api_key,
+                encrypted,
+                expires,
+            );
+        }
     }
 }
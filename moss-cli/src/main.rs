@@ -1,9 +1,14 @@
 use clap::Parser;
 
+mod assets;
 mod bundle;
+mod compress;
 mod embed;
+mod errors;
 mod flags;
+mod routes;
 mod server;
+mod tls;
 
 ///  moss-cli command line
 #[derive(Parser)]
@@ -23,11 +28,17 @@ enum MossCli {
     Deploy(flags::Deploy),
     /// Auth login to the cloud
     Auth(flags::Auth),
+    /// Functions manages deployed functions
+    Functions(flags::Functions),
+    /// Env manages environment variables of function
+    Env(flags::Env),
+    /// Token manages access tokens of current user
+    Token(flags::Token),
 }
 
 #[tokio::main]
 async fn main() {
-    moss_lib::tracing::init_tracing();
+    moss_lib::tracing::init_tracing("moss-cli");
 
     let args = MossCli::parse();
     match args {
@@ -36,5 +47,10 @@ async fn main() {
         MossCli::Serve(cmd) => cmd.run().await,
         MossCli::Deploy(cmd) => cmd.run().await,
         MossCli::Auth(cmd) => cmd.run().await,
+        MossCli::Functions(cmd) => cmd.run().await,
+        MossCli::Env(cmd) => cmd.run().await,
+        MossCli::Token(cmd) => cmd.run().await,
     }
+
+    moss_lib::tracing::shutdown_tracing();
 }
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use matchit::Router;
+use moss_lib::metadata::MetadataRoute;
+
+/// Route matches requests of a function by path, methods and host
+struct Route {
+    router: Router<()>,
+    methods: Vec<String>,
+    host: Option<String>,
+    strip_prefix: Option<String>,
+    function: usize,
+}
+
+impl Route {
+    fn match_host(&self, host: &str) -> bool {
+        let pattern = match &self.host {
+            Some(pattern) => pattern.to_lowercase(),
+            None => return true,
+        };
+        let host = strip_port(host).to_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{domain}")),
+            None => host == pattern,
+        }
+    }
+
+    fn match_method(&self, method: &str) -> bool {
+        self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    /// strip returns path passed to function
+    fn strip(&self, path: &str) -> String {
+        // prefix is only stripped at segment boundary, "/api" is not stripped from "/apis"
+        let rest = match &self.strip_prefix {
+            Some(prefix) => match path.strip_prefix(prefix.trim_end_matches('/')) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+                _ => path,
+            },
+            None => path,
+        };
+        if rest.starts_with('/') {
+            rest.to_string()
+        } else {
+            format!("/{rest}")
+        }
+    }
+}
+
+/// Matched is the result of routing a request
+#[derive(Debug, PartialEq, Eq)]
+pub enum Matched {
+    /// Found is the index of function and path passed to it
+    Found {
+        function: usize,
+        path: String,
+    },
+    /// MethodNotAllowed is returned if path matches but method does not, with allowed methods
+    MethodNotAllowed(Vec<String>),
+    NotFound,
+}
+
+/// Routes are routes of all functions, they are matched in order they are inserted
+#[derive(Default)]
+pub struct Routes {
+    routes: Vec<Route>,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// insert adds route of function by its index
+    pub fn insert(&mut self, route: &MetadataRoute, function: usize) -> Result<()> {
+        let mut router = Router::new();
+        router.insert(route.path.as_str(), ())?;
+        self.routes.push(Route {
+            router,
+            methods: route.methods.clone(),
+            host: route.host.clone(),
+            strip_prefix: route.strip_prefix.clone(),
+            function,
+        });
+        Ok(())
+    }
+
+    /// at returns the first route matching request
+    pub fn at(&self, method: &str, host: &str, path: &str) -> Matched {
+        let mut allowed: Vec<String> = vec![];
+        for route in &self.routes {
+            if route.router.at(path).is_err() || !route.match_host(host) {
+                continue;
+            }
+            if route.match_method(method) {
+                return Matched::Found {
+                    function: route.function,
+                    path: route.strip(path),
+                };
+            }
+            allowed.extend(route.methods.iter().map(|m| m.to_uppercase()));
+        }
+        if allowed.is_empty() {
+            return Matched::NotFound;
+        }
+        allowed.sort();
+        allowed.dedup();
+        Matched::MethodNotAllowed(allowed)
+    }
+}
+
+/// strip_port removes port of host header, brackets of IPv6 literal are kept
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return host.find(']').map_or(host, |end| &host[..=end]);
+    }
+    match host.split_once(':') {
+        Some((name, port)) if !port.contains(':') => name,
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(
+        path: &str,
+        methods: &[&str],
+        host: Option<&str>,
+        strip: Option<&str>,
+    ) -> MetadataRoute {
+        MetadataRoute {
+            path: path.to_string(),
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+            host: host.map(|h| h.to_string()),
+            strip_prefix: strip.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn match_routes() {
+        let mut routes = Routes::new();
+        routes
+            .insert(
+                &route("/api/*path", &["GET", "post"], None, Some("/api")),
+                0,
+            )
+            .unwrap();
+        routes
+            .insert(&route("/*path", &[], Some("*.example.com"), None), 1)
+            .unwrap();
+        routes
+            .insert(&route("/users/:id", &[], None, None), 2)
+            .unwrap();
+        routes
+            .insert(&route("/*path", &[], Some("strip.local"), Some("/api/")), 3)
+            .unwrap();
+        routes
+            .insert(&route("/api/*path", &["GET"], Some("api.local"), None), 4)
+            .unwrap();
+        assert!(routes.insert(&route("/bad/:", &[], None, None), 5).is_err());
+
+        let found = |function: usize, path: &str| Matched::Found {
+            function,
+            path: path.to_string(),
+        };
+        assert_eq!(
+            routes.at("GET", "localhost", "/api/users"),
+            found(0, "/users")
+        );
+        assert_eq!(
+            routes.at("POST", "localhost:8678", "/api/x"),
+            found(0, "/x")
+        );
+        assert_eq!(
+            routes.at("DELETE", "localhost", "/api/users"),
+            Matched::MethodNotAllowed(vec!["GET".to_string(), "POST".to_string()])
+        );
+        // allowed methods of all matching routes are merged
+        assert_eq!(
+            routes.at("DELETE", "api.local", "/api/users"),
+            Matched::MethodNotAllowed(vec!["GET".to_string(), "POST".to_string()])
+        );
+        // other methods fall through to routes matching all hosts and methods
+        assert_eq!(
+            routes.at("DELETE", "a.example.com", "/api/x"),
+            found(1, "/api/x")
+        );
+        assert_eq!(routes.at("GET", "A.Example.com:443", "/x"), found(1, "/x"));
+        assert_eq!(routes.at("GET", "example.com", "/x"), Matched::NotFound);
+        assert_eq!(
+            routes.at("GET", "localhost", "/users/1"),
+            found(2, "/users/1")
+        );
+        // prefix is stripped at segment boundary
+        assert_eq!(routes.at("GET", "strip.local", "/api"), found(3, "/"));
+        assert_eq!(routes.at("GET", "strip.local", "/apis"), found(3, "/apis"));
+    }
+
+    #[test]
+    fn strip_host_port() {
+        assert_eq!(strip_port("localhost:8678"), "localhost");
+        assert_eq!(strip_port("localhost"), "localhost");
+        assert_eq!(strip_port("[::1]:8080"), "[::1]");
+        assert_eq!(strip_port("[::1]"), "[::1]");
+        assert_eq!(strip_port("::1"), "::1");
+
+        let mut routes = Routes::new();
+        routes
+            .insert(&route("/*path", &[], Some("[::1]"), None), 0)
+            .unwrap();
+        let found = Matched::Found {
+            function: 0,
+            path: "/x".to_string(),
+        };
+        assert_eq!(routes.at("GET", "[::1]:8080", "/x"), found);
+        assert_eq!(routes.at("GET", "[::1]", "/x"), found);
+    }
+}
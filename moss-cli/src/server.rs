@@ -1,12 +1,22 @@
+use crate::assets::StaticAssets;
+use crate::compress;
+use crate::errors::{ErrorCode, ErrorPages};
+use crate::routes::{Matched, Routes};
 use futures::future::{self, Ready};
+use futures::{stream, StreamExt};
 use hyper::body::Body;
-use hyper::http::{Request, Response, StatusCode};
-use hyper::server::conn::AddrStream;
+use hyper::http::{header, HeaderValue, Method, Request, Response};
+use hyper::server::accept::{self, Accept};
+use hyper::server::conn::{AddrIncoming, AddrStream};
 use hyper::service::Service;
-use matchit::Router;
+use moss_host_call::http_impl::http_context::RequestContext;
 use moss_host_call::http_impl::http_handler::{Request as HostRequest, Response as HostResponse};
-use moss_lib::metadata::Metadata;
-use moss_runtime::pool;
+use moss_lib::metadata::{Metadata, MetadataCompression};
+use moss_lib::{metrics, shutdown};
+use moss_runtime::metrics::HTTP_REQUESTS;
+use moss_runtime::{pool, worker};
+use opentelemetry::global;
+use opentelemetry_http::HeaderExtractor;
 use std::convert::Infallible;
 use std::future::Future;
 use std::net::SocketAddr;
@@ -14,26 +24,111 @@ use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::time::Instant;
-use tracing::{error, error_span, info, info_span};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// FUNCTION_VERSION is the function version of local serve
+const FUNCTION_VERSION: &str = "dev";
+
+/// TLS_HANDSHAKE_CONCURRENCY is the max number of TLS handshakes in progress
+const TLS_HANDSHAKE_CONCURRENCY: usize = 64;
+
+/// TLS_HANDSHAKE_TIMEOUT is the max time of a TLS handshake, stalled handshakes are dropped
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TlsStream is an accepted TLS connection
+type TlsStream = tokio_rustls::server::TlsStream<AddrStream>;
+
+/// Function is a function of local serve with its own worker pool
+struct Function {
+    name: String,
+    worker_pool: Arc<pool::WorkerPool>,
+    compression: MetadataCompression,
+    error_pages: Arc<ErrorPages>,
+    /// assets are served before requests routed to function are passed to it
+    assets: Option<Arc<StaticAssets>>,
+    /// timeout is the max execution time of a request, None means no limit
+    timeout: Option<Duration>,
+}
+
+/// limit_duration converts limit in milliseconds to duration, zero means no limit
+fn limit_duration(millis: u64) -> Option<Duration> {
+    (millis > 0).then(|| Duration::from_millis(millis))
+}
+
+impl Function {
+    fn new(meta: Metadata) -> Self {
+        let limits = meta.get_limits();
+        let timeout = limit_duration(limits.timeout);
+        let options = worker::Options {
+            envs: meta.get_envs(),
+            fetch_cache: moss_runtime::create_fetch_cache(&meta.get_fetch_cache(), &meta.name)
+                .unwrap(),
+            timeout,
+        };
+        let error_pages = ErrorPages::load(&meta.get_error_pages())
+            .unwrap_or_else(|e| panic!("Invalid error pages of {}: {e}", meta.name));
+        let worker_pool = pool::create_with_options(
+            &meta.get_output(),
+            options,
+            limit_duration(limits.pool_wait_timeout),
+        )
+        .unwrap();
+        Self {
+            worker_pool: Arc::new(worker_pool),
+            compression: meta.get_compression(),
+            error_pages: Arc::new(error_pages),
+            assets: meta.get_static().map(|c| Arc::new(StaticAssets::new(&c))),
+            timeout,
+            name: meta.name,
+        }
+    }
+}
 
 struct HttpService {
     req_id: Arc<AtomicU64>,
-    worker_pool: Arc<pool::WorkerPool>,
-    router: Arc<Router<i32>>,
+    functions: Arc<Vec<Function>>,
+    routes: Arc<Routes>,
+    metrics_path: Arc<String>,
 }
 
 impl HttpService {
-    fn new(meta: Metadata) -> Self {
-        let mut router = Router::new();
-        router.insert(meta.get_route_base(), 1).unwrap();
+    /// new creates service of functions, routes are matched in order of functions
+    fn new(metas: Vec<Metadata>, metrics_path: String) -> Self {
+        let mut routes = Routes::new();
+        let mut functions = vec![];
+        for (index, meta) in metas.into_iter().enumerate() {
+            for route in meta.get_routes() {
+                routes.insert(&route, index).unwrap_or_else(|e| {
+                    panic!("Invalid route {} of {}: {e}", route.path, meta.name)
+                });
+            }
+            functions.push(Function::new(meta));
+        }
 
         Self {
             req_id: Arc::new(AtomicU64::new(0)),
-            worker_pool: Arc::new(pool::create(&meta.get_output()).unwrap()),
-            router: Arc::new(router),
+            functions: Arc::new(functions),
+            routes: Arc::new(routes),
+            metrics_path: Arc::new(metrics_path),
         }
     }
+
+    /// create_request_context creates service of one connection
+    fn create_request_context(&self, remote_addr: SocketAddr, tls: bool) -> HttpRequestContext {
+        HttpRequestContext::new(
+            self.req_id.clone(),
+            self.functions.clone(),
+            self.routes.clone(),
+            self.metrics_path.clone(),
+            remote_addr,
+            tls,
+        )
+    }
 }
 
 impl<'addr> Service<&'addr AddrStream> for HttpService {
@@ -45,31 +140,66 @@ impl<'addr> Service<&'addr AddrStream> for HttpService {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, _addr: &'addr AddrStream) -> Self::Future {
-        future::ok(HttpRequestContext::new(
-            self.req_id.clone(),
-            self.worker_pool.clone(),
-            self.router.clone(),
-        ))
+    fn call(&mut self, addr: &'addr AddrStream) -> Self::Future {
+        future::ok(self.create_request_context(addr.remote_addr(), false))
+    }
+}
+
+impl<'conn> Service<&'conn TlsStream> for HttpService {
+    type Response = HttpRequestContext;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, conn: &'conn TlsStream) -> Self::Future {
+        future::ok(self.create_request_context(conn.get_ref().0.remote_addr(), true))
     }
 }
 
 struct HttpRequestContext {
     req_id: Arc<AtomicU64>,
-    worker_pool: Arc<pool::WorkerPool>,
-    router: Arc<Router<i32>>,
+    functions: Arc<Vec<Function>>,
+    routes: Arc<Routes>,
+    metrics_path: Arc<String>,
+    remote_addr: SocketAddr,
+    tls: bool,
 }
 
 impl HttpRequestContext {
     fn new(
         req_id: Arc<AtomicU64>,
-        worker_pool: Arc<pool::WorkerPool>,
-        router: Arc<Router<i32>>,
+        functions: Arc<Vec<Function>>,
+        routes: Arc<Routes>,
+        metrics_path: Arc<String>,
+        remote_addr: SocketAddr,
+        tls: bool,
     ) -> Self {
         Self {
             req_id,
-            worker_pool,
-            router,
+            functions,
+            routes,
+            metrics_path,
+            remote_addr,
+            tls,
+        }
+    }
+
+    /// create_context creates request context passed to guest,
+    /// deadline is unix milliseconds when execution timeout of function is reached
+    fn create_context(&self, req_id: u64, function: &Function) -> RequestContext {
+        let deadline = function.timeout.map_or(0, |timeout| {
+            (SystemTime::now().duration_since(UNIX_EPOCH).unwrap() + timeout).as_millis() as u64
+        });
+        RequestContext {
+            remote_addr: self.remote_addr.to_string(),
+            request_id: req_id.to_string(),
+            tls: self.tls,
+            function_name: function.name.clone(),
+            function_version: FUNCTION_VERSION.to_string(),
+            deadline,
         }
     }
 }
@@ -82,55 +212,133 @@ impl Service<Request<Body>> for HttpRequestContext {
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         Poll::Ready(Ok(()))
     }
-    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
-        let req_id = self.req_id.fetch_add(1, Ordering::SeqCst);
-        let worker_pool = self.worker_pool.clone();
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        // metrics path is served by host, not counted as function request
+        if !self.metrics_path.is_empty() && req.uri().path() == self.metrics_path.as_str() {
+            return Box::pin(async move { Ok(create_metrics_response()) });
+        }
 
-        // do route match
+        self.call_function(req)
+    }
+}
+
+impl HttpRequestContext {
+    /// call_function routes request to function and calls it by a worker
+    fn call_function(
+        &mut self,
+        mut req: Request<Body>,
+    ) -> Pin<Box<dyn Future<Output = Result<Response<Body>, hyper::Error>> + Send>> {
         let uri = req.uri().clone();
         let path = uri.path();
-        let matched = self.router.at(path);
-        if matched.is_err() {
-            return Box::pin(async move {
-                Ok(create_error_response(
-                    StatusCode::NOT_FOUND,
-                    "route mismatch".to_string(),
-                ))
-            });
-        }
+        let accept = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        let req_id = self.req_id.fetch_add(1, Ordering::SeqCst);
+
+        // do route match by method, host and path
+        let host = match uri.host() {
+            Some(host) => host.to_string(),
+            None => req
+                .headers()
+                .get(header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string(),
+        };
+        let (function, route_path) = match self.routes.at(req.method().as_str(), &host, path) {
+            Matched::Found { function, path } => (&self.functions[function], path),
+            matched => {
+                // requests not matching any function use error pages of the first function
+                let pages = &self.functions[0].error_pages;
+                let resp = create_route_error_response(pages, matched, &accept, req_id);
+                HTTP_REQUESTS
+                    .with_label_values(&[resp.status().as_str()])
+                    .inc();
+                return Box::pin(async move { Ok(resp) });
+            }
+        };
+        let worker_pool = function.worker_pool.clone();
+        let error_pages = function.error_pages.clone();
+        let assets = function.assets.clone();
+        let context = self.create_context(req_id, function);
+        let compression = function.compression.clone();
+
+        // path prefix stripped by route is replaced in uri passed to function, query is kept
+        let url = if route_path == path {
+            uri.to_string()
+        } else {
+            match uri.query() {
+                Some(query) => format!("{route_path}?{query}"),
+                None => route_path,
+            }
+        };
+
+        // continue trace of the caller if traceparent header is sent
+        let span = info_span!(
+            "[Req]",
+            req_id = req_id,
+            function = function.name.as_str(),
+            method = req.method().as_str(),
+            uri = %uri
+        );
+        let parent = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(req.headers()))
+        });
+        span.set_parent(parent);
 
         let fut = async move {
             let start_time = Instant::now();
-            let mut worker = match worker_pool.get().await {
+            // static assets of function are served before request is passed to it
+            if let Some(assets) = &assets {
+                if let Some(resp) = assets.serve(&req).await {
+                    debug!(status = resp.status().as_u16(), "static asset");
+                    return Ok(resp);
+                }
+            }
+
+            let mut worker = match pool::get(&worker_pool).await {
                 Ok(w) => w,
                 Err(e) => {
-                    error_span!(
-                        "[Req]",
-                        req_id = req_id,
-                        method = req.method().as_str(),
-                        uri = req.uri().path()
-                    )
-                    .in_scope(|| {
-                        error!("get worker failed: {}", e);
-                    });
-                    error!(elapsed = ?start_time.elapsed(), "get worker failed: {}", e);
-                    return Ok(create_error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("get worker failed: {e}"),
-                    ));
+                    error!(elapsed = ?start_time.elapsed(), "get worker failed: {:?}", e);
+                    return Ok(error_pages.response(ErrorCode::from(&e), &accept, req_id));
                 }
             };
 
+            let mut req_headers = req.headers().clone();
+            let mut body_bytes = hyper::body::to_bytes(req.body_mut()).await?.to_vec();
+
+            // function receives decompressed body without content-encoding
+            if compression.request {
+                if let Some(encoding) = req_headers.remove(header::CONTENT_ENCODING) {
+                    let encoding = String::from_utf8_lossy(encoding.as_bytes()).to_string();
+                    let max_size = compression.max_body_size;
+                    body_bytes = match compress::decompress(&encoding, body_bytes, max_size) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            warn!("decompress request body failed: {e}");
+                            let code = if e.is::<compress::BodyTooLarge>() {
+                                ErrorCode::PayloadTooLarge
+                            } else {
+                                ErrorCode::BadRequest
+                            };
+                            return Ok(error_pages.response(code, &accept, req_id));
+                        }
+                    };
+                    req_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body_bytes.len()));
+                }
+            }
+
             // convert hyper request to host-call request
             let mut headers: Vec<(&str, &str)> = vec![];
-            let req_headers = req.headers().clone();
             req_headers.iter().for_each(|(k, v)| {
                 headers.push((k.as_str(), v.to_str().unwrap()));
             });
 
-            let url = req.uri().to_string();
             let method = req.method().clone();
-            let body_bytes = hyper::body::to_bytes(req.body_mut()).await?.to_vec();
 
             let host_req = HostRequest {
                 method: method.as_str(),
@@ -140,24 +348,28 @@ impl Service<Request<Body>> for HttpRequestContext {
             };
 
             // call worker execute
-            let host_resp: HostResponse = match worker.handle_request(host_req).await {
-                Ok(r) => r,
-                Err(e) => {
-                    error_span!(
-                        "[Req]",
-                        req_id = req_id,
-                        method = method.as_str(),
-                        uri = url.as_str()
-                    )
-                    .in_scope(|| {
-                        error!(elapsed = ?start_time.elapsed(),"execute failed: {e}");
-                    });
-                    return Ok(create_error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("execute failed: {e}"),
-                    ));
-                }
-            };
+            let mut host_resp: HostResponse =
+                match worker.handle_request_with_context(host_req, context).await {
+                    Ok(r) => r,
+                    Err(e) => {
+                        // full error with trap backtrace is only logged
+                        let code = ErrorCode::from(worker::error_kind(&e));
+                        error!(
+                            elapsed = ?start_time.elapsed(),
+                            code = code.as_str(),
+                            "execute failed: {e:?}"
+                        );
+                        return Ok(error_pages.response(code, &accept, req_id));
+                    }
+                };
+
+            if compression.response {
+                let accept_encoding = req_headers
+                    .get(header::ACCEPT_ENCODING)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                compress_response(&mut host_resp, &method, accept_encoding).await;
+            }
 
             // convert wasm response to hyper response
             let mut builder = Response::builder().status(host_resp.status);
@@ -166,46 +378,195 @@ impl Service<Request<Body>> for HttpRequestContext {
             }
             let resp = builder.body(Body::from(host_resp.body.unwrap())).unwrap();
 
-            info_span!(
-                "[Req]",
-                req_id = req_id,
-                method = method.as_str(),
-                uri = url.as_str(),
-                status = resp.status().as_u16()
-            )
-            .in_scope(|| {
-                info!(elapsed = ?start_time.elapsed(),  "request finished");
-            });
+            info!(
+                status = resp.status().as_u16(),
+                elapsed = ?start_time.elapsed(),
+                "request finished"
+            );
 
             Ok(resp)
         };
 
-        Box::pin(fut)
+        Box::pin(
+            async move {
+                let resp = fut.await?;
+                HTTP_REQUESTS
+                    .with_label_values(&[resp.status().as_str()])
+                    .inc();
+                Ok(resp)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// compress_response compresses body of compressible response by encoding client accepts
+async fn compress_response(resp: &mut HostResponse, method: &Method, accept_encoding: &str) {
+    let header_value = |name: &str| {
+        resp.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    // responses encoded by function are sent as is
+    if *method == Method::HEAD
+        || resp.status < 200
+        || resp.status == 204
+        || resp.status == 304
+        || header_value("content-encoding").is_some()
+        || !compress::is_compressible(header_value("content-type").unwrap_or_default())
+    {
+        return;
+    }
+    if resp.body.as_ref().map_or(0, |body| body.len()) < compress::MIN_SIZE {
+        return;
+    }
+    let encoding = match compress::negotiate(accept_encoding) {
+        Some(encoding) => encoding,
+        None => return,
+    };
+    let body = resp.body.take().unwrap_or_default();
+    let (body, result) = if body.len() >= compress::BLOCKING_SIZE {
+        // brotli and zstd of large bodies would stall other requests on the executor
+        tokio::task::spawn_blocking(move || {
+            let result = compress::compress(encoding, &body);
+            (body, result)
+        })
+        .await
+        .expect("compress response task panicked")
+    } else {
+        let result = compress::compress(encoding, &body);
+        (body, result)
+    };
+    match result {
+        Ok(data) => {
+            resp.body = Some(data);
+            resp.headers
+                .retain(|(k, _)| !k.eq_ignore_ascii_case("content-length"));
+            resp.headers.push((
+                "content-encoding".to_string(),
+                encoding.as_str().to_string(),
+            ));
+            compress::add_vary(&mut resp.headers);
+        }
+        Err(e) => {
+            warn!("compress response failed: {e}");
+            resp.body = Some(body);
+        }
+    }
+}
+
+/// create_route_error_response creates response of request not matching any route
+fn create_route_error_response(
+    pages: &ErrorPages,
+    matched: Matched,
+    accept: &str,
+    req_id: u64,
+) -> Response<Body> {
+    match matched {
+        Matched::MethodNotAllowed(allowed) => {
+            let mut resp = pages.response(ErrorCode::MethodNotAllowed, accept, req_id);
+            if let Ok(allow) = HeaderValue::from_str(&allowed.join(", ")) {
+                resp.headers_mut().insert(header::ALLOW, allow);
+            }
+            resp
+        }
+        _ => pages.response(ErrorCode::RouteMismatch, accept, req_id),
+    }
+}
+
+fn create_metrics_response() -> Response<Body> {
+    match metrics::render() {
+        Ok(body) => Response::builder()
+            .header(header::CONTENT_TYPE, metrics::CONTENT_TYPE)
+            .body(Body::from(body))
+            .unwrap(),
+        Err(e) => {
+            error!("render metrics failed: {e}");
+            ErrorPages::default().response(ErrorCode::Internal, "", 0)
+        }
     }
 }
 
-fn create_error_response(status: StatusCode, message: String) -> Response<Body> {
-    Response::builder()
-        .status(status)
-        .body(Body::from(message))
-        .unwrap()
+/// tls_incoming accepts TLS connections, handshakes are running concurrently with timeout
+fn tls_incoming(
+    mut incoming: AddrIncoming,
+    config: Arc<ServerConfig>,
+) -> impl Accept<Conn = TlsStream, Error = std::io::Error> {
+    let acceptor = TlsAcceptor::from(config);
+    let stream = stream::poll_fn(move |cx| Pin::new(&mut incoming).poll_accept(cx))
+        .map(move |conn| {
+            let acceptor = acceptor.clone();
+            async move {
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(conn?)).await {
+                    Ok(conn) => conn,
+                    Err(_) => Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "handshake timed out",
+                    )),
+                }
+            }
+        })
+        .buffer_unordered(TLS_HANDSHAKE_CONCURRENCY)
+        .filter_map(|conn| async move {
+            match conn {
+                Ok(conn) => Some(Ok(conn)),
+                Err(e) => {
+                    warn!("tls handshake failed: {e}");
+                    None
+                }
+            }
+        });
+    accept::from_stream(stream)
 }
 
-pub async fn start(addr: SocketAddr, meta: Metadata) {
-    let svc = HttpService::new(meta);
+/// start serves functions until shutdown, requests are routed to the first function matching them
+pub async fn start(
+    addr: SocketAddr,
+    metas: Vec<Metadata>,
+    metrics_path: String,
+    tls: Option<Arc<ServerConfig>>,
+    shutdown_timeout: Duration,
+) {
+    let svc = HttpService::new(metas, metrics_path);
+    let functions = svc.functions.clone();
+    let signal = shutdown::Signal::listen();
 
-    let server = match hyper::Server::try_bind(&addr) {
-        Ok(server) => server.serve(svc),
+    let incoming = match AddrIncoming::bind(&addr) {
+        Ok(incoming) => incoming,
         Err(e) => {
             error!("starting failed to bind: {e}");
             return;
         }
     };
 
-    info!("starting on {}", addr);
-
-    // Run this server for... forever!
-    if let Err(e) = server.await {
+    // run until shutdown signal, then wait in-flight requests finished.
+    // HTTP/2 is served by ALPN over TLS, or by prior knowledge over plain HTTP
+    let result = match tls {
+        Some(config) => {
+            info!("starting on https://{}", addr);
+            let server = hyper::Server::builder(tls_incoming(incoming, config))
+                .serve(svc)
+                .with_graceful_shutdown(signal.clone().recv());
+            shutdown::drain(server, signal, shutdown_timeout).await
+        }
+        None => {
+            info!("starting on http://{}", addr);
+            let server = hyper::Server::builder(incoming)
+                .serve(svc)
+                .with_graceful_shutdown(signal.clone().recv());
+            shutdown::drain(server, signal, shutdown_timeout).await
+        }
+    };
+    if let Some(Err(e)) = result {
         error!("starting error: {e}");
     }
+
+    for function in functions.iter() {
+        function.worker_pool.close();
+    }
+    if let Err(e) = moss_runtime::flush_kv().await {
+        error!("flush kv failed: {e:?}");
+    }
+    info!("shutdown");
 }
@@ -0,0 +1,80 @@
+use anyhow::{bail, Result};
+use rcgen::{CertificateParams, SanType};
+use rustls_pemfile::Item;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+
+/// load_config reads PEM encoded certificate chain and private key
+pub fn load_config(cert_path: &Path, key_path: &Path) -> Result<Arc<ServerConfig>> {
+    let mut reader = BufReader::new(File::open(cert_path)?);
+    let certs: Vec<Certificate> = rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certs.is_empty() {
+        bail!("no certificate found in {}", cert_path.display());
+    }
+    let key = read_private_key(key_path)?;
+    create_config(certs, key)
+}
+
+fn read_private_key(path: &Path) -> Result<PrivateKey> {
+    let mut reader = BufReader::new(File::open(path)?);
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(Item::PKCS8Key(key)) | Some(Item::RSAKey(key)) | Some(Item::ECKey(key)) => {
+                return Ok(PrivateKey(key))
+            }
+            Some(_) => continue,
+            None => bail!("no private key found in {}", path.display()),
+        }
+    }
+}
+
+/// self_signed_config generates certificate of localhost and 127.0.0.1 for local development
+pub fn self_signed_config() -> Result<Arc<ServerConfig>> {
+    let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+    params
+        .subject_alt_names
+        .push(SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    let cert = rcgen::Certificate::from_params(params)?;
+    create_config(
+        vec![Certificate(cert.serialize_der()?)],
+        PrivateKey(cert.serialize_private_key_der()),
+    )
+}
+
+fn create_config(certs: Vec<Certificate>, key: PrivateKey) -> Result<Arc<ServerConfig>> {
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    // negotiate HTTP/2 by ALPN, fallback to HTTP/1.1
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_pem_files() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = (dir.path().join("cert.pem"), dir.path().join("key.pem"));
+        std::fs::write(&cert_path, cert.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+
+        let config = load_config(&cert_path, &key_path).unwrap();
+        assert_eq!(config.alpn_protocols[0], b"h2");
+        assert!(load_config(&key_path, &key_path).is_err());
+        assert!(load_config(&cert_path, &cert_path).is_err());
+
+        assert!(self_signed_config().is_ok());
+    }
+}
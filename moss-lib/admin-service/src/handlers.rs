@@ -0,0 +1,274 @@
+use crate::types::{
+    ApiError, CreateResourceRequest, CreateUserRequest, FunctionResponse, GrantResourceRequest,
+    ResourceResponse, TokenResponse, UpdateResourceRequest, UsageResponse, UserResponse,
+    UserStatusRequest, UserUsage,
+};
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use moss_core_service::{function, resource, user, user_token};
+
+/// List all users
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    responses((status = 200, body = [UserResponse])),
+    security(("admin_token" = []))
+)]
+pub async fn list_users() -> Result<Json<Vec<UserResponse>>, ApiError> {
+    let users = user::list().await?;
+    Ok(Json(users.into_iter().map(UserResponse::from).collect()))
+}
+
+/// Get user by id
+#[utoipa::path(
+    get,
+    path = "/admin/users/{id}",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn get_user(Path(id): Path<i32>) -> Result<Json<UserResponse>, ApiError> {
+    let user = user::get(id).await?;
+    Ok(Json(user.into()))
+}
+
+/// Create user with password
+#[utoipa::path(
+    post,
+    path = "/admin/users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, body = UserResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn create_user(
+    Json(req): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserResponse>), ApiError> {
+    let user = user::create(&req.username, &req.email, &req.password).await?;
+    Ok((StatusCode::CREATED, Json(user.into())))
+}
+
+/// Enable or disable user, disabling revokes its tokens and disables its functions
+#[utoipa::path(
+    post,
+    path = "/admin/users/{id}/status",
+    params(("id" = i32, Path, description = "User id")),
+    request_body = UserStatusRequest,
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn set_user_status(
+    Path(id): Path<i32>,
+    Json(req): Json<UserStatusRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let user = user::set_status(id, &req.status).await?;
+    Ok(Json(user.into()))
+}
+
+/// List tokens of user, secret tokens are not returned
+#[utoipa::path(
+    get,
+    path = "/admin/users/{id}/tokens",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, body = [TokenResponse]),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn list_user_tokens(Path(id): Path<i32>) -> Result<Json<Vec<TokenResponse>>, ApiError> {
+    let user = user::get(id).await?;
+    let tokens = user_token::list(user.id).await?;
+    Ok(Json(tokens.into_iter().map(TokenResponse::from).collect()))
+}
+
+/// List functions of all users, excluding deleted ones
+#[utoipa::path(
+    get,
+    path = "/admin/functions",
+    responses((status = 200, body = [FunctionResponse])),
+    security(("admin_token" = []))
+)]
+pub async fn list_functions() -> Result<Json<Vec<FunctionResponse>>, ApiError> {
+    let functions = function::list_all().await?;
+    Ok(Json(
+        functions.into_iter().map(FunctionResponse::from).collect(),
+    ))
+}
+
+/// List resource profiles
+#[utoipa::path(
+    get,
+    path = "/admin/resources",
+    responses((status = 200, body = [ResourceResponse])),
+    security(("admin_token" = []))
+)]
+pub async fn list_resources() -> Result<Json<Vec<ResourceResponse>>, ApiError> {
+    let resources = resource::list().await?;
+    Ok(Json(
+        resources.into_iter().map(ResourceResponse::from).collect(),
+    ))
+}
+
+/// Create resource profile
+#[utoipa::path(
+    post,
+    path = "/admin/resources",
+    request_body = CreateResourceRequest,
+    responses(
+        (status = 201, body = ResourceResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 409, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn create_resource(
+    Json(req): Json<CreateResourceRequest>,
+) -> Result<(StatusCode, Json<ResourceResponse>), ApiError> {
+    let resource = resource::create(&req.name, &req.limits()).await?;
+    Ok((StatusCode::CREATED, Json(resource.into())))
+}
+
+/// Get resource profile by id
+#[utoipa::path(
+    get,
+    path = "/admin/resources/{id}",
+    params(("id" = i32, Path, description = "Resource profile id")),
+    responses(
+        (status = 200, body = ResourceResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn get_resource(Path(id): Path<i32>) -> Result<Json<ResourceResponse>, ApiError> {
+    let resource = resource::get(id).await?;
+    Ok(Json(resource.into()))
+}
+
+/// Update limits and status of resource profile
+#[utoipa::path(
+    put,
+    path = "/admin/resources/{id}",
+    params(("id" = i32, Path, description = "Resource profile id")),
+    request_body = UpdateResourceRequest,
+    responses(
+        (status = 200, body = ResourceResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn update_resource(
+    Path(id): Path<i32>,
+    Json(req): Json<UpdateResourceRequest>,
+) -> Result<Json<ResourceResponse>, ApiError> {
+    let resource = resource::update(id, &req.limits(), &req.status).await?;
+    Ok(Json(resource.into()))
+}
+
+/// Delete resource profile which is not used by any function
+#[utoipa::path(
+    delete,
+    path = "/admin/resources/{id}",
+    params(("id" = i32, Path, description = "Resource profile id")),
+    responses(
+        (status = 200, body = ResourceResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn delete_resource(Path(id): Path<i32>) -> Result<Json<ResourceResponse>, ApiError> {
+    let resource = resource::delete(id).await?;
+    Ok(Json(resource.into()))
+}
+
+/// List resource profiles granted to user, default profile is always granted
+#[utoipa::path(
+    get,
+    path = "/admin/users/{id}/resources",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, body = [ResourceResponse]),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn list_user_resources(
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<ResourceResponse>>, ApiError> {
+    let user = user::get(id).await?;
+    let resources = resource::list_granted(user.id).await?;
+    Ok(Json(
+        resources.into_iter().map(ResourceResponse::from).collect(),
+    ))
+}
+
+/// Grant resource profile to user
+#[utoipa::path(
+    post,
+    path = "/admin/users/{id}/resources",
+    params(("id" = i32, Path, description = "User id")),
+    request_body = GrantResourceRequest,
+    responses(
+        (status = 200, body = ResourceResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn grant_user_resource(
+    Path(id): Path<i32>,
+    Json(req): Json<GrantResourceRequest>,
+) -> Result<Json<ResourceResponse>, ApiError> {
+    let resource = resource::grant(id, req.resource_id).await?;
+    Ok(Json(resource.into()))
+}
+
+/// Revoke resource profile from user, deployed functions keep their profile
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{id}/resources/{resource_id}",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("resource_id" = i32, Path, description = "Resource profile id")
+    ),
+    responses(
+        (status = 200, body = ResourceResponse),
+        (status = 404, body = ErrorResponse)
+    ),
+    security(("admin_token" = []))
+)]
+pub async fn revoke_user_resource(
+    Path((id, resource_id)): Path<(i32, i32)>,
+) -> Result<Json<ResourceResponse>, ApiError> {
+    let resource = resource::revoke(id, resource_id).await?;
+    Ok(Json(resource.into()))
+}
+
+/// Get store usage of functions bundles
+#[utoipa::path(
+    get,
+    path = "/admin/store/usage",
+    responses((status = 200, body = UsageResponse)),
+    security(("admin_token" = []))
+)]
+pub async fn store_usage() -> Result<Json<UsageResponse>, ApiError> {
+    let usages = function::usage().await?;
+    let resp = UsageResponse {
+        functions: usages.iter().map(|u| u.functions).sum(),
+        storage_size: usages.iter().map(|u| u.storage_size).sum(),
+        users: usages.into_iter().map(UserUsage::from).collect(),
+    };
+    Ok(Json(resp))
+}
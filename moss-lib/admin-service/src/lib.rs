@@ -0,0 +1,5 @@
+mod handlers;
+mod server;
+mod types;
+
+pub use server::{router, start, ApiDoc, Config as AdminConfig};
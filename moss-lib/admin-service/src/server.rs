@@ -0,0 +1,373 @@
+use crate::handlers;
+use crate::types::{
+    ApiError, CreateResourceRequest, CreateUserRequest, ErrorResponse, FunctionResponse,
+    GrantResourceRequest, ResourceResponse, TokenResponse, UpdateResourceRequest, UsageResponse,
+    UserResponse, UserStatusRequest, UserUsage,
+};
+use axum::extract::State;
+use axum::http::{header, Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use moss_lib::metrics;
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// ADMIN_REQUESTS counts admin api requests by response status
+static ADMIN_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moss_admin_requests_total",
+        "Admin api requests by response status",
+        &["status"]
+    )
+    .unwrap()
+});
+
+/// Config is the admin api config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub addr: String,
+    /// token is the bearer token of admin api, empty token disables admin api
+    pub token: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:8680".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handlers::list_users,
+        handlers::create_user,
+        handlers::get_user,
+        handlers::set_user_status,
+        handlers::list_user_tokens,
+        handlers::list_user_resources,
+        handlers::grant_user_resource,
+        handlers::revoke_user_resource,
+        handlers::list_functions,
+        handlers::list_resources,
+        handlers::create_resource,
+        handlers::get_resource,
+        handlers::update_resource,
+        handlers::delete_resource,
+        handlers::store_usage,
+    ),
+    components(schemas(
+        ErrorResponse,
+        UserResponse,
+        CreateUserRequest,
+        UserStatusRequest,
+        TokenResponse,
+        FunctionResponse,
+        ResourceResponse,
+        CreateResourceRequest,
+        UpdateResourceRequest,
+        GrantResourceRequest,
+        UserUsage,
+        UsageResponse,
+    )),
+    modifiers(&SecurityAddon)
+)]
+pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_token",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// render_metrics returns metrics of server in prometheus text format
+async fn render_metrics() -> Result<([(header::HeaderName, &'static str); 1], String), ApiError> {
+    let body = metrics::render()
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, metrics::CONTENT_TYPE)], body))
+}
+
+/// track counts admin api requests, including unauthorized ones
+async fn track<B>(req: Request<B>, next: Next<B>) -> Response {
+    let resp = next.run(req).await;
+    ADMIN_REQUESTS
+        .with_label_values(&[resp.status().as_str()])
+        .inc();
+    resp
+}
+
+/// constant_time_eq compares tokens without leaking matched length by timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// auth checks bearer token of admin api requests
+async fn auth<B>(
+    State(token): State<Arc<String>>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !constant_time_eq(bearer.as_bytes(), token.as_bytes()) {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Invalid admin token".to_string(),
+        ));
+    }
+    Ok(next.run(req).await)
+}
+
+/// router creates admin api routes, all routes except openapi.json and metrics require token.
+/// Admin api is disabled when token is empty, metrics is always served.
+pub fn router(token: String) -> Router {
+    let metrics = Router::new().route("/metrics", get(render_metrics));
+    if token.is_empty() {
+        return metrics;
+    }
+    let api = Router::new()
+        .route(
+            "/admin/users",
+            get(handlers::list_users).post(handlers::create_user),
+        )
+        .route("/admin/users/:id", get(handlers::get_user))
+        .route("/admin/users/:id/status", post(handlers::set_user_status))
+        .route("/admin/users/:id/tokens", get(handlers::list_user_tokens))
+        .route(
+            "/admin/users/:id/resources",
+            get(handlers::list_user_resources).post(handlers::grant_user_resource),
+        )
+        .route(
+            "/admin/users/:id/resources/:resource_id",
+            delete(handlers::revoke_user_resource),
+        )
+        .route("/admin/functions", get(handlers::list_functions))
+        .route(
+            "/admin/resources",
+            get(handlers::list_resources).post(handlers::create_resource),
+        )
+        .route(
+            "/admin/resources/:id",
+            get(handlers::get_resource)
+                .put(handlers::update_resource)
+                .delete(handlers::delete_resource),
+        )
+        .route("/admin/store/usage", get(handlers::store_usage))
+        .route_layer(middleware::from_fn_with_state(Arc::new(token), auth))
+        .route_layer(middleware::from_fn(track));
+    metrics
+        .route("/admin/openapi.json", get(openapi))
+        .merge(api)
+}
+
+/// start serves admin api until shutdown future resolves
+pub async fn start(config: Config, shutdown: impl Future<Output = ()>) -> anyhow::Result<()> {
+    if config.token.is_empty() {
+        warn!("admin token is not configured, admin api is disabled and only metrics is served");
+    }
+    let addr: SocketAddr = config.addr.parse()?;
+    info!("AdminServer listening on {addr}");
+    axum::Server::bind(&addr)
+        .serve(router(config.token).into_make_service())
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use moss_core_service::{init_db, DbConfig, DB};
+    use moss_migration::{Migrator, MigratorTrait};
+    use tower::ServiceExt;
+
+    fn request(uri: &str, token: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn post_json(uri: &str, token: &str, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(resp: Response) -> serde_json::Value {
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn admin_api() {
+        let config = DbConfig {
+            driver: "sqlite".to_string(),
+            path: ":memory:".to_string(),
+            ..Default::default()
+        };
+        init_db(&config).await.unwrap();
+        Migrator::up(DB.get().unwrap(), None).await.unwrap();
+
+        let app = router("admin".to_string());
+
+        // openapi is public
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/openapi.json", ""))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let doc = body_json(resp).await;
+        assert!(doc["paths"]["/admin/users"].is_object());
+
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/users", "wrong"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/users", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await, serde_json::json!([]));
+
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/users/1", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let user = serde_json::json!({
+            "username": "moss",
+            "email": "moss@example.com",
+            "password": "password",
+        });
+        let resp = app
+            .clone()
+            .oneshot(post_json("/admin/users", "admin", user.clone()))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(body_json(resp).await["status"], "active");
+        let resp = app
+            .clone()
+            .oneshot(post_json("/admin/users", "admin", user))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let status = serde_json::json!({ "status": "disabled" });
+        let resp = app
+            .clone()
+            .oneshot(post_json("/admin/users/1/status", "admin", status))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["status"], "disabled");
+
+        // default resource profile is created by migration
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/resources", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await[0]["name"], "default");
+
+        let resource = serde_json::json!({
+            "name": "large",
+            "cpu_time": 2000,
+            "memory_usage": 256,
+            "wall_time": 60000,
+            "fetch_counts": 10,
+            "fetch_remote_list": "*",
+        });
+        let resp = app
+            .clone()
+            .oneshot(post_json("/admin/resources", "admin", resource))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let resource_id = body_json(resp).await["id"].clone();
+
+        let grant = serde_json::json!({ "resource_id": resource_id });
+        let resp = app
+            .clone()
+            .oneshot(post_json("/admin/users/1/resources", "admin", grant))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/users/1/resources", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(body_json(resp).await[0]["name"], "large");
+
+        let resp = app
+            .clone()
+            .oneshot(request("/admin/store/usage", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["functions"], 0);
+
+        // metrics is public
+        let resp = app.oneshot(request("/metrics", "")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("moss_admin_requests_total"));
+    }
+
+    #[tokio::test]
+    async fn metrics_without_token() {
+        let app = router(String::new());
+        let resp = app.clone().oneshot(request("/metrics", "")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = app.oneshot(request("/admin/users", "")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
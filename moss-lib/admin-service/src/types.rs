@@ -0,0 +1,250 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use moss_core_service::entity::{function_info, function_resource, user_info, user_token};
+use moss_core_service::function::FunctionUsage;
+use moss_core_service::resource::ResourceLimits;
+use moss_core_service::Error as CoreError;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// ApiError is the json error response of admin api
+#[derive(Debug)]
+pub struct ApiError(pub StatusCode, pub String);
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(ErrorResponse { error: self.1 })).into_response()
+    }
+}
+
+impl From<CoreError> for ApiError {
+    fn from(e: CoreError) -> Self {
+        let status = match e {
+            CoreError::RecordNotFound
+            | CoreError::UserNotFound
+            | CoreError::TokenNotFound
+            | CoreError::ResourceNotFound(_) => StatusCode::NOT_FOUND,
+            CoreError::RecordStatusInvalid(_) => StatusCode::BAD_REQUEST,
+            CoreError::RecordExists(_, _) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: i32,
+    pub uuid: String,
+    pub username: String,
+    pub nickname: String,
+    pub email: String,
+    pub status: String,
+    pub created_at: i64,
+}
+
+impl From<user_info::Model> for UserResponse {
+    fn from(model: user_info::Model) -> Self {
+        UserResponse {
+            id: model.id,
+            uuid: model.uuid,
+            username: model.username,
+            nickname: model.nickname,
+            email: model.email,
+            status: model.status,
+            created_at: model.created_at.timestamp(),
+        }
+    }
+}
+
+/// CreateUserRequest creates an active user with password
+#[derive(Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+/// UserStatusRequest changes user status, disabling revokes tokens and disables functions
+#[derive(Deserialize, ToSchema)]
+pub struct UserStatusRequest {
+    /// active or disabled
+    pub status: String,
+}
+
+/// TokenResponse is user token without secret
+#[derive(Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub id: i32,
+    pub user_id: i32,
+    pub access_token: String,
+    pub usage: String,
+    pub status: String,
+    pub created_at: i64,
+    pub expired_at: i64,
+}
+
+impl From<user_token::Model> for TokenResponse {
+    fn from(model: user_token::Model) -> Self {
+        TokenResponse {
+            id: model.id,
+            user_id: model.user_id,
+            access_token: model.access_token,
+            usage: model.from,
+            status: model.status,
+            created_at: model.created_at.timestamp(),
+            expired_at: model.expired_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FunctionResponse {
+    pub id: i32,
+    pub user_id: i32,
+    pub uuid: String,
+    pub name: String,
+    pub function_type: String,
+    pub resource: i32,
+    pub status: String,
+    pub storage_path: String,
+    pub storage_size: i32,
+    pub storage_md5: String,
+    pub created_at: i64,
+}
+
+impl From<function_info::Model> for FunctionResponse {
+    fn from(model: function_info::Model) -> Self {
+        FunctionResponse {
+            id: model.id,
+            user_id: model.user_id,
+            uuid: model.uuid,
+            name: model.name,
+            function_type: model.function_type,
+            resource: model.resource,
+            status: model.status,
+            storage_path: model.storage_path,
+            storage_size: model.storage_size,
+            storage_md5: model.storage_md5,
+            created_at: model.created_at.timestamp(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ResourceResponse {
+    pub id: i32,
+    pub name: String,
+    pub cpu_time: i32,
+    pub memory_usage: i32,
+    pub wall_time: i32,
+    pub fetch_counts: i32,
+    pub fetch_remote_list: String,
+    pub status: String,
+    pub created_at: i64,
+}
+
+impl From<function_resource::Model> for ResourceResponse {
+    fn from(model: function_resource::Model) -> Self {
+        ResourceResponse {
+            id: model.id,
+            name: model.name,
+            cpu_time: model.cpu_time,
+            memory_usage: model.memory_usage,
+            wall_time: model.wall_time,
+            fetch_counts: model.fetch_counts,
+            fetch_remote_list: model.fetch_remote_list,
+            status: model.status,
+            created_at: model.created_at.timestamp(),
+        }
+    }
+}
+
+/// CreateResourceRequest creates an active resource profile
+#[derive(Deserialize, ToSchema)]
+pub struct CreateResourceRequest {
+    pub name: String,
+    /// max cpu time in milliseconds
+    pub cpu_time: i32,
+    /// max memory in MB
+    pub memory_usage: i32,
+    /// max wall time in milliseconds
+    pub wall_time: i32,
+    /// max fetch requests of one invocation
+    pub fetch_counts: i32,
+    /// comma separated hosts allowed to fetch, * means all
+    pub fetch_remote_list: String,
+}
+
+impl CreateResourceRequest {
+    pub fn limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            cpu_time: self.cpu_time,
+            memory_usage: self.memory_usage,
+            wall_time: self.wall_time,
+            fetch_counts: self.fetch_counts,
+            fetch_remote_list: self.fetch_remote_list.clone(),
+        }
+    }
+}
+
+/// UpdateResourceRequest changes limits and status of resource profile
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateResourceRequest {
+    pub cpu_time: i32,
+    pub memory_usage: i32,
+    pub wall_time: i32,
+    pub fetch_counts: i32,
+    pub fetch_remote_list: String,
+    /// active or disabled, disabled profile can't be used by new deployments
+    pub status: String,
+}
+
+impl UpdateResourceRequest {
+    pub fn limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            cpu_time: self.cpu_time,
+            memory_usage: self.memory_usage,
+            wall_time: self.wall_time,
+            fetch_counts: self.fetch_counts,
+            fetch_remote_list: self.fetch_remote_list.clone(),
+        }
+    }
+}
+
+/// GrantResourceRequest grants resource profile to user
+#[derive(Deserialize, ToSchema)]
+pub struct GrantResourceRequest {
+    pub resource_id: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserUsage {
+    pub user_id: i32,
+    pub functions: u64,
+    pub storage_size: i64,
+}
+
+impl From<FunctionUsage> for UserUsage {
+    fn from(usage: FunctionUsage) -> Self {
+        UserUsage {
+            user_id: usage.user_id,
+            functions: usage.functions,
+            storage_size: usage.storage_size,
+        }
+    }
+}
+
+/// UsageResponse is the store usage of all users
+#[derive(Serialize, ToSchema)]
+pub struct UsageResponse {
+    pub functions: u64,
+    pub storage_size: i64,
+    pub users: Vec<UserUsage>,
+}
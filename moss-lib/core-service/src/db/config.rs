@@ -1,33 +1,94 @@
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 
+/// SQLITE_MEMORY is the sqlite path of in-memory database
+pub const SQLITE_MEMORY: &str = ":memory:";
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    /// driver is one of mysql, postgres and sqlite
+    pub driver: String,
     pub host: String,
     pub port: u16,
     pub user: String,
     pub password: String,
     pub database: String,
+    /// path is the sqlite database file, or ":memory:" for in-memory database
+    pub path: String,
     pub pool_size: u32,
+    /// auto_migrate applies pending migrations on startup
+    pub auto_migrate: bool,
 }
 
 impl Config {
-    pub fn url(&self) -> String {
-        format!(
-            "mysql://{}:{}@{}:{}/{}",
-            self.user, self.password, self.host, self.port, self.database
-        )
+    pub fn url(&self) -> Result<String> {
+        let url = match self.driver.as_str() {
+            "mysql" => format!(
+                "mysql://{}:{}@{}:{}/{}",
+                self.user, self.password, self.host, self.port, self.database
+            ),
+            "postgres" => format!(
+                "postgres://{}:{}@{}:{}/{}",
+                self.user, self.password, self.host, self.port, self.database
+            ),
+            "sqlite" if self.is_memory() => "sqlite::memory:".to_string(),
+            "sqlite" => format!("sqlite://{}?mode=rwc", self.path),
+            _ => bail!("unsupported database driver: {}", self.driver),
+        };
+        Ok(url)
+    }
+
+    /// is_memory checks if database is sqlite in-memory database
+    pub fn is_memory(&self) -> bool {
+        self.driver == "sqlite" && self.path == SQLITE_MEMORY
     }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            driver: "mysql".to_string(),
             host: "localhost".to_string(),
             port: 3306,
             user: "root".to_string(),
             password: "".to_string(),
             database: "moss-serverless".to_string(),
+            path: "moss-serverless.db".to_string(),
             pool_size: 10,
+            auto_migrate: true,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.url().unwrap(),
+            "mysql://root:@localhost:3306/moss-serverless"
+        );
+
+        config.driver = "postgres".to_string();
+        config.port = 5432;
+        assert_eq!(
+            config.url().unwrap(),
+            "postgres://root:@localhost:5432/moss-serverless"
+        );
+
+        config.driver = "sqlite".to_string();
+        assert_eq!(
+            config.url().unwrap(),
+            "sqlite://moss-serverless.db?mode=rwc"
+        );
+        config.path = SQLITE_MEMORY.to_string();
+        assert_eq!(config.url().unwrap(), "sqlite::memory:");
+
+        config.driver = "oracle".to_string();
+        assert!(config.url().is_err());
+    }
+}
@@ -9,19 +9,78 @@ pub static DB: OnceCell<DatabaseConnection> = OnceCell::new();
 
 /// init_db init database connection
 pub async fn init_db(db_config: &Config) -> Result<()> {
-    let url = db_config.url();
+    let url = db_config.url()?;
     debug!("connect to database: {url}");
 
     let mut opt = ConnectOptions::new(url);
-    opt.max_connections(db_config.pool_size)
-        .min_connections(1)
-        .connect_timeout(Duration::from_secs(10))
+    opt.connect_timeout(Duration::from_secs(10))
         .acquire_timeout(Duration::from_secs(10))
-        .idle_timeout(Duration::from_secs(10))
-        .max_lifetime(Duration::from_secs(10))
         .sqlx_logging(true);
+    if db_config.is_memory() {
+        // in-memory database is dropped with its connection, keep the only connection forever
+        let forever = Duration::from_secs(u32::MAX as u64);
+        opt.max_connections(1)
+            .min_connections(1)
+            .idle_timeout(forever)
+            .max_lifetime(forever);
+    } else {
+        opt.max_connections(db_config.pool_size)
+            .min_connections(1)
+            .idle_timeout(Duration::from_secs(10))
+            .max_lifetime(Duration::from_secs(10));
+    }
 
     let db = Database::connect(opt).await?;
     DB.set(db).unwrap();
     Ok(())
 }
+
+/// testing shares the in-memory database of tests, DB can be initialized only once
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use crate::db::config::SQLITE_MEMORY;
+    use moss_migration::{Migrator, MigratorTrait};
+    use once_cell::sync::Lazy;
+    use std::future::Future;
+    use tokio::runtime::Runtime;
+
+    /// RUNTIME runs all database tests, as connections are bound to the runtime creating them
+    static RUNTIME: Lazy<Runtime> = Lazy::new(|| Runtime::new().unwrap());
+
+    static INIT: tokio::sync::OnceCell<()> = tokio::sync::OnceCell::const_new();
+
+    /// run runs the test after the in-memory database is initialized and migrated.
+    /// Tests run concurrently on the same database, so records must not be shared by tests.
+    pub fn run<F: Future>(test: F) -> F::Output {
+        RUNTIME.block_on(async {
+            INIT.get_or_init(|| async {
+                let config = Config {
+                    driver: "sqlite".to_string(),
+                    path: SQLITE_MEMORY.to_string(),
+                    ..Default::default()
+                };
+                init_db(&config).await.unwrap();
+                Migrator::up(DB.get().unwrap(), None).await.unwrap();
+            })
+            .await;
+            test.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moss_migration::{Migrator, MigratorTrait};
+
+    #[test]
+    fn init_sqlite_memory() {
+        testing::run(async {
+            let pending = Migrator::get_pending_migrations(DB.get().unwrap())
+                .await
+                .unwrap();
+            assert!(pending.is_empty());
+        })
+    }
+}
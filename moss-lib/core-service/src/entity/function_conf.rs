@@ -6,8 +6,8 @@ use sea_orm::entity::prelude::*;
 #[sea_orm(table_name = "function_conf")]
 pub struct Model {
     #[sea_orm(primary_key)]
-    pub id: u32,
-    pub function_id: u32,
+    pub id: i32,
+    pub function_id: i32,
     pub name: String,
     pub value: String,
     pub conf_type: String,
@@ -20,7 +20,7 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(
         belongs_to = "super::function_info::Entity",
-        from = "Column::Id",
+        from = "Column::FunctionId",
         to = "super::function_info::Column::Id",
         on_update = "NoAction",
         on_delete = "NoAction"
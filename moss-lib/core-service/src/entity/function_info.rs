@@ -6,12 +6,12 @@ use sea_orm::entity::prelude::*;
 #[sea_orm(table_name = "function_info")]
 pub struct Model {
     #[sea_orm(primary_key)]
-    pub id: u32,
-    pub user_id: u32,
+    pub id: i32,
+    pub user_id: i32,
     #[sea_orm(unique)]
     pub uuid: String,
     pub name: String,
-    pub resource: u32,
+    pub resource: i32,
     pub function_type: String,
     pub storage_path: String,
     pub storage_size: i32,
@@ -6,7 +6,7 @@ use sea_orm::entity::prelude::*;
 #[sea_orm(table_name = "function_resource")]
 pub struct Model {
     #[sea_orm(primary_key)]
-    pub id: u32,
+    pub id: i32,
     #[sea_orm(unique)]
     pub name: String,
     pub cpu_time: i32,
@@ -23,6 +23,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::function_info::Entity")]
     FunctionInfo,
+    #[sea_orm(has_many = "super::user_resource::Entity")]
+    UserResource,
 }
 
 impl Related<super::function_info::Entity> for Entity {
@@ -31,4 +33,10 @@ impl Related<super::function_info::Entity> for Entity {
     }
 }
 
+impl Related<super::user_resource::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserResource.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
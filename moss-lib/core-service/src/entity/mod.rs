@@ -6,4 +6,5 @@ pub mod function_conf;
 pub mod function_info;
 pub mod function_resource;
 pub mod user_info;
+pub mod user_resource;
 pub mod user_token;
@@ -4,4 +4,5 @@ pub use super::function_conf::Entity as FunctionConf;
 pub use super::function_info::Entity as FunctionInfo;
 pub use super::function_resource::Entity as FunctionResource;
 pub use super::user_info::Entity as UserInfo;
+pub use super::user_resource::Entity as UserResource;
 pub use super::user_token::Entity as UserToken;
@@ -6,7 +6,7 @@ use sea_orm::entity::prelude::*;
 #[sea_orm(table_name = "user_info")]
 pub struct Model {
     #[sea_orm(primary_key)]
-    pub id: u32,
+    pub id: i32,
     pub uuid: String,
     pub username: String,
     pub nickname: String,
@@ -15,6 +15,8 @@ pub struct Model {
     pub avatar: String,
     pub phone: String,
     pub status: String,
+    /// password is argon2 hash in PHC string format, empty means password login is disabled
+    pub password: String,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -23,6 +25,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::function_info::Entity")]
     FunctionInfo,
+    #[sea_orm(has_many = "super::user_resource::Entity")]
+    UserResource,
     #[sea_orm(has_many = "super::user_token::Entity")]
     UserToken,
 }
@@ -33,6 +37,12 @@ impl Related<super::function_info::Entity> for Entity {
     }
 }
 
+impl Related<super::user_resource::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserResource.def()
+    }
+}
+
 impl Related<super::user_token::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserToken.def()
@@ -24,9 +24,6 @@ pub enum Error {
     /// TokenInactive means this token is disabled
     #[error("Token inactive")]
     TokenInactive,
-    /// TokenSecretIncorrect
-    #[error("Token secret incorrect")]
-    TokenSecretIncorrect,
 
     /// UserNotFound
     #[error("User not found")]
@@ -34,8 +31,25 @@ pub enum Error {
     /// UserInActive
     #[error("User inactive")]
     UserInactive,
+    /// LoginFailed means account, password or login key is incorrect
+    #[error("Login failed: account, password or key is incorrect")]
+    LoginFailed,
+
+    /// ResourceNotFound means resource profile is not found
+    #[error("Resource profile not found: {0}")]
+    ResourceNotFound(String),
+    /// ResourceNotEntitled means user is not granted to use the resource profile
+    #[error("Resource profile '{0}' is not granted to user")]
+    ResourceNotEntitled(String),
 
     /// StoreWriteError
     #[error("Store write error")]
     StoreWriteError(#[from] opendal::Error),
+
+    /// SecretKeyMissing means secret key is not configured
+    #[error("Secret key is not configured")]
+    SecretKeyMissing,
+    /// SecretInvalid means secret value can't be encrypted or decrypted
+    #[error("Secret value is invalid")]
+    SecretInvalid,
 }
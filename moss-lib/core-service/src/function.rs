@@ -8,9 +8,18 @@ use sea_orm::ActiveValue::Set;
 use sea_orm::ColumnTrait;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
 use sea_orm::TryIntoModel;
+use std::collections::BTreeMap;
 use tracing::debug;
 
+/// FUNCTION_STATUS_ACTIVE means function is serving requests
+pub const FUNCTION_STATUS_ACTIVE: &str = "active";
+/// FUNCTION_STATUS_DISABLED means function is kept but not serving requests
+pub const FUNCTION_STATUS_DISABLED: &str = "disabled";
+/// FUNCTION_STATUS_DELETED means function is soft deleted
+pub const FUNCTION_STATUS_DELETED: &str = "deleted";
+
 /// upsert_info
 #[tracing::instrument(skip(function_model))]
 pub async fn save(
@@ -27,7 +36,7 @@ pub async fn save(
     let obj = store.object(&object_name);
     obj.write(bytes::Bytes::from(bundle_content))
         .await
-        .map_err(|e| Error::StoreWriteError(e))?;
+        .map_err(Error::StoreWriteError)?;
     function_model.storage_path = format!("fs://{}", object_name);
     debug!("function storage path: {}", function_model.storage_path);
     // update file to db
@@ -41,7 +50,7 @@ async fn upsert_function(
 
     // get function info by name and user id
     let function_info = FunctionInfo::find()
-        .filter(function_info::Column::Name.contains(&function_model.name))
+        .filter(function_info::Column::Name.eq(&function_model.name))
         .filter(function_info::Column::UserId.eq(function_model.user_id))
         .one(db)
         .await?;
@@ -49,7 +58,7 @@ async fn upsert_function(
     let mut active_model: function_info::ActiveModel;
 
     // if record is found, set id
-    if function_info.is_some() {
+    if let Some(function_info) = function_info {
         debug!(
             "function is found, update it, id: {}, user_id: {}, name: {}",
             function_model.id, function_model.user_id, function_model.name,
@@ -58,7 +67,7 @@ async fn upsert_function(
             <function_info::Model as Into<function_info::ActiveModel>>::into(function_model)
                 .reset_all();
         active_model = active_model.reset_all();
-        active_model.id = Set(function_info.as_ref().unwrap().id);
+        active_model.id = Set(function_info.id);
         active_model.not_set(function_info::Column::CreatedAt);
         active_model.not_set(function_info::Column::Uuid);
     } else {
@@ -76,3 +85,103 @@ async fn upsert_function(
     let result = active_model.save(db).await.map_err(Error::DbInternal)?;
     Ok(result.try_into_model().unwrap())
 }
+
+/// list returns all functions of the user, excluding deleted ones
+pub async fn list(user_id: i32) -> Result<Vec<function_info::Model>, Error> {
+    let db = DB.get().unwrap();
+    let functions = FunctionInfo::find()
+        .filter(function_info::Column::UserId.eq(user_id))
+        .filter(function_info::Column::Status.ne(FUNCTION_STATUS_DELETED))
+        .order_by_asc(function_info::Column::Id)
+        .all(db)
+        .await?;
+    Ok(functions)
+}
+
+/// get returns the function of the user by name
+pub async fn get(user_id: i32, name: &str) -> Result<function_info::Model, Error> {
+    let db = DB.get().unwrap();
+    let function_info = FunctionInfo::find()
+        .filter(function_info::Column::UserId.eq(user_id))
+        .filter(function_info::Column::Name.eq(name))
+        .filter(function_info::Column::Status.ne(FUNCTION_STATUS_DELETED))
+        .one(db)
+        .await?;
+    function_info.ok_or(Error::RecordNotFound)
+}
+
+/// delete marks the function as deleted, the bundle is kept in store
+#[tracing::instrument]
+pub async fn delete(user_id: i32, name: &str) -> Result<function_info::Model, Error> {
+    let function_info = get(user_id, name).await?;
+    let mut active_model: function_info::ActiveModel = function_info.into();
+    active_model.status = Set(FUNCTION_STATUS_DELETED.to_string());
+    active_model.deleted_at = Set(chrono::Utc::now());
+    let result = active_model.update(DB.get().unwrap()).await?;
+    debug!(
+        "function is deleted, id: {}, name: {}",
+        result.id, result.name
+    );
+    Ok(result)
+}
+
+/// set_status changes the function status to active or disabled
+#[tracing::instrument]
+pub async fn set_status(
+    user_id: i32,
+    name: &str,
+    status: &str,
+) -> Result<function_info::Model, Error> {
+    if status != FUNCTION_STATUS_ACTIVE && status != FUNCTION_STATUS_DISABLED {
+        return Err(Error::RecordStatusInvalid(format!(
+            "unsupported function status '{status}'"
+        )));
+    }
+    let function_info = get(user_id, name).await?;
+    if function_info.status == status {
+        return Ok(function_info);
+    }
+    let mut active_model: function_info::ActiveModel = function_info.into();
+    active_model.status = Set(status.to_string());
+    let result = active_model.update(DB.get().unwrap()).await?;
+    debug!(
+        "function status is changed, id: {}, name: {}, status: {}",
+        result.id, result.name, result.status
+    );
+    Ok(result)
+}
+
+/// list_all returns functions of all users, excluding deleted ones
+pub async fn list_all() -> Result<Vec<function_info::Model>, Error> {
+    let db = DB.get().unwrap();
+    let functions = FunctionInfo::find()
+        .filter(function_info::Column::Status.ne(FUNCTION_STATUS_DELETED))
+        .order_by_asc(function_info::Column::Id)
+        .all(db)
+        .await?;
+    Ok(functions)
+}
+
+/// FunctionUsage is the functions count and bundle size of a user
+#[derive(Debug, Clone, Default)]
+pub struct FunctionUsage {
+    pub user_id: i32,
+    pub functions: u64,
+    pub storage_size: i64,
+}
+
+/// usage sums functions and bundle size of each user, excluding deleted ones
+pub async fn usage() -> Result<Vec<FunctionUsage>, Error> {
+    let mut usages: BTreeMap<i32, FunctionUsage> = BTreeMap::new();
+    for function_info in list_all().await? {
+        let usage = usages
+            .entry(function_info.user_id)
+            .or_insert_with(|| FunctionUsage {
+                user_id: function_info.user_id,
+                ..Default::default()
+            });
+        usage.functions += 1;
+        usage.storage_size += function_info.storage_size as i64;
+    }
+    Ok(usages.into_values().collect())
+}
@@ -0,0 +1,208 @@
+use crate::entity::function_conf;
+use crate::entity::prelude::FunctionConf;
+use crate::errors::Error;
+use crate::{secret, DB};
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::TryIntoModel;
+use tracing::debug;
+
+/// CONF_TYPE_ENV is plain environment variable
+pub const CONF_TYPE_ENV: &str = "env";
+/// CONF_TYPE_SECRET is environment variable encrypted at rest
+pub const CONF_TYPE_SECRET: &str = "secret";
+
+const CONF_STATUS_ACTIVE: &str = "active";
+const CONF_STATUS_DELETED: &str = "deleted";
+
+/// CONF_NAME_MAX_SIZE is the max size of the name column
+const CONF_NAME_MAX_SIZE: usize = 128;
+/// CONF_VALUE_MAX_SIZE is the max size of the value column
+const CONF_VALUE_MAX_SIZE: usize = 512;
+
+/// validate_name checks name is a valid environment variable name
+fn validate_name(name: &str) -> Result<(), Error> {
+    let valid = !name.is_empty()
+        && name.len() <= CONF_NAME_MAX_SIZE
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(Error::RecordStatusInvalid(format!(
+            "invalid environment variable name '{name}'"
+        )));
+    }
+    Ok(())
+}
+
+async fn find(function_id: i32, name: &str) -> Result<Option<function_conf::Model>, Error> {
+    let db = DB.get().unwrap();
+    let conf = FunctionConf::find()
+        .filter(function_conf::Column::FunctionId.eq(function_id))
+        .filter(function_conf::Column::Name.eq(name))
+        .filter(function_conf::Column::Status.eq(CONF_STATUS_ACTIVE))
+        .one(db)
+        .await?;
+    Ok(conf)
+}
+
+/// list returns active configs of the function, secret values are still encrypted
+pub async fn list(function_id: i32) -> Result<Vec<function_conf::Model>, Error> {
+    let db = DB.get().unwrap();
+    let confs = FunctionConf::find()
+        .filter(function_conf::Column::FunctionId.eq(function_id))
+        .filter(function_conf::Column::Status.eq(CONF_STATUS_ACTIVE))
+        .order_by_asc(function_conf::Column::Name)
+        .all(db)
+        .await?;
+    Ok(confs)
+}
+
+/// set creates or updates a config value of the function
+#[tracing::instrument(skip(value))]
+pub async fn set(
+    function_id: i32,
+    name: &str,
+    value: &str,
+    conf_type: &str,
+) -> Result<function_conf::Model, Error> {
+    validate_name(name)?;
+    let value = match conf_type {
+        CONF_TYPE_ENV => value.to_string(),
+        CONF_TYPE_SECRET => secret::encrypt(value)?,
+        _ => {
+            return Err(Error::RecordStatusInvalid(format!(
+                "unsupported config type '{conf_type}'"
+            )))
+        }
+    };
+    if value.len() > CONF_VALUE_MAX_SIZE {
+        return Err(Error::RecordStatusInvalid(format!(
+            "value of '{name}' is too large"
+        )));
+    }
+
+    let db = DB.get().unwrap();
+    let mut active_model: function_conf::ActiveModel = match find(function_id, name).await? {
+        Some(conf) => {
+            debug!(
+                "config is found, update it, id: {}, name: {}",
+                conf.id, name
+            );
+            conf.into()
+        }
+        None => {
+            debug!("config is not found, create it, name: {}", name);
+            let now = chrono::Utc::now();
+            function_conf::ActiveModel {
+                id: NotSet,
+                function_id: Set(function_id),
+                name: Set(name.to_string()),
+                status: Set(CONF_STATUS_ACTIVE.to_string()),
+                created_at: Set(now),
+                deleted_at: Set(now),
+                ..Default::default()
+            }
+        }
+    };
+    active_model.value = Set(value);
+    active_model.conf_type = Set(conf_type.to_string());
+    let result = active_model.save(db).await?;
+    Ok(result.try_into_model()?)
+}
+
+/// unset soft deletes a config value of the function
+#[tracing::instrument]
+pub async fn unset(function_id: i32, name: &str) -> Result<function_conf::Model, Error> {
+    let conf = find(function_id, name)
+        .await?
+        .ok_or(Error::RecordNotFound)?;
+    let mut active_model: function_conf::ActiveModel = conf.into();
+    active_model.status = Set(CONF_STATUS_DELETED.to_string());
+    active_model.deleted_at = Set(chrono::Utc::now());
+    let result = active_model.update(DB.get().unwrap()).await?;
+    Ok(result)
+}
+
+/// list_envs returns configs with decrypted secrets, as wasi environment variables
+/// of the deployed function set by worker options of runtime
+pub async fn list_envs(function_id: i32) -> Result<Vec<(String, String)>, Error> {
+    let mut envs = vec![];
+    for conf in list(function_id).await? {
+        let value = if conf.conf_type == CONF_TYPE_SECRET {
+            secret::decrypt(&conf.value)?
+        } else {
+            conf.value
+        };
+        envs.push((conf.name, value));
+    }
+    Ok(envs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::testing;
+    use crate::entity::function_info;
+    use crate::function::FUNCTION_STATUS_ACTIVE;
+
+    #[test]
+    fn list_decrypted_envs() {
+        testing::run(async {
+            secret::init_testing();
+            let user = crate::user::create("conf-user", "conf-user@example.com", "password")
+                .await
+                .unwrap();
+            let resource = crate::resource::get_by_name(crate::resource::DEFAULT_RESOURCE)
+                .await
+                .unwrap();
+            let now = chrono::Utc::now();
+            let function = function_info::ActiveModel {
+                id: NotSet,
+                user_id: Set(user.id),
+                uuid: Set(uuid::Uuid::new_v4().to_string()),
+                name: Set("conf-function".to_string()),
+                resource: Set(resource.id),
+                function_type: Set("http".to_string()),
+                storage_path: Set(String::new()),
+                storage_size: Set(0),
+                storage_md5: Set(String::new()),
+                status: Set(FUNCTION_STATUS_ACTIVE.to_string()),
+                created_at: Set(now),
+                deleted_at: Set(now),
+            }
+            .insert(DB.get().unwrap())
+            .await
+            .unwrap();
+
+            set(function.id, "MODE", "dev", CONF_TYPE_ENV)
+                .await
+                .unwrap();
+            set(function.id, "API_KEY", "secret-value", CONF_TYPE_SECRET)
+                .await
+                .unwrap();
+            assert!(set(function.id, "1KEY", "value", CONF_TYPE_ENV)
+                .await
+                .is_err());
+
+            // secret is encrypted at rest and decrypted for runtime
+            let confs = list(function.id).await.unwrap();
+            assert_eq!(confs[0].name, "API_KEY");
+            assert_ne!(confs[0].value, "secret-value");
+            let envs = list_envs(function.id).await.unwrap();
+            assert_eq!(
+                envs,
+                vec![
+                    ("API_KEY".to_string(), "secret-value".to_string()),
+                    ("MODE".to_string(), "dev".to_string()),
+                ]
+            );
+
+            unset(function.id, "MODE").await.unwrap();
+            assert_eq!(list_envs(function.id).await.unwrap().len(), 1);
+        })
+    }
+}
@@ -1,5 +1,8 @@
 pub mod entity;
 pub mod function;
+pub mod function_conf;
+pub mod resource;
+pub mod user;
 pub mod user_token;
 
 mod db;
@@ -8,8 +11,13 @@ pub use db::db::init_db;
 pub use db::db::DB;
 
 mod errors;
+pub use errors::Error;
 
 mod store;
 pub use store::config::Config as StoreConfig;
 pub use store::init_store;
 pub use store::STORE;
+
+mod secret;
+pub use secret::config::Config as SecretConfig;
+pub use secret::init_secret;
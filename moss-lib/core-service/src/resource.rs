@@ -0,0 +1,298 @@
+use crate::entity::prelude::{FunctionInfo, FunctionResource, UserResource};
+use crate::entity::{function_info, function_resource, user_resource};
+use crate::errors::Error;
+use crate::function::FUNCTION_STATUS_DELETED;
+use crate::DB;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use tracing::debug;
+
+/// DEFAULT_RESOURCE is the resource profile granted to all users
+pub const DEFAULT_RESOURCE: &str = "default";
+
+/// RESOURCE_STATUS_ACTIVE means resource profile can be used by new deployments
+pub const RESOURCE_STATUS_ACTIVE: &str = "active";
+/// RESOURCE_STATUS_DISABLED means resource profile is kept for deployed functions only
+pub const RESOURCE_STATUS_DISABLED: &str = "disabled";
+
+/// ResourceLimits is the limits of a resource profile
+#[derive(Debug, Clone)]
+pub struct ResourceLimits {
+    /// cpu_time is max cpu time in milliseconds
+    pub cpu_time: i32,
+    /// memory_usage is max memory in MB
+    pub memory_usage: i32,
+    /// wall_time is max wall time in milliseconds
+    pub wall_time: i32,
+    /// fetch_counts is max fetch requests of one invocation
+    pub fetch_counts: i32,
+    /// fetch_remote_list is comma separated hosts allowed to fetch, * means all
+    pub fetch_remote_list: String,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            cpu_time: 1000,
+            memory_usage: 128,
+            wall_time: 30000,
+            fetch_counts: 5,
+            fetch_remote_list: "*".to_string(),
+        }
+    }
+}
+
+impl ResourceLimits {
+    fn validate(&self) -> Result<(), Error> {
+        if self.cpu_time <= 0
+            || self.memory_usage <= 0
+            || self.wall_time <= 0
+            || self.fetch_counts < 0
+        {
+            return Err(Error::RecordStatusInvalid(
+                "resource limits must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// list returns all resource profiles
+pub async fn list() -> Result<Vec<function_resource::Model>, Error> {
+    let db = DB.get().unwrap();
+    let resources = FunctionResource::find()
+        .order_by_asc(function_resource::Column::Id)
+        .all(db)
+        .await?;
+    Ok(resources)
+}
+
+/// get returns the resource profile by id
+pub async fn get(id: i32) -> Result<function_resource::Model, Error> {
+    let db = DB.get().unwrap();
+    let resource = FunctionResource::find_by_id(id).one(db).await?;
+    resource.ok_or_else(|| Error::ResourceNotFound(id.to_string()))
+}
+
+/// get_by_name returns the resource profile by name
+pub async fn get_by_name(name: &str) -> Result<function_resource::Model, Error> {
+    let db = DB.get().unwrap();
+    let resource = FunctionResource::find()
+        .filter(function_resource::Column::Name.eq(name))
+        .one(db)
+        .await?;
+    resource.ok_or_else(|| Error::ResourceNotFound(name.to_string()))
+}
+
+/// create creates an active resource profile
+#[tracing::instrument]
+pub async fn create(
+    name: &str,
+    limits: &ResourceLimits,
+) -> Result<function_resource::Model, Error> {
+    if name.is_empty() || name.len() > 32 {
+        return Err(Error::RecordStatusInvalid(
+            "resource name must be 1 to 32 characters".to_string(),
+        ));
+    }
+    limits.validate()?;
+    if let Ok(resource) = get_by_name(name).await {
+        return Err(Error::RecordExists(
+            name.to_string(),
+            resource.id.to_string(),
+        ));
+    }
+
+    let now = chrono::Utc::now();
+    let active_model = function_resource::ActiveModel {
+        id: NotSet,
+        name: Set(name.to_string()),
+        cpu_time: Set(limits.cpu_time),
+        memory_usage: Set(limits.memory_usage),
+        wall_time: Set(limits.wall_time),
+        fetch_counts: Set(limits.fetch_counts),
+        fetch_remote_list: Set(limits.fetch_remote_list.clone()),
+        status: Set(RESOURCE_STATUS_ACTIVE.to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    let resource = active_model.insert(DB.get().unwrap()).await?;
+    debug!("resource is created, id: {}, name: {}", resource.id, name);
+    Ok(resource)
+}
+
+/// update changes limits and status of the resource profile
+#[tracing::instrument]
+pub async fn update(
+    id: i32,
+    limits: &ResourceLimits,
+    status: &str,
+) -> Result<function_resource::Model, Error> {
+    if status != RESOURCE_STATUS_ACTIVE && status != RESOURCE_STATUS_DISABLED {
+        return Err(Error::RecordStatusInvalid(format!(
+            "unsupported resource status '{status}'"
+        )));
+    }
+    limits.validate()?;
+    let resource = get(id).await?;
+    if resource.name == DEFAULT_RESOURCE && status != RESOURCE_STATUS_ACTIVE {
+        return Err(Error::RecordStatusInvalid(
+            "default resource can't be disabled".to_string(),
+        ));
+    }
+    let mut active_model: function_resource::ActiveModel = resource.into();
+    active_model.cpu_time = Set(limits.cpu_time);
+    active_model.memory_usage = Set(limits.memory_usage);
+    active_model.wall_time = Set(limits.wall_time);
+    active_model.fetch_counts = Set(limits.fetch_counts);
+    active_model.fetch_remote_list = Set(limits.fetch_remote_list.clone());
+    active_model.status = Set(status.to_string());
+    active_model.updated_at = Set(chrono::Utc::now());
+    let resource = active_model.update(DB.get().unwrap()).await?;
+    debug!("resource is updated, id: {}", resource.id);
+    Ok(resource)
+}
+
+/// delete removes the resource profile and its grants, it must not be used by any function
+#[tracing::instrument]
+pub async fn delete(id: i32) -> Result<function_resource::Model, Error> {
+    let resource = get(id).await?;
+    if resource.name == DEFAULT_RESOURCE {
+        return Err(Error::RecordStatusInvalid(
+            "default resource can't be deleted".to_string(),
+        ));
+    }
+    let db = DB.get().unwrap();
+    let functions = FunctionInfo::find()
+        .filter(function_info::Column::Resource.eq(id))
+        .filter(function_info::Column::Status.ne(FUNCTION_STATUS_DELETED))
+        .count(db)
+        .await?;
+    if functions > 0 {
+        return Err(Error::RecordStatusInvalid(format!(
+            "resource is used by {functions} functions, disable it instead"
+        )));
+    }
+    UserResource::delete_many()
+        .filter(user_resource::Column::ResourceId.eq(id))
+        .exec(db)
+        .await?;
+    resource.clone().delete(db).await?;
+    debug!("resource is deleted, id: {}", id);
+    Ok(resource)
+}
+
+/// list_granted returns resource profiles granted to the user, excluding default one
+pub async fn list_granted(user_id: i32) -> Result<Vec<function_resource::Model>, Error> {
+    let db = DB.get().unwrap();
+    let resources = FunctionResource::find()
+        .inner_join(UserResource)
+        .filter(user_resource::Column::UserId.eq(user_id))
+        .order_by_asc(function_resource::Column::Id)
+        .all(db)
+        .await?;
+    Ok(resources)
+}
+
+/// grant allows the user to deploy functions with the resource profile
+#[tracing::instrument]
+pub async fn grant(user_id: i32, resource_id: i32) -> Result<function_resource::Model, Error> {
+    crate::user::get(user_id).await?;
+    let resource = get(resource_id).await?;
+    if is_granted(user_id, &resource).await? {
+        return Ok(resource);
+    }
+    let active_model = user_resource::ActiveModel {
+        id: NotSet,
+        user_id: Set(user_id),
+        resource_id: Set(resource_id),
+        created_at: Set(chrono::Utc::now()),
+    };
+    active_model.insert(DB.get().unwrap()).await?;
+    debug!(
+        "resource is granted, user: {}, resource: {}",
+        user_id, resource_id
+    );
+    Ok(resource)
+}
+
+/// revoke disallows new deployments of the user with the resource profile,
+/// deployed functions keep their profile
+#[tracing::instrument]
+pub async fn revoke(user_id: i32, resource_id: i32) -> Result<function_resource::Model, Error> {
+    let resource = get(resource_id).await?;
+    UserResource::delete_many()
+        .filter(user_resource::Column::UserId.eq(user_id))
+        .filter(user_resource::Column::ResourceId.eq(resource_id))
+        .exec(DB.get().unwrap())
+        .await?;
+    debug!(
+        "resource is revoked, user: {}, resource: {}",
+        user_id, resource_id
+    );
+    Ok(resource)
+}
+
+async fn is_granted(user_id: i32, resource: &function_resource::Model) -> Result<bool, Error> {
+    if resource.name == DEFAULT_RESOURCE {
+        return Ok(true);
+    }
+    let grants = UserResource::find()
+        .filter(user_resource::Column::UserId.eq(user_id))
+        .filter(user_resource::Column::ResourceId.eq(resource.id))
+        .count(DB.get().unwrap())
+        .await?;
+    Ok(grants > 0)
+}
+
+/// resolve returns the resource profile requested by deployment,
+/// empty name means default one, the profile must be active and granted to user
+pub async fn resolve(user_id: i32, name: &str) -> Result<function_resource::Model, Error> {
+    let name = if name.is_empty() {
+        DEFAULT_RESOURCE
+    } else {
+        name
+    };
+    let resource = get_by_name(name).await?;
+    if resource.status != RESOURCE_STATUS_ACTIVE {
+        return Err(Error::RecordStatusInvalid(format!(
+            "resource '{name}' is disabled"
+        )));
+    }
+    if !is_granted(user_id, &resource).await? {
+        return Err(Error::ResourceNotEntitled(name.to_string()));
+    }
+    Ok(resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::testing;
+    use crate::user;
+
+    #[test]
+    fn grant_and_resolve() {
+        testing::run(async {
+            let user = user::create("resource-user", "resource-user@example.com", "password")
+                .await
+                .unwrap();
+
+            let default = resolve(user.id, "").await.unwrap();
+            assert_eq!(default.name, DEFAULT_RESOURCE);
+            let large = create("large", &ResourceLimits::default()).await.unwrap();
+            assert!(resolve(user.id, "large").await.is_err());
+
+            grant(user.id, large.id).await.unwrap();
+            assert_eq!(resolve(user.id, "large").await.unwrap().id, large.id);
+            assert_eq!(list_granted(user.id).await.unwrap().len(), 1);
+
+            revoke(user.id, large.id).await.unwrap();
+            assert!(resolve(user.id, "large").await.is_err());
+            assert!(delete(default.id).await.is_err());
+            delete(large.id).await.unwrap();
+        })
+    }
+}
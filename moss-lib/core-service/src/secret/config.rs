@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// key is base64 encoded 32 bytes key to encrypt secret values,
+    /// generate by `openssl rand -base64 32`
+    pub key: String,
+}
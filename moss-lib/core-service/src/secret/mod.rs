@@ -0,0 +1,87 @@
+use crate::errors::Error;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use once_cell::sync::OnceCell;
+use tracing::{debug, warn};
+
+pub mod config;
+
+/// SECRET_NONCE_SIZE is the nonce size of AES-256-GCM
+const SECRET_NONCE_SIZE: usize = 12;
+
+pub static SECRET: OnceCell<Aes256Gcm> = OnceCell::new();
+
+/// init_secret initializes the cipher to encrypt secret values
+pub fn init_secret(cfg: &config::Config) -> Result<()> {
+    if cfg.key.is_empty() {
+        warn!("secret key is not configured, secret values are disabled");
+        return Ok(());
+    }
+    let key = BASE64.decode(cfg.key.as_bytes())?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|_| anyhow!("secret key must be 32 bytes"))?;
+    SECRET
+        .set(cipher)
+        .map_err(|_| anyhow!("secret is already initialized"))?;
+    debug!("init secret cipher");
+    Ok(())
+}
+
+/// encrypt encrypts plain value to base64 encoded nonce and ciphertext
+pub fn encrypt(plain: &str) -> Result<String, Error> {
+    let cipher = SECRET.get().ok_or(Error::SecretKeyMissing)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut data = nonce.to_vec();
+    data.extend(
+        cipher
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|_| Error::SecretInvalid)?,
+    );
+    Ok(BASE64.encode(data))
+}
+
+/// decrypt decrypts value generated by encrypt
+pub fn decrypt(value: &str) -> Result<String, Error> {
+    let cipher = SECRET.get().ok_or(Error::SecretKeyMissing)?;
+    let data = BASE64
+        .decode(value.as_bytes())
+        .map_err(|_| Error::SecretInvalid)?;
+    if data.len() < SECRET_NONCE_SIZE {
+        return Err(Error::SecretInvalid);
+    }
+    let (nonce, ciphertext) = data.split_at(SECRET_NONCE_SIZE);
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::SecretInvalid)?;
+    String::from_utf8(plain).map_err(|_| Error::SecretInvalid)
+}
+
+/// init_testing initializes the cipher shared by tests, SECRET can be set only once
+#[cfg(test)]
+pub(crate) fn init_testing() {
+    SECRET.get_or_init(|| Aes256Gcm::new_from_slice(&[7u8; 32]).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let cfg = config::Config {
+            key: BASE64.encode([7u8; 16]),
+        };
+        assert!(init_secret(&cfg).is_err());
+        init_testing();
+
+        let value = encrypt("secret-value").unwrap();
+        assert_ne!(value, "secret-value");
+        assert_ne!(value, encrypt("secret-value").unwrap());
+        assert_eq!(decrypt(&value).unwrap(), "secret-value");
+
+        assert!(decrypt("invalid").is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use crate::entity::prelude::{FunctionInfo, UserInfo, UserToken};
+use crate::entity::{function_info, user_info, user_token};
+use crate::errors::Error;
+use crate::function::{FUNCTION_STATUS_ACTIVE, FUNCTION_STATUS_DISABLED};
+use crate::user_token::{TOKEN_STATUS_ACTIVE, TOKEN_STATUS_REVOKED};
+use crate::DB;
+use aes_gcm::aead::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use once_cell::sync::Lazy;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use tracing::debug;
+
+/// USER_STATUS_ACTIVE means user can login and call rpc
+pub const USER_STATUS_ACTIVE: &str = "active";
+/// USER_STATUS_DISABLED means user, its tokens and functions are disabled
+pub const USER_STATUS_DISABLED: &str = "disabled";
+
+/// USAGE_LOGIN_KEY is the token usage of login key, exchanged for moss-cli token
+pub const USAGE_LOGIN_KEY: &str = "login-key";
+
+/// USAGE_MOSS_CLI is the token usage of moss-cli
+const USAGE_MOSS_CLI: &str = "moss-cli";
+
+const PASSWORD_MIN_LENGTH: usize = 8;
+
+/// DUMMY_PASSWORD_HASH is verified when account is unknown,
+/// so login takes the same time whether the account exists or not
+static DUMMY_PASSWORD_HASH: Lazy<String> = Lazy::new(|| {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(salt.as_str().as_bytes(), &salt)
+        .unwrap()
+        .to_string()
+});
+
+/// list returns all users
+pub async fn list() -> Result<Vec<user_info::Model>, Error> {
+    let db = DB.get().unwrap();
+    let users = UserInfo::find()
+        .order_by_asc(user_info::Column::Id)
+        .all(db)
+        .await?;
+    Ok(users)
+}
+
+/// get returns the user by id
+pub async fn get(id: i32) -> Result<user_info::Model, Error> {
+    let db = DB.get().unwrap();
+    let user = UserInfo::find_by_id(id).one(db).await?;
+    user.ok_or(Error::UserNotFound)
+}
+
+fn hash_password(password: &str) -> Result<String, Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| Error::RecordStatusInvalid(e.to_string()))?;
+    Ok(hash.to_string())
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// create creates an active user with password
+#[tracing::instrument(skip(password))]
+pub async fn create(
+    username: &str,
+    email: &str,
+    password: &str,
+) -> Result<user_info::Model, Error> {
+    if username.is_empty() || username.len() > 32 {
+        return Err(Error::RecordStatusInvalid(
+            "username must be 1 to 32 characters".to_string(),
+        ));
+    }
+    if !email.contains('@') || email.len() > 256 {
+        return Err(Error::RecordStatusInvalid("email is invalid".to_string()));
+    }
+    if password.len() < PASSWORD_MIN_LENGTH {
+        return Err(Error::RecordStatusInvalid(format!(
+            "password must be at least {PASSWORD_MIN_LENGTH} characters"
+        )));
+    }
+
+    let db = DB.get().unwrap();
+    let exists = UserInfo::find()
+        .filter(
+            Condition::any()
+                .add(user_info::Column::Username.eq(username))
+                .add(user_info::Column::Email.eq(email)),
+        )
+        .one(db)
+        .await?;
+    if let Some(user) = exists {
+        let field = if user.username == username {
+            username
+        } else {
+            email
+        };
+        return Err(Error::RecordExists(field.to_string(), user.uuid));
+    }
+
+    let now = chrono::Utc::now();
+    let active_model = user_info::ActiveModel {
+        id: NotSet,
+        uuid: Set(uuid::Uuid::new_v4().simple().to_string()),
+        username: Set(username.to_string()),
+        nickname: Set(username.to_string()),
+        email: Set(email.to_string()),
+        avatar: Set(String::new()),
+        phone: Set(String::new()),
+        status: Set(USER_STATUS_ACTIVE.to_string()),
+        password: Set(hash_password(password)?),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    let user = active_model.insert(db).await?;
+    debug!("user is created, id: {}, username: {}", user.id, username);
+    Ok(user)
+}
+
+/// login verifies password of username or email, and issues moss-cli token
+#[tracing::instrument(skip(password))]
+pub async fn login(
+    account: &str,
+    password: &str,
+    expires_in: i64,
+) -> Result<user_token::Model, Error> {
+    let db = DB.get().unwrap();
+    let user = UserInfo::find()
+        .filter(
+            Condition::any()
+                .add(user_info::Column::Username.eq(account))
+                .add(user_info::Column::Email.eq(account)),
+        )
+        .one(db)
+        .await?;
+    let user = match user {
+        Some(user) if verify_password(password, &user.password) => user,
+        Some(_) => return Err(Error::LoginFailed),
+        None => {
+            verify_password(password, &DUMMY_PASSWORD_HASH);
+            return Err(Error::LoginFailed);
+        }
+    };
+    if user.status != USER_STATUS_ACTIVE {
+        return Err(Error::UserInactive);
+    }
+    crate::user_token::issue(user.id, USAGE_MOSS_CLI, expires_in).await
+}
+
+/// login_with_key issues moss-cli token of the user of login key token,
+/// the key and the request signature by its secret are verified by caller
+#[tracing::instrument(skip_all, fields(key = key.id))]
+pub async fn login_with_key(
+    key: &user_token::Model,
+    expires_in: i64,
+) -> Result<user_token::Model, Error> {
+    if key.from != USAGE_LOGIN_KEY {
+        return Err(Error::LoginFailed);
+    }
+    crate::user_token::issue(key.user_id, USAGE_MOSS_CLI, expires_in).await
+}
+
+/// set_status enables or disables user, disabling revokes tokens and disables functions of user
+#[tracing::instrument]
+pub async fn set_status(id: i32, status: &str) -> Result<user_info::Model, Error> {
+    if status != USER_STATUS_ACTIVE && status != USER_STATUS_DISABLED {
+        return Err(Error::RecordStatusInvalid(format!(
+            "unsupported user status '{status}'"
+        )));
+    }
+    let user = get(id).await?;
+
+    let txn = DB.get().unwrap().begin().await?;
+    let mut active_model: user_info::ActiveModel = user.into();
+    active_model.status = Set(status.to_string());
+    active_model.updated_at = Set(chrono::Utc::now());
+    let user = active_model.update(&txn).await?;
+
+    if status == USER_STATUS_DISABLED {
+        let tokens = UserToken::update_many()
+            .col_expr(
+                user_token::Column::Status,
+                Expr::value(TOKEN_STATUS_REVOKED),
+            )
+            .filter(user_token::Column::UserId.eq(id))
+            .filter(user_token::Column::Status.eq(TOKEN_STATUS_ACTIVE))
+            .exec(&txn)
+            .await?;
+        let functions = FunctionInfo::update_many()
+            .col_expr(
+                function_info::Column::Status,
+                Expr::value(FUNCTION_STATUS_DISABLED),
+            )
+            .filter(function_info::Column::UserId.eq(id))
+            .filter(function_info::Column::Status.eq(FUNCTION_STATUS_ACTIVE))
+            .exec(&txn)
+            .await?;
+        debug!(
+            "user is disabled, id: {}, revoked tokens: {}, disabled functions: {}",
+            id, tokens.rows_affected, functions.rows_affected
+        );
+    }
+    txn.commit().await?;
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::testing;
+
+    #[test]
+    fn password_hash() {
+        let hash = hash_password("password").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_password("password", &hash));
+        assert!(!verify_password("wrong", &hash));
+        assert!(!verify_password("password", ""));
+        assert!(DUMMY_PASSWORD_HASH.starts_with("$argon2"));
+    }
+
+    #[test]
+    fn create_and_login() {
+        testing::run(async {
+            let user = create("login-user", "login-user@example.com", "password")
+                .await
+                .unwrap();
+            assert!(create("login-user", "other@example.com", "password")
+                .await
+                .is_err());
+
+            assert!(login("login-user", "wrong", 3600).await.is_err());
+            assert!(matches!(
+                login("unknown-user", "password", 3600).await,
+                Err(Error::LoginFailed)
+            ));
+            let token = login("login-user@example.com", "password", 3600)
+                .await
+                .unwrap();
+            assert_eq!(token.user_id, user.id);
+
+            // only login key tokens are exchanged
+            assert!(matches!(
+                login_with_key(&token, 3600).await,
+                Err(Error::LoginFailed)
+            ));
+            let key = crate::user_token::issue(user.id, USAGE_LOGIN_KEY, 0)
+                .await
+                .unwrap();
+            let token = login_with_key(&key, 3600).await.unwrap();
+            assert_eq!(token.user_id, user.id);
+            assert_eq!(token.from, USAGE_MOSS_CLI);
+        })
+    }
+
+    #[test]
+    fn disable_user() {
+        testing::run(async {
+            let user = create("disabled-user", "disabled-user@example.com", "password")
+                .await
+                .unwrap();
+            crate::user_token::issue(user.id, USAGE_MOSS_CLI, 3600)
+                .await
+                .unwrap();
+            let resource = crate::resource::get_by_name(crate::resource::DEFAULT_RESOURCE)
+                .await
+                .unwrap();
+            let now = chrono::Utc::now();
+            function_info::ActiveModel {
+                id: NotSet,
+                user_id: Set(user.id),
+                uuid: Set(uuid::Uuid::new_v4().to_string()),
+                name: Set("disabled-function".to_string()),
+                resource: Set(resource.id),
+                function_type: Set("http".to_string()),
+                storage_path: Set(String::new()),
+                storage_size: Set(0),
+                storage_md5: Set(String::new()),
+                status: Set(FUNCTION_STATUS_ACTIVE.to_string()),
+                created_at: Set(now),
+                deleted_at: Set(now),
+            }
+            .insert(DB.get().unwrap())
+            .await
+            .unwrap();
+
+            let disabled = set_status(user.id, USER_STATUS_DISABLED).await.unwrap();
+            assert_eq!(disabled.status, USER_STATUS_DISABLED);
+            let tokens = crate::user_token::list(user.id).await.unwrap();
+            assert!(tokens.iter().all(|t| t.status == TOKEN_STATUS_REVOKED));
+            let functions = crate::function::list(user.id).await.unwrap();
+            assert_eq!(functions.len(), 1);
+            assert_eq!(functions[0].status, FUNCTION_STATUS_DISABLED);
+            assert!(login("disabled-user", "password", 3600).await.is_err());
+        })
+    }
+}
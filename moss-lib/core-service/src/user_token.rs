@@ -2,8 +2,16 @@ use crate::entity::prelude::{UserInfo, UserToken};
 use crate::entity::{user_info, user_token};
 use crate::errors::Error;
 use crate::DB;
-use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
 use std::time;
+use tracing::debug;
+
+/// TOKEN_STATUS_ACTIVE means token can be used
+pub const TOKEN_STATUS_ACTIVE: &str = "active";
+/// TOKEN_STATUS_REVOKED means token is revoked by user
+pub const TOKEN_STATUS_REVOKED: &str = "revoked";
 
 /// UserTokenBundle contains token and user info
 pub struct UserTokenBundle {
@@ -11,20 +19,11 @@ pub struct UserTokenBundle {
     pub token: user_token::Model,
 }
 
-impl UserTokenBundle {
-    pub fn validate_signature(&self, sign: String) -> Result<(), Error> {
-        if self.token.secret_token != sign {
-            return Err(Error::TokenSecretIncorrect);
-        }
-        Ok(())
-    }
-}
-
 /// get gets token
 pub async fn get(api_key: String, usage: &str) -> Result<user_token::Model, Error> {
     let db = DB.get().unwrap();
     let user_token = UserToken::find()
-        .filter(user_token::Column::AccessToken.contains(&api_key))
+        .filter(user_token::Column::AccessToken.eq(&api_key))
         .one(db)
         .await
         .map_err(Error::DbInternal)?;
@@ -33,15 +32,11 @@ pub async fn get(api_key: String, usage: &str) -> Result<user_token::Model, Erro
     Ok(user_token)
 }
 
-/// verify token
-pub async fn verify(
-    api_key: String,
-    api_secret: String,
-    usage: &str,
-) -> Result<UserTokenBundle, Error> {
+/// verify token and its user, the request signature is verified by caller with token secret
+pub async fn verify(api_key: String, usage: &str) -> Result<UserTokenBundle, Error> {
     let db = DB.get().unwrap();
     let user_token = UserToken::find()
-        .filter(user_token::Column::AccessToken.contains(&api_key))
+        .filter(user_token::Column::AccessToken.eq(&api_key))
         .one(db)
         .await
         .map_err(Error::DbInternal)?;
@@ -54,31 +49,158 @@ pub async fn verify(
         .await
         .map_err(Error::DbInternal)?;
     let user_info = user_info.ok_or(Error::UserNotFound)?;
-    if user_info.status != "active" {
+    if user_info.status != crate::user::USER_STATUS_ACTIVE {
         return Err(Error::UserInactive);
     }
-    let bundle = UserTokenBundle {
+    Ok(UserTokenBundle {
         info: user_info,
         token: user_token,
-    };
-    bundle.validate_signature(api_secret)?;
-    Ok(bundle)
+    })
 }
 
 /// is_token_expired checks if a token is expired
 fn validate_token(token: &user_token::Model, usage: &str) -> Result<(), Error> {
-    if token.status != "active" {
+    if token.status != TOKEN_STATUS_ACTIVE {
         return Err(Error::TokenInactive);
     }
     if token.from != usage {
         return Err(Error::TokenInvalidUsage);
     }
-    let now_unixstamp = time::SystemTime::now()
+    if is_expired(token) {
+        return Err(Error::TokenExpired);
+    }
+    Ok(())
+}
+
+fn now_unixstamp() -> i64 {
+    time::SystemTime::now()
         .duration_since(time::UNIX_EPOCH)
         .unwrap()
-        .as_secs();
-    if token.expired_at > 0 && token.expired_at < now_unixstamp as i32 {
+        .as_secs() as i64
+}
+
+/// is_expired checks if a token is expired, zero expired_at means never expired
+pub fn is_expired(token: &user_token::Model) -> bool {
+    token.expired_at > 0 && token.expired_at < now_unixstamp()
+}
+
+/// generate_secret generates random hex string with 128 bits per uuid
+fn generate_secret(uuids: usize) -> String {
+    (0..uuids)
+        .map(|_| uuid::Uuid::new_v4().simple().to_string())
+        .collect()
+}
+
+/// issue creates a new token of user for usage, expires_in is seconds and zero means never expired
+#[tracing::instrument]
+pub async fn issue(user_id: i32, usage: &str, expires_in: i64) -> Result<user_token::Model, Error> {
+    if usage.is_empty() {
+        return Err(Error::RecordStatusInvalid(
+            "token usage is required".to_string(),
+        ));
+    }
+    let now = chrono::Utc::now();
+    let expired_at = if expires_in > 0 {
+        now.timestamp() + expires_in
+    } else {
+        0
+    };
+    let active_model = user_token::ActiveModel {
+        id: NotSet,
+        user_id: Set(user_id),
+        access_token: Set(generate_secret(1)),
+        secret_token: Set(generate_secret(2)),
+        from: Set(usage.to_string()),
+        status: Set(TOKEN_STATUS_ACTIVE.to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+        expired_at: Set(expired_at),
+    };
+    let result = active_model.insert(DB.get().unwrap()).await?;
+    debug!("token is issued, id: {}, user_id: {}", result.id, user_id);
+    Ok(result)
+}
+
+/// list returns all tokens of the user
+pub async fn list(user_id: i32) -> Result<Vec<user_token::Model>, Error> {
+    let db = DB.get().unwrap();
+    let tokens = UserToken::find()
+        .filter(user_token::Column::UserId.eq(user_id))
+        .order_by_asc(user_token::Column::Id)
+        .all(db)
+        .await?;
+    Ok(tokens)
+}
+
+async fn find_owned(user_id: i32, access_token: &str) -> Result<user_token::Model, Error> {
+    let db = DB.get().unwrap();
+    let token = UserToken::find()
+        .filter(user_token::Column::UserId.eq(user_id))
+        .filter(user_token::Column::AccessToken.eq(access_token))
+        .one(db)
+        .await?;
+    token.ok_or(Error::TokenNotFound)
+}
+
+/// revoke disables the token of the user
+#[tracing::instrument(skip(access_token))]
+pub async fn revoke(user_id: i32, access_token: &str) -> Result<user_token::Model, Error> {
+    let token = find_owned(user_id, access_token).await?;
+    let mut active_model: user_token::ActiveModel = token.into();
+    active_model.status = Set(TOKEN_STATUS_REVOKED.to_string());
+    active_model.updated_at = Set(chrono::Utc::now());
+    let result = active_model.update(DB.get().unwrap()).await?;
+    debug!("token is revoked, id: {}", result.id);
+    Ok(result)
+}
+
+/// rotate replaces the secret of an active token of the user
+#[tracing::instrument(skip(access_token))]
+pub async fn rotate(user_id: i32, access_token: &str) -> Result<user_token::Model, Error> {
+    let token = find_owned(user_id, access_token).await?;
+    if token.status != TOKEN_STATUS_ACTIVE {
+        return Err(Error::TokenInactive);
+    }
+    if is_expired(&token) {
         return Err(Error::TokenExpired);
     }
-    Ok(())
+    let mut active_model: user_token::ActiveModel = token.into();
+    active_model.secret_token = Set(generate_secret(2));
+    active_model.updated_at = Set(chrono::Utc::now());
+    let result = active_model.update(DB.get().unwrap()).await?;
+    debug!("token is rotated, id: {}", result.id);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::testing;
+    use crate::user;
+
+    #[test]
+    fn token_lifecycle() {
+        testing::run(async {
+            let user = user::create("token-user", "token-user@example.com", "password")
+                .await
+                .unwrap();
+
+            let token = issue(user.id, "moss-cli", 3600).await.unwrap();
+            assert_eq!(token.status, TOKEN_STATUS_ACTIVE);
+            assert!(token.expired_at > 0);
+
+            let rotated = rotate(user.id, &token.access_token).await.unwrap();
+            assert_ne!(token.secret_token, rotated.secret_token);
+
+            let revoked = revoke(user.id, &token.access_token).await.unwrap();
+            assert_eq!(revoked.status, TOKEN_STATUS_REVOKED);
+
+            let tokens = list(user.id).await.unwrap();
+            assert_eq!(tokens.len(), 1);
+
+            // tokens of other users are not visible
+            assert!(list(user.id + 1000).await.unwrap().is_empty());
+            assert!(rotate(user.id + 1000, &token.access_token).await.is_err());
+        })
+    }
 }
@@ -24,6 +24,10 @@ pub trait KvStorage: Send + Sync {
     async fn set(&mut self, k: Key, v: Value) -> Result<(), KvError>;
     async fn delete(&mut self, k: Key) -> Result<(), KvError>;
     async fn get_all(&mut self) -> Result<Vec<Pair>, KvError>;
+    /// flush persists pending writes before shutdown, nothing to do by default
+    async fn flush(&mut self) -> Result<(), KvError> {
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for dyn KvStorage {
@@ -1,5 +1,5 @@
 use crate::{Key, KvError, KvStorage, Pair, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::debug;
 
 /// MEMORY_KV_VALUE_MAX_SIZE is the maximum size of the value in the memory key-value store.
@@ -9,7 +9,14 @@ const MEMORY_KV_KEY_MAX_SIZE: usize = 1024;
 
 #[derive(Debug)]
 pub struct MemoryKvStorage {
-    data: HashMap<Key, Value>,
+    /// data is value and its last access tick by key
+    data: HashMap<Key, (Value, u64)>,
+    /// recent is keys ordered by last access tick, the first is least recently used
+    recent: BTreeMap<u64, Key>,
+    tick: u64,
+    /// size is total bytes of keys and values
+    size: usize,
+    max_size: Option<usize>,
 }
 
 impl MemoryKvStorage {
@@ -17,6 +24,45 @@ impl MemoryKvStorage {
         debug!("[kv] init MemoryKvStorage");
         MemoryKvStorage {
             data: HashMap::new(),
+            recent: BTreeMap::new(),
+            tick: 0,
+            size: 0,
+            max_size: None,
+        }
+    }
+
+    /// with_max_size creates storage evicting least recently used pairs over max_size bytes
+    pub fn with_max_size(max_size: usize) -> Self {
+        MemoryKvStorage {
+            max_size: Some(max_size),
+            ..Self::new()
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, k: &Key) {
+        if let Some((value, tick)) = self.data.remove(k) {
+            self.recent.remove(&tick);
+            self.size -= k.len() + value.0.len();
+        }
+    }
+
+    fn evict(&mut self) {
+        let max_size = match self.max_size {
+            Some(max_size) => max_size,
+            None => return,
+        };
+        while self.size > max_size {
+            let key = match self.recent.values().next() {
+                Some(key) => key.clone(),
+                None => return,
+            };
+            debug!("[kv] evict {}", key);
+            self.remove(&key);
         }
     }
 }
@@ -30,7 +76,13 @@ impl Default for MemoryKvStorage {
 #[async_trait::async_trait]
 impl KvStorage for MemoryKvStorage {
     async fn get(&mut self, k: Key) -> Result<Value, KvError> {
-        self.data.get(&k).cloned().ok_or(KvError::KeyNotFound)
+        let tick = self.next_tick();
+        let (value, last) = self.data.get_mut(&k).ok_or(KvError::KeyNotFound)?;
+        self.recent.remove(last);
+        *last = tick;
+        let value = value.clone();
+        self.recent.insert(tick, k);
+        Ok(value)
     }
     async fn set(&mut self, k: Key, v: Value) -> Result<(), KvError> {
         if k.len() > MEMORY_KV_KEY_MAX_SIZE {
@@ -39,18 +91,23 @@ impl KvStorage for MemoryKvStorage {
         if v.0.len() > MEMORY_KV_VALUE_MAX_SIZE {
             return Err(KvError::ValueTooLarge);
         }
-        self.data.insert(k, v);
+        self.remove(&k);
+        let tick = self.next_tick();
+        self.size += k.len() + v.0.len();
+        self.recent.insert(tick, k.clone());
+        self.data.insert(k, (v, tick));
+        self.evict();
         Ok(())
     }
     async fn delete(&mut self, k: Key) -> Result<(), KvError> {
-        self.data.remove(&k);
+        self.remove(&k);
         Ok(())
     }
     async fn get_all(&mut self) -> Result<Vec<Pair>, KvError> {
         Ok(self
             .data
             .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
+            .map(|(k, (v, _))| (k.clone(), v.clone()))
             .collect())
     }
 }
@@ -77,4 +134,29 @@ mod tests {
         let values = storage.get_all().await.unwrap();
         assert_eq!(values.len(), 0);
     }
+
+    #[tokio::test]
+    async fn evict_least_recently_used() {
+        // each pair is 1 byte key and 9 bytes value
+        let mut storage = MemoryKvStorage::with_max_size(30);
+        for key in ["a", "b", "c"] {
+            storage.set(key.to_string(), (vec![0; 9], 0)).await.unwrap();
+        }
+        storage.get("a".to_string()).await.unwrap();
+        storage.set("d".to_string(), (vec![0; 9], 0)).await.unwrap();
+        assert!(storage.get("b".to_string()).await.is_err());
+        assert!(storage.get("a".to_string()).await.is_ok());
+        assert_eq!(storage.size, 30);
+
+        // replacing value updates size
+        storage.set("a".to_string(), (vec![0; 4], 0)).await.unwrap();
+        assert_eq!(storage.size, 25);
+        storage
+            .set("e".to_string(), (vec![0; 19], 0))
+            .await
+            .unwrap();
+        assert_eq!(storage.get_all().await.unwrap().len(), 2);
+        assert!(storage.get("e".to_string()).await.is_ok());
+        assert!(storage.get("a".to_string()).await.is_ok());
+    }
 }
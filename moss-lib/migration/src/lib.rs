@@ -0,0 +1,70 @@
+pub use sea_orm_migration::prelude::*;
+
+mod m20230301_000001_create_tables;
+mod m20230302_000001_fix_legacy_tables;
+mod m20230310_000001_add_user_password;
+mod m20230315_000001_create_user_resource;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20230301_000001_create_tables::Migration),
+            Box::new(m20230302_000001_fix_legacy_tables::Migration),
+            Box::new(m20230310_000001_add_user_password::Migration),
+            Box::new(m20230315_000001_create_user_resource::Migration),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm_migration::sea_orm::{ConnectOptions, Database, DatabaseConnection};
+
+    async fn connect_memory() -> DatabaseConnection {
+        // each connection of in-memory sqlite is a new database, use only one
+        let mut opt = ConnectOptions::new("sqlite::memory:".to_string());
+        opt.max_connections(1).min_connections(1);
+        Database::connect(opt).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn migrate_up_and_down() {
+        let db = connect_memory().await;
+        let tables = [
+            "user_info",
+            "user_token",
+            "function_resource",
+            "function_info",
+            "function_conf",
+            "user_resource",
+        ];
+
+        Migrator::up(&db, None).await.unwrap();
+        assert!(Migrator::get_pending_migrations(&db)
+            .await
+            .unwrap()
+            .is_empty());
+        let manager = SchemaManager::new(&db);
+        for table in tables {
+            assert!(manager.has_table(table).await.unwrap(), "{table}");
+        }
+        assert!(manager.has_column("user_info", "password").await.unwrap());
+
+        Migrator::down(&db, None).await.unwrap();
+        assert_eq!(
+            Migrator::get_pending_migrations(&db).await.unwrap().len(),
+            Migrator::migrations().len()
+        );
+        for table in tables {
+            assert!(!manager.has_table(table).await.unwrap(), "{table}");
+        }
+
+        // migrations can be applied again after rollback
+        Migrator::up(&db, None).await.unwrap();
+        assert!(manager.has_table("user_info").await.unwrap());
+    }
+}
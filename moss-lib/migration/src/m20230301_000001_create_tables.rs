@@ -0,0 +1,343 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum UserInfo {
+    Table,
+    Id,
+    Uuid,
+    Username,
+    Nickname,
+    Email,
+    Avatar,
+    Phone,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum UserToken {
+    Table,
+    Id,
+    UserId,
+    AccessToken,
+    SecretToken,
+    From,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+    ExpiredAt,
+}
+
+#[derive(Iden)]
+enum FunctionResource {
+    Table,
+    Id,
+    Name,
+    CpuTime,
+    MemoryUsage,
+    WallTime,
+    FetchCounts,
+    FetchRemoteList,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum FunctionInfo {
+    Table,
+    Id,
+    UserId,
+    Uuid,
+    Name,
+    Resource,
+    FunctionType,
+    StoragePath,
+    StorageSize,
+    StorageMd5,
+    Status,
+    CreatedAt,
+    DeletedAt,
+}
+
+#[derive(Iden)]
+enum FunctionConf {
+    Table,
+    Id,
+    FunctionId,
+    Name,
+    Value,
+    ConfType,
+    Status,
+    CreatedAt,
+    DeletedAt,
+}
+
+fn id(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col)
+        .integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .to_owned()
+}
+
+fn string(col: impl IntoIden, len: u32) -> ColumnDef {
+    ColumnDef::new(col)
+        .string_len(len)
+        .not_null()
+        .default("")
+        .to_owned()
+}
+
+fn integer(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col).integer().not_null().to_owned()
+}
+
+fn timestamp(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col)
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::current_timestamp())
+        .to_owned()
+}
+
+fn index(table: impl IntoTableRef, name: &str, col: impl IntoIndexColumn) -> IndexCreateStatement {
+    Index::create().name(name).table(table).col(col).to_owned()
+}
+
+impl Migration {
+    async fn create_user_info(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserInfo::Table)
+                    .col(&mut id(UserInfo::Id))
+                    .col(&mut string(UserInfo::Uuid, 32))
+                    .col(&mut string(UserInfo::Username, 32))
+                    .col(&mut string(UserInfo::Nickname, 32))
+                    .col(string(UserInfo::Email, 256).unique_key())
+                    .col(&mut string(UserInfo::Avatar, 256))
+                    .col(&mut string(UserInfo::Phone, 32))
+                    .col(&mut string(UserInfo::Status, 12))
+                    .col(&mut timestamp(UserInfo::CreatedAt))
+                    .col(&mut timestamp(UserInfo::UpdatedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(index(UserInfo::Table, "idx-user_info-uuid", UserInfo::Uuid))
+            .await?;
+        manager
+            .create_index(index(
+                UserInfo::Table,
+                "idx-user_info-status",
+                UserInfo::Status,
+            ))
+            .await
+    }
+
+    async fn create_user_token(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserToken::Table)
+                    .col(&mut id(UserToken::Id))
+                    .col(&mut integer(UserToken::UserId))
+                    .col(&mut string(UserToken::AccessToken, 128))
+                    .col(&mut string(UserToken::SecretToken, 128))
+                    .col(&mut string(UserToken::From, 32))
+                    .col(&mut string(UserToken::Status, 12))
+                    .col(&mut timestamp(UserToken::CreatedAt))
+                    .col(&mut timestamp(UserToken::UpdatedAt))
+                    .col(
+                        ColumnDef::new(UserToken::ExpiredAt)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_token-user_id")
+                            .from(UserToken::Table, UserToken::UserId)
+                            .to(UserInfo::Table, UserInfo::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(index(
+                UserToken::Table,
+                "idx-user_token-user_id",
+                UserToken::UserId,
+            ))
+            .await?;
+        manager
+            .create_index(index(
+                UserToken::Table,
+                "idx-user_token-access_token",
+                UserToken::AccessToken,
+            ))
+            .await
+    }
+
+    async fn create_function_resource(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FunctionResource::Table)
+                    .col(&mut id(FunctionResource::Id))
+                    .col(string(FunctionResource::Name, 32).unique_key())
+                    .col(integer(FunctionResource::CpuTime).default(1000))
+                    .col(integer(FunctionResource::MemoryUsage).default(128))
+                    .col(integer(FunctionResource::WallTime).default(30000))
+                    .col(integer(FunctionResource::FetchCounts).default(5))
+                    .col(string(FunctionResource::FetchRemoteList, 256).default("*"))
+                    .col(string(FunctionResource::Status, 12).default("active"))
+                    .col(&mut timestamp(FunctionResource::CreatedAt))
+                    .col(&mut timestamp(FunctionResource::UpdatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // default resource profile of deployed functions
+        let insert = Query::insert()
+            .into_table(FunctionResource::Table)
+            .columns([FunctionResource::Name])
+            .values_panic(["default".into()])
+            .to_owned();
+        manager.exec_stmt(insert).await
+    }
+
+    async fn create_function_info(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FunctionInfo::Table)
+                    .col(&mut id(FunctionInfo::Id))
+                    .col(&mut integer(FunctionInfo::UserId))
+                    .col(string(FunctionInfo::Uuid, 64).unique_key())
+                    .col(&mut string(FunctionInfo::Name, 32))
+                    .col(&mut integer(FunctionInfo::Resource))
+                    .col(&mut string(FunctionInfo::FunctionType, 16))
+                    .col(&mut string(FunctionInfo::StoragePath, 128))
+                    .col(&mut integer(FunctionInfo::StorageSize))
+                    .col(&mut string(FunctionInfo::StorageMd5, 40))
+                    .col(&mut string(FunctionInfo::Status, 16))
+                    .col(&mut timestamp(FunctionInfo::CreatedAt))
+                    .col(&mut timestamp(FunctionInfo::DeletedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-function_info-resource")
+                            .from(FunctionInfo::Table, FunctionInfo::Resource)
+                            .to(FunctionResource::Table, FunctionResource::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-function_info-user_id")
+                            .from(FunctionInfo::Table, FunctionInfo::UserId)
+                            .to(UserInfo::Table, UserInfo::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(index(
+                FunctionInfo::Table,
+                "idx-function_info-user_id",
+                FunctionInfo::UserId,
+            ))
+            .await?;
+        manager
+            .create_index(index(
+                FunctionInfo::Table,
+                "idx-function_info-status",
+                FunctionInfo::Status,
+            ))
+            .await
+    }
+
+    async fn create_function_conf(&self, manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FunctionConf::Table)
+                    .col(&mut id(FunctionConf::Id))
+                    .col(&mut integer(FunctionConf::FunctionId))
+                    .col(&mut string(FunctionConf::Name, 128))
+                    .col(&mut string(FunctionConf::Value, 512))
+                    .col(&mut string(FunctionConf::ConfType, 16))
+                    .col(string(FunctionConf::Status, 16).default("active"))
+                    .col(&mut timestamp(FunctionConf::CreatedAt))
+                    .col(&mut timestamp(FunctionConf::DeletedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-function_conf-function_id")
+                            .from(FunctionConf::Table, FunctionConf::FunctionId)
+                            .to(FunctionInfo::Table, FunctionInfo::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(index(
+                FunctionConf::Table,
+                "idx-function_conf-function_id",
+                FunctionConf::FunctionId,
+            ))
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // databases created by the former etc/moss-serverless.sql dump already have the tables,
+        // m20230302_000001_fix_legacy_tables corrects their schema
+        if manager.has_table("user_info").await? {
+            return Ok(());
+        }
+        self.create_user_info(manager).await?;
+        self.create_user_token(manager).await?;
+        self.create_function_resource(manager).await?;
+        self.create_function_info(manager).await?;
+        self.create_function_conf(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FunctionConf::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FunctionInfo::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(FunctionResource::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(UserToken::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(UserInfo::Table).if_exists().to_owned())
+            .await
+    }
+}
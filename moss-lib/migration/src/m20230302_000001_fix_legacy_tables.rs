@@ -0,0 +1,238 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum UserInfo {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum UserToken {
+    Table,
+    Id,
+    UserId,
+    ExpiredAt,
+}
+
+#[derive(Iden)]
+enum FunctionResource {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum FunctionInfo {
+    Table,
+    Id,
+    UserId,
+    Resource,
+}
+
+#[derive(Iden)]
+enum FunctionConf {
+    Table,
+    Id,
+    FunctionId,
+}
+
+/// LEGACY_FOREIGN_KEY is the wrong foreign key on function_conf.id created by the former sql dump
+const LEGACY_FOREIGN_KEY: &str = "fn_info_id";
+
+fn id(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col)
+        .integer()
+        .not_null()
+        .auto_increment()
+        .to_owned()
+}
+
+fn integer(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col).integer().not_null().to_owned()
+}
+
+fn foreign_key(
+    name: &str,
+    from: impl IntoIden + 'static,
+    from_col: impl IntoIden + 'static,
+    to: impl IntoIden + 'static,
+    to_col: impl IntoIden + 'static,
+) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(name)
+        .from(from, from_col)
+        .to(to, to_col)
+        .to_owned()
+}
+
+/// legacy_foreign_keys are the foreign keys created by the former sql dump, by name and table
+fn legacy_foreign_keys() -> Vec<ForeignKeyDropStatement> {
+    [
+        (LEGACY_FOREIGN_KEY, FunctionConf::Table.into_iden()),
+        ("fn_info_resource", FunctionInfo::Table.into_iden()),
+        ("fn_info_user", FunctionInfo::Table.into_iden()),
+        ("user_token_user_info", UserToken::Table.into_iden()),
+    ]
+    .into_iter()
+    .map(|(name, table)| ForeignKey::drop().name(name).table(table).to_owned())
+    .collect()
+}
+
+/// alter_columns changes unsigned ids to signed integers and expired_at to bigint,
+/// the same types as created by m20230301_000001_create_tables
+fn alter_columns() -> Vec<TableAlterStatement> {
+    vec![
+        Table::alter()
+            .table(UserInfo::Table)
+            .modify_column(&mut id(UserInfo::Id))
+            .to_owned(),
+        Table::alter()
+            .table(UserToken::Table)
+            .modify_column(&mut id(UserToken::Id))
+            .modify_column(&mut integer(UserToken::UserId))
+            .modify_column(
+                ColumnDef::new(UserToken::ExpiredAt)
+                    .big_integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned(),
+        Table::alter()
+            .table(FunctionResource::Table)
+            .modify_column(&mut id(FunctionResource::Id))
+            .to_owned(),
+        Table::alter()
+            .table(FunctionInfo::Table)
+            .modify_column(&mut id(FunctionInfo::Id))
+            .modify_column(&mut integer(FunctionInfo::UserId))
+            .modify_column(&mut integer(FunctionInfo::Resource))
+            .to_owned(),
+        Table::alter()
+            .table(FunctionConf::Table)
+            .modify_column(&mut id(FunctionConf::Id))
+            .modify_column(&mut integer(FunctionConf::FunctionId))
+            .to_owned(),
+    ]
+}
+
+/// foreign_keys are the foreign keys created by m20230301_000001_create_tables
+fn foreign_keys() -> Vec<ForeignKeyCreateStatement> {
+    vec![
+        foreign_key(
+            "fk-user_token-user_id",
+            UserToken::Table,
+            UserToken::UserId,
+            UserInfo::Table,
+            UserInfo::Id,
+        ),
+        foreign_key(
+            "fk-function_info-resource",
+            FunctionInfo::Table,
+            FunctionInfo::Resource,
+            FunctionResource::Table,
+            FunctionResource::Id,
+        ),
+        foreign_key(
+            "fk-function_info-user_id",
+            FunctionInfo::Table,
+            FunctionInfo::UserId,
+            UserInfo::Table,
+            UserInfo::Id,
+        ),
+        foreign_key(
+            "fk-function_conf-function_id",
+            FunctionConf::Table,
+            FunctionConf::FunctionId,
+            FunctionInfo::Table,
+            FunctionInfo::Id,
+        ),
+    ]
+}
+
+/// is_legacy returns whether tables are created by the former etc/moss-serverless.sql dump.
+/// The dump is mysql only and is recognized by its wrong foreign key on function_conf.
+async fn is_legacy(manager: &SchemaManager<'_>) -> Result<bool, DbErr> {
+    if manager.get_database_backend() != DbBackend::MySql {
+        return Ok(false);
+    }
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "SELECT COUNT(*) AS num FROM information_schema.TABLE_CONSTRAINTS \
+         WHERE CONSTRAINT_SCHEMA = DATABASE() AND TABLE_NAME = ? AND CONSTRAINT_NAME = ?",
+        ["function_conf".into(), LEGACY_FOREIGN_KEY.into()],
+    );
+    let num: i64 = match manager.get_connection().query_one(stmt).await? {
+        Some(row) => row.try_get("", "num")?,
+        None => 0,
+    };
+    Ok(num > 0)
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // tables created by m20230301_000001_create_tables are already correct
+        if !is_legacy(manager).await? {
+            return Ok(());
+        }
+        // mysql requires the same signedness of foreign key columns, drop keys before altering
+        for stmt in legacy_foreign_keys() {
+            manager.drop_foreign_key(stmt).await?;
+        }
+        for stmt in alter_columns() {
+            manager.alter_table(stmt).await?;
+        }
+        for stmt in foreign_keys() {
+            manager.create_foreign_key(stmt).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // the fixed tables are the same as created by m20230301_000001_create_tables,
+        // which drops them when rolling back
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_statements() {
+        let drops: Vec<String> = legacy_foreign_keys()
+            .iter()
+            .map(|stmt| stmt.to_string(MysqlQueryBuilder))
+            .collect();
+        assert_eq!(
+            drops[0],
+            "ALTER TABLE `function_conf` DROP FOREIGN KEY `fn_info_id`"
+        );
+
+        let alters: Vec<String> = alter_columns()
+            .iter()
+            .map(|stmt| stmt.to_string(MysqlQueryBuilder))
+            .collect();
+        assert_eq!(
+            alters[1],
+            "ALTER TABLE `user_token` \
+             MODIFY COLUMN `id` int NOT NULL AUTO_INCREMENT, \
+             MODIFY COLUMN `user_id` int NOT NULL, \
+             MODIFY COLUMN `expired_at` bigint NOT NULL DEFAULT 0"
+        );
+
+        let creates: Vec<String> = foreign_keys()
+            .iter()
+            .map(|stmt| stmt.to_string(MysqlQueryBuilder))
+            .collect();
+        assert_eq!(
+            creates[3],
+            "ALTER TABLE `function_conf` ADD CONSTRAINT `fk-function_conf-function_id` \
+             FOREIGN KEY (`function_id`) REFERENCES `function_info` (`id`)"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum UserInfo {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum FunctionResource {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum UserResource {
+    Table,
+    Id,
+    UserId,
+    ResourceId,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // user_resource grants non-default resource profiles to users
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserResource::Table)
+                    .col(
+                        ColumnDef::new(UserResource::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserResource::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(UserResource::ResourceId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserResource::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_resource-user_id")
+                            .from(UserResource::Table, UserResource::UserId)
+                            .to(UserInfo::Table, UserInfo::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_resource-resource_id")
+                            .from(UserResource::Table, UserResource::ResourceId)
+                            .to(FunctionResource::Table, FunctionResource::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-user_resource-user_id-resource_id")
+                    .table(UserResource::Table)
+                    .col(UserResource::UserId)
+                    .col(UserResource::ResourceId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserResource::Table).to_owned())
+            .await
+    }
+}
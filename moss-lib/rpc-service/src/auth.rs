@@ -1,97 +1,326 @@
+// helpers return tonic::Status as rpc handlers do, so it is propagated with `?` without conversion
+#![allow(clippy::result_large_err)]
+
+use hmac::{Hmac, Mac};
 use moss_core_service::entity::user_token::Model as UserTokenModel;
-use tonic::{metadata::MetadataValue, service::Interceptor, Request, Status};
-
-/// AUTH_STATIC_TOKEN is a static token for authorization
-const AUTH_STATIC_TOKEN: &str = "e20a0453781758a542116380672548449e3a34ef";
-/// AUTH_STATIC_HEADER is a static token header
-const AUTH_STATIC_HEADER: &str = "authorization";
-const AUTH_DYNAMIC_TOKRN_HEADER: &str = "x-moss-token";
-const AUTH_DYNAMIC_SECRET_HEADER: &str = "x-moss-signature";
-const AUTH_DYNAMIC_ACTION_HEADER: &str = "x-moss-action";
-
-const AUTH_ACTION_CLI_AUTH: &str = "moss_cli_auth";
-const AUTH_ACTION_CLI_RPC_CALL: &str = "moss_cli_rpc_call";
-
-/// AuthStaticTokenInterceptor is a interceptor to add static token to request
-pub struct AuthStaticTokenInterceptor {}
-
-impl Interceptor for AuthStaticTokenInterceptor {
-    fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
-        let token: MetadataValue<_> = format!("Bearer {AUTH_STATIC_TOKEN}").parse().unwrap();
-        req.metadata_mut().insert(AUTH_STATIC_HEADER, token);
-        req.metadata_mut().insert(
-            AUTH_DYNAMIC_ACTION_HEADER,
-            MetadataValue::from_static(AUTH_ACTION_CLI_AUTH),
-        );
-        Ok(req)
+use moss_core_service::user::USAGE_LOGIN_KEY;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tonic::{metadata::MetadataValue, Request, Status};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const AUTH_TOKEN_HEADER: &str = "x-moss-token";
+const AUTH_SIGNATURE_HEADER: &str = "x-moss-signature";
+const AUTH_TIMESTAMP_HEADER: &str = "x-moss-timestamp";
+const AUTH_NONCE_HEADER: &str = "x-moss-nonce";
+const AUTH_ACTION_HEADER: &str = "x-moss-action";
+
+pub const AUTH_ACTION_CLI_AUTH: &str = "moss_cli_auth";
+pub const AUTH_ACTION_CLI_RPC_CALL: &str = "moss_cli_rpc_call";
+
+/// AUTH_TOKEN_USAGE is the token usage of moss-cli
+const AUTH_TOKEN_USAGE: &str = "moss-cli";
+
+/// LOGIN_WINDOW is the seconds of window counting login attempts
+const LOGIN_WINDOW: u64 = 60;
+
+/// Config is the auth config of rpc server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// bootstrap_secret signs the CreateToken requests from `moss-cli auth <user_token>`,
+    /// it is kept by operators and never needed to login
+    pub bootstrap_secret: String,
+    /// signature_ttl is the max seconds between request timestamp and server time
+    pub signature_ttl: u64,
+    /// login_token_ttl is the seconds to expire of tokens issued by Login
+    pub login_token_ttl: i64,
+    /// login_attempts is the max Login requests of an account or address per minute
+    pub login_attempts: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bootstrap_secret: String::new(),
+            signature_ttl: 300,
+            login_token_ttl: 30 * 24 * 3600,
+            login_attempts: 10,
+        }
     }
 }
 
-/// AuthDynamicTokenInterceptor is a interceptor to add dynamic token to request
-pub struct AuthDynamicTokenInterceptor {
-    pub access_token: String,
-    pub secret_token: String,
+fn now_unixstamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// string_to_sign joins timestamp, nonce, rpc method and body hash
+fn string_to_sign(timestamp: &str, nonce: &str, method: &str, body: &[u8]) -> String {
+    let body_hash = hex::encode(Sha256::digest(body));
+    format!("{timestamp}\n{nonce}\n{method}\n{body_hash}")
 }
 
-impl Interceptor for AuthDynamicTokenInterceptor {
-    fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
-        let token: MetadataValue<_> = self.access_token.parse().unwrap();
-        req.metadata_mut().insert(AUTH_DYNAMIC_TOKRN_HEADER, token);
-        let secret: MetadataValue<_> = self.secret_token.parse().unwrap();
-        req.metadata_mut()
-            .insert(AUTH_DYNAMIC_SECRET_HEADER, secret);
-        req.metadata_mut().insert(
-            AUTH_DYNAMIC_ACTION_HEADER,
-            MetadataValue::from_static(AUTH_ACTION_CLI_RPC_CALL),
-        );
-        Ok(req)
+fn create_mac(secret: &str, payload: &str) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts key of any size");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// sign_request adds timestamp, nonce and signature of the message to request
+pub fn sign_request<T: prost::Message>(
+    req: &mut Request<T>,
+    action: &'static str,
+    method: &str,
+    access_token: &str,
+    secret: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let timestamp = now_unixstamp().to_string();
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let payload = string_to_sign(&timestamp, &nonce, method, &req.get_ref().encode_to_vec());
+    let signature = hex::encode(create_mac(secret, &payload).finalize().into_bytes());
+
+    let metadata = req.metadata_mut();
+    metadata.insert(AUTH_ACTION_HEADER, MetadataValue::from_static(action));
+    if !access_token.is_empty() {
+        metadata.insert(AUTH_TOKEN_HEADER, access_token.parse()?);
     }
+    metadata.insert(AUTH_TIMESTAMP_HEADER, timestamp.parse()?);
+    metadata.insert(AUTH_NONCE_HEADER, nonce.parse()?);
+    metadata.insert(AUTH_SIGNATURE_HEADER, signature.parse()?);
+    Ok(())
 }
 
-/// verify_auth_token verify auth token
-pub async fn verify_auth_token<T>(req: &Request<T>) -> Result<(), Status> {
-    // get action name
-    let action = match req.metadata().get(AUTH_DYNAMIC_ACTION_HEADER) {
-        Some(t) => t,
-        _ => return Err(Status::unauthenticated("Invalid rpc action")),
-    };
-    // cli auth request
-    if action != AUTH_ACTION_CLI_AUTH {
-        return Err(Status::unauthenticated("Invalid rpc action"));
-    }
-    let token = match req.metadata().get(AUTH_STATIC_HEADER) {
-        Some(t) => t,
-        _ => return Err(Status::unauthenticated("Invalid auth token")),
-    };
-    let static_token: MetadataValue<_> = format!("Bearer {AUTH_STATIC_TOKEN}").parse().unwrap();
-    if token == static_token {
-        return Ok(());
-    }
-    Err(Status::unauthenticated("Invalid auth token"))
+/// Verifier verifies signed requests, rejects replayed nonces and limits login attempts
+pub struct Verifier {
+    config: Config,
+    nonces: Mutex<HashMap<String, u64>>,
+    /// login attempts by key, with start of window and count in window
+    attempts: Mutex<HashMap<String, (u64, u32)>>,
+}
+
+impl Verifier {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            nonces: Mutex::new(HashMap::new()),
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn header<'a, T>(req: &'a Request<T>, name: &str) -> Result<&'a str, Status> {
+        req.metadata()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Status::unauthenticated(format!("Header {name} is required")))
+    }
+
+    fn verify_action<T>(req: &Request<T>, action: &str) -> Result<(), Status> {
+        if Self::header(req, AUTH_ACTION_HEADER)? != action {
+            return Err(Status::unauthenticated("Invalid rpc action"));
+        }
+        Ok(())
+    }
+
+    /// verify_signature checks request signature signed by secret
+    fn verify_signature<T: prost::Message>(
+        &self,
+        req: &Request<T>,
+        method: &str,
+        secret: &str,
+    ) -> Result<(), Status> {
+        let timestamp = Self::header(req, AUTH_TIMESTAMP_HEADER)?;
+        let nonce = Self::header(req, AUTH_NONCE_HEADER)?;
+        let signature = hex::decode(Self::header(req, AUTH_SIGNATURE_HEADER)?)
+            .map_err(|_| Status::unauthenticated("Invalid signature"))?;
+
+        let payload = string_to_sign(timestamp, nonce, method, &req.get_ref().encode_to_vec());
+        create_mac(secret, &payload)
+            .verify_slice(&signature)
+            .map_err(|_| Status::unauthenticated("Invalid signature"))?;
+
+        self.verify_replay(timestamp, nonce)
+    }
+
+    /// verify_replay checks timestamp is in window and nonce is never used in window
+    fn verify_replay(&self, timestamp: &str, nonce: &str) -> Result<(), Status> {
+        let timestamp: u64 = timestamp
+            .parse()
+            .map_err(|_| Status::unauthenticated("Invalid timestamp"))?;
+        let now = now_unixstamp();
+        let ttl = self.config.signature_ttl;
+        if now.abs_diff(timestamp) > ttl {
+            return Err(Status::unauthenticated("Request timestamp expired"));
+        }
+
+        let mut nonces = self.nonces.lock().unwrap();
+        nonces.retain(|_, t| now.abs_diff(*t) <= ttl);
+        if nonces.contains_key(nonce) {
+            return Err(Status::unauthenticated("Request nonce is reused"));
+        }
+        nonces.insert(nonce.to_string(), timestamp);
+        Ok(())
+    }
+
+    /// verify_auth_token verifies requests signed by bootstrap secret
+    pub fn verify_auth_token<T: prost::Message>(
+        &self,
+        req: &Request<T>,
+        method: &str,
+    ) -> Result<(), Status> {
+        Self::verify_action(req, AUTH_ACTION_CLI_AUTH)?;
+        if self.config.bootstrap_secret.is_empty() {
+            return Err(Status::unauthenticated(
+                "Bootstrap secret is not configured",
+            ));
+        }
+        self.verify_signature(req, method, &self.config.bootstrap_secret)
+    }
+
+    /// verify_rpc_call_token verifies requests signed by user token secret
+    pub async fn verify_rpc_call_token<T: prost::Message>(
+        &self,
+        req: &Request<T>,
+        method: &str,
+    ) -> Result<UserTokenModel, Status> {
+        Self::verify_action(req, AUTH_ACTION_CLI_RPC_CALL)?;
+        let token = Self::header(req, AUTH_TOKEN_HEADER)?.to_string();
+        let token_data = moss_core_service::user_token::verify(token, AUTH_TOKEN_USAGE)
+            .await
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        self.verify_signature(req, method, &token_data.token.secret_token)?;
+        Ok(token_data.token)
+    }
+
+    /// verify_login_key verifies login requests signed by secret of login key,
+    /// the access token alone is not enough to login
+    pub async fn verify_login_key<T: prost::Message>(
+        &self,
+        req: &Request<T>,
+        method: &str,
+        key: &str,
+    ) -> Result<UserTokenModel, Status> {
+        Self::verify_action(req, AUTH_ACTION_CLI_AUTH)?;
+        if Self::header(req, AUTH_TOKEN_HEADER)? != key {
+            return Err(Status::unauthenticated("Invalid login key"));
+        }
+        // token errors are not told apart, as password login does not tell unknown accounts
+        let token_data = moss_core_service::user_token::verify(key.to_string(), USAGE_LOGIN_KEY)
+            .await
+            .map_err(|_| Status::unauthenticated("Invalid login key"))?;
+        self.verify_signature(req, method, &token_data.token.secret_token)?;
+        Ok(token_data.token)
+    }
+
+    /// limit_login counts login attempts of account and remote address in window,
+    /// attempts over config.login_attempts are rejected until the window ends
+    pub fn limit_login<T>(&self, req: &Request<T>, account: &str) -> Result<(), Status> {
+        let mut keys = vec![format!("account:{}", account.to_lowercase())];
+        if let Some(addr) = req.remote_addr() {
+            keys.push(format!("addr:{}", addr.ip()));
+        }
+
+        let now = now_unixstamp();
+        let mut attempts = self.attempts.lock().unwrap();
+        attempts.retain(|_, (start, _)| now.saturating_sub(*start) < LOGIN_WINDOW);
+        let mut limited = false;
+        for key in keys {
+            let (_, count) = attempts.entry(key).or_insert((now, 0));
+            *count = count.saturating_add(1);
+            limited |= *count > self.config.login_attempts;
+        }
+        if limited {
+            return Err(Status::resource_exhausted(
+                "Too many login attempts, try again later",
+            ));
+        }
+        Ok(())
+    }
 }
 
-/// verify_rpc_call_token verify rpc_call token
-pub async fn verify_rpc_call_token<T>(req: &Request<T>) -> Result<UserTokenModel, Status> {
-    // get action name
-    let action = match req.metadata().get(AUTH_DYNAMIC_ACTION_HEADER) {
-        Some(t) => t,
-        _ => return Err(Status::unauthenticated("Invalid rpc action")),
-    };
-
-    if action != AUTH_ACTION_CLI_RPC_CALL {
-        return Err(Status::unauthenticated("Invalid rpc action"));
-    }
-
-    let token = match req.metadata().get(AUTH_DYNAMIC_TOKRN_HEADER) {
-        Some(t) => t.to_str().unwrap().to_string(),
-        _ => return Err(Status::unauthenticated("Access token is required")),
-    };
-    let secret = match req.metadata().get(AUTH_DYNAMIC_SECRET_HEADER) {
-        Some(t) => t.to_str().unwrap().to_string(),
-        _ => return Err(Status::unauthenticated("Signature token is required")),
-    };
-    let token_data = moss_core_service::user_token::verify(token, secret, "moss-cli")
-        .await
-        .map_err(|e| Status::internal(e.to_string()))?;
-    Ok(token_data.token)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenRequest;
+
+    fn create_verifier() -> Verifier {
+        Verifier::new(Config {
+            bootstrap_secret: "bootstrap".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn create_request(token: &str, secret: &str) -> Request<TokenRequest> {
+        let mut req = Request::new(TokenRequest {
+            token: token.to_string(),
+        });
+        sign_request(&mut req, AUTH_ACTION_CLI_AUTH, "CreateToken", "", secret).unwrap();
+        req
+    }
+
+    #[test]
+    fn verify_signed_request() {
+        let verifier = create_verifier();
+        let req = create_request("token", "bootstrap");
+        assert!(verifier.verify_auth_token(&req, "CreateToken").is_ok());
+
+        // replayed request is rejected
+        let err = verifier.verify_auth_token(&req, "CreateToken").unwrap_err();
+        assert_eq!(err.message(), "Request nonce is reused");
+    }
+
+    #[test]
+    fn verify_invalid_request() {
+        let verifier = create_verifier();
+
+        // wrong secret
+        let req = create_request("token", "wrong");
+        assert!(verifier.verify_auth_token(&req, "CreateToken").is_err());
+
+        // signed for another method
+        let req = create_request("token", "bootstrap");
+        assert!(verifier.verify_auth_token(&req, "UploadBundle").is_err());
+
+        // tampered body
+        let mut req = create_request("token", "bootstrap");
+        req.get_mut().token = "another".to_string();
+        assert!(verifier.verify_auth_token(&req, "CreateToken").is_err());
+
+        // expired timestamp
+        let timestamp = (now_unixstamp() - 600).to_string();
+        let err = verifier.verify_replay(&timestamp, "nonce").unwrap_err();
+        assert_eq!(err.message(), "Request timestamp expired");
+    }
+
+    #[test]
+    fn limit_login_attempts() {
+        let verifier = Verifier::new(Config {
+            login_attempts: 2,
+            ..Default::default()
+        });
+        let req = Request::new(());
+        assert!(verifier.limit_login(&req, "moss").is_ok());
+        assert!(verifier.limit_login(&req, "Moss").is_ok());
+        let err = verifier.limit_login(&req, "moss").unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+        // other accounts are counted separately
+        assert!(verifier.limit_login(&req, "other").is_ok());
+
+        // attempts are allowed again in next window
+        verifier
+            .attempts
+            .lock()
+            .unwrap()
+            .values_mut()
+            .for_each(|(start, _)| *start -= LOGIN_WINDOW);
+        assert!(verifier.limit_login(&req, "moss").is_ok());
+    }
 }
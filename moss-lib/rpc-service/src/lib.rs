@@ -7,3 +7,4 @@ mod rpc_client;
 pub use rpc_client::Client;
 
 mod auth;
+pub use auth::Config as AuthConfig;
@@ -1,7 +1,12 @@
-use crate::auth::{AuthDynamicTokenInterceptor, AuthStaticTokenInterceptor};
+use crate::auth::{sign_request, AUTH_ACTION_CLI_AUTH, AUTH_ACTION_CLI_RPC_CALL};
 use crate::moss_rpc_service_client::MossRpcServiceClient;
-use crate::{BundleUploadRequest, TokenRequest, TokenResponse};
-use tonic::{codegen::InterceptedService, transport::Channel, Request};
+use crate::{
+    BundleUploadRequest, FunctionEnv, FunctionInfo, FunctionRequest, FunctionStatusRequest,
+    IssueTokenRequest, ListFunctionEnvRequest, ListFunctionsRequest, ListTokensRequest,
+    LoginRequest, SetFunctionEnvRequest, TokenRequest, TokenResponse, UnsetFunctionEnvRequest,
+    UserToken, UserTokenRequest,
+};
+use tonic::{transport::Channel, Request};
 use tracing::{debug, instrument};
 
 pub struct Client {
@@ -19,32 +24,28 @@ impl Client {
         }
     }
 
-    async fn create_static_client(
-        self,
-    ) -> Result<
-        MossRpcServiceClient<InterceptedService<Channel, AuthStaticTokenInterceptor>>,
-        Box<dyn std::error::Error>,
-    > {
-        let channel = Channel::from_shared(self.addr)?.connect().await?;
-        let client = MossRpcServiceClient::with_interceptor(channel, AuthStaticTokenInterceptor {});
-        Ok(client)
+    async fn create_client(
+        &self,
+    ) -> Result<MossRpcServiceClient<Channel>, Box<dyn std::error::Error>> {
+        let channel = Channel::from_shared(self.addr.clone())?.connect().await?;
+        Ok(MossRpcServiceClient::new(channel))
     }
 
-    async fn create_token_client(
-        self,
-    ) -> Result<
-        MossRpcServiceClient<InterceptedService<Channel, AuthDynamicTokenInterceptor>>,
-        Box<dyn std::error::Error>,
-    > {
-        let channel = Channel::from_shared(self.addr)?.connect().await?;
-        let client = MossRpcServiceClient::with_interceptor(
-            channel,
-            AuthDynamicTokenInterceptor {
-                access_token: self.access_token.clone(),
-                secret_token: self.secret_token.clone(),
-            },
-        );
-        Ok(client)
+    /// create_request creates request signed by token secret
+    fn create_request<T: prost::Message>(
+        &self,
+        message: T,
+        method: &str,
+    ) -> Result<Request<T>, Box<dyn std::error::Error>> {
+        let mut request = Request::new(message);
+        sign_request(
+            &mut request,
+            AUTH_ACTION_CLI_RPC_CALL,
+            method,
+            &self.access_token,
+            &self.secret_token,
+        )?;
+        Ok(request)
     }
 
     #[instrument(
@@ -56,14 +57,57 @@ impl Client {
     pub async fn auth_token(
         self,
         user_token: String,
+        bootstrap_secret: String,
     ) -> Result<TokenResponse, Box<dyn std::error::Error>> {
-        let mut client = self.create_static_client().await?;
-        let request = Request::new(TokenRequest { token: user_token });
+        let mut client = self.create_client().await?;
+        let mut request = Request::new(TokenRequest { token: user_token });
+        sign_request(
+            &mut request,
+            AUTH_ACTION_CLI_AUTH,
+            "CreateToken",
+            "",
+            &bootstrap_secret,
+        )?;
         let response = client.create_token(request).await?;
         debug!("[auth_token] response={:?}", response);
         Ok(response.into_inner())
     }
 
+    #[instrument(skip_all, name = "[Rpc]", level = "debug", fields(method = "login"))]
+    pub async fn login(
+        self,
+        username: String,
+        password: String,
+    ) -> Result<TokenResponse, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = Request::new(LoginRequest {
+            username,
+            password,
+            ..Default::default()
+        });
+        let response = client.login(request).await?;
+        // response contains token secret, do not log it
+        Ok(response.into_inner())
+    }
+
+    /// login_with_key logins by login key, the request is signed by secret of the key
+    #[instrument(skip_all, name = "[Rpc]", level = "debug", fields(method = "login"))]
+    pub async fn login_with_key(
+        self,
+        key: String,
+        secret: String,
+    ) -> Result<TokenResponse, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let mut request = Request::new(LoginRequest {
+            key: key.clone(),
+            ..Default::default()
+        });
+        sign_request(&mut request, AUTH_ACTION_CLI_AUTH, "Login", &key, &secret)?;
+        let response = client.login(request).await?;
+        // response contains token secret, do not log it
+        Ok(response.into_inner())
+    }
+
     #[instrument(
         skip_all,
         name = "[Rpc]",
@@ -74,10 +118,197 @@ impl Client {
         self,
         bundle_req: BundleUploadRequest,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut client = self.create_token_client().await?;
-        let request = Request::new(bundle_req);
+        let mut client = self.create_client().await?;
+        let request = self.create_request(bundle_req, "UploadBundle")?;
         let response = client.upload_bundle(request).await?;
         debug!("response={response:?}");
         Ok(())
     }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "list_functions")
+    )]
+    pub async fn list_functions(self) -> Result<Vec<FunctionInfo>, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(ListFunctionsRequest {}, "ListFunctions")?;
+        let response = client.list_functions(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().functions)
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "get_function")
+    )]
+    pub async fn get_function(
+        self,
+        name: String,
+    ) -> Result<FunctionInfo, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(FunctionRequest { name }, "GetFunction")?;
+        let response = client.get_function(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().function.unwrap_or_default())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "delete_function")
+    )]
+    pub async fn delete_function(
+        self,
+        name: String,
+    ) -> Result<FunctionInfo, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(FunctionRequest { name }, "DeleteFunction")?;
+        let response = client.delete_function(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().function.unwrap_or_default())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "set_function_status")
+    )]
+    pub async fn set_function_status(
+        self,
+        name: String,
+        status: String,
+    ) -> Result<FunctionInfo, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request =
+            self.create_request(FunctionStatusRequest { name, status }, "SetFunctionStatus")?;
+        let response = client.set_function_status(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().function.unwrap_or_default())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "set_function_env")
+    )]
+    pub async fn set_function_env(
+        self,
+        env_req: SetFunctionEnvRequest,
+    ) -> Result<FunctionEnv, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(env_req, "SetFunctionEnv")?;
+        let response = client.set_function_env(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "unset_function_env")
+    )]
+    pub async fn unset_function_env(
+        self,
+        function: String,
+        name: String,
+    ) -> Result<FunctionEnv, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(
+            UnsetFunctionEnvRequest { function, name },
+            "UnsetFunctionEnv",
+        )?;
+        let response = client.unset_function_env(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "list_function_env")
+    )]
+    pub async fn list_function_env(
+        self,
+        function: String,
+    ) -> Result<Vec<FunctionEnv>, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request =
+            self.create_request(ListFunctionEnvRequest { function }, "ListFunctionEnv")?;
+        let response = client.list_function_env(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().envs)
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "issue_token")
+    )]
+    pub async fn issue_token(
+        self,
+        usage: String,
+        expires_in: i64,
+    ) -> Result<UserToken, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(IssueTokenRequest { usage, expires_in }, "IssueToken")?;
+        let response = client.issue_token(request).await?;
+        Ok(response.into_inner())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "list_tokens")
+    )]
+    pub async fn list_tokens(self) -> Result<Vec<UserToken>, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(ListTokensRequest {}, "ListTokens")?;
+        let response = client.list_tokens(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner().tokens)
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "revoke_token")
+    )]
+    pub async fn revoke_token(
+        self,
+        access_token: String,
+    ) -> Result<UserToken, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(UserTokenRequest { access_token }, "RevokeToken")?;
+        let response = client.revoke_token(request).await?;
+        debug!("response={response:?}");
+        Ok(response.into_inner())
+    }
+
+    #[instrument(
+        skip_all,
+        name = "[Rpc]",
+        level = "debug",
+        fields(method = "rotate_token")
+    )]
+    pub async fn rotate_token(
+        self,
+        access_token: String,
+    ) -> Result<UserToken, Box<dyn std::error::Error>> {
+        let mut client = self.create_client().await?;
+        let request = self.create_request(UserTokenRequest { access_token }, "RotateToken")?;
+        let response = client.rotate_token(request).await?;
+        Ok(response.into_inner())
+    }
 }
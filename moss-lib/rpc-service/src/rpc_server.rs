@@ -1,12 +1,124 @@
+use crate::auth::{Config as AuthConfig, Verifier};
 use crate::moss_rpc_service_server::{MossRpcService, MossRpcServiceServer};
-use crate::{BundleUploadRequest, BundleUploadResponse, TokenRequest, TokenResponse};
+use crate::{
+    BundleUploadRequest, BundleUploadResponse, FunctionEnv, FunctionInfo, FunctionRequest,
+    FunctionResource, FunctionResponse, FunctionStatusRequest, IssueTokenRequest,
+    ListFunctionEnvRequest, ListFunctionEnvResponse, ListFunctionsRequest, ListFunctionsResponse,
+    ListTokensRequest, ListTokensResponse, LoginRequest, SetFunctionEnvRequest, TokenRequest,
+    TokenResponse, UnsetFunctionEnvRequest, UserToken, UserTokenRequest,
+};
+use moss_core_service::entity::function_conf::Model as FunctionConfModel;
 use moss_core_service::entity::function_info::Model as FunctionInfoModel;
+use moss_core_service::entity::function_resource::Model as FunctionResourceModel;
+use moss_core_service::entity::user_token::Model as UserTokenModel;
+use moss_core_service::function::FUNCTION_STATUS_ACTIVE;
+use moss_core_service::function_conf::{CONF_TYPE_ENV, CONF_TYPE_SECRET};
+use moss_core_service::Error as CoreError;
+use std::future::Future;
 use std::net::SocketAddr;
 use tonic::{transport::Server, Request, Response, Status};
-use tracing::info;
+use tracing::{info, warn};
 
-#[derive(Default)]
-pub struct MossRpcImpl {}
+pub struct MossRpcImpl {
+    verifier: Verifier,
+    login_token_ttl: i64,
+}
+
+impl MossRpcImpl {
+    pub fn new(auth_config: AuthConfig) -> Self {
+        Self {
+            login_token_ttl: auth_config.login_token_ttl,
+            verifier: Verifier::new(auth_config),
+        }
+    }
+}
+
+impl From<FunctionInfoModel> for FunctionInfo {
+    fn from(model: FunctionInfoModel) -> Self {
+        FunctionInfo {
+            uuid: model.uuid,
+            name: model.name,
+            function_type: model.function_type,
+            status: model.status,
+            storage_size: model.storage_size as i64,
+            storage_md5: model.storage_md5,
+            created_at: model.created_at.timestamp(),
+            resource: None,
+        }
+    }
+}
+
+impl From<FunctionResourceModel> for FunctionResource {
+    fn from(model: FunctionResourceModel) -> Self {
+        FunctionResource {
+            name: model.name,
+            cpu_time: model.cpu_time,
+            memory_usage: model.memory_usage,
+            wall_time: model.wall_time,
+            fetch_counts: model.fetch_counts,
+            fetch_remote_list: model.fetch_remote_list,
+        }
+    }
+}
+
+/// SECRET_MASK replaces secret values in responses
+const SECRET_MASK: &str = "******";
+
+impl From<FunctionConfModel> for FunctionEnv {
+    fn from(model: FunctionConfModel) -> Self {
+        let secret = model.conf_type == CONF_TYPE_SECRET;
+        FunctionEnv {
+            name: model.name,
+            value: if secret {
+                SECRET_MASK.to_string()
+            } else {
+                model.value
+            },
+            secret,
+        }
+    }
+}
+
+impl From<UserTokenModel> for UserToken {
+    fn from(model: UserTokenModel) -> Self {
+        UserToken {
+            access_token: model.access_token,
+            // secret token is only returned when issued or rotated
+            secret_token: String::new(),
+            usage: model.from,
+            status: model.status,
+            created_at: model.created_at.timestamp(),
+            expired_at: model.expired_at,
+        }
+    }
+}
+
+/// with_secret converts token model to response with secret token
+fn with_secret(model: UserTokenModel) -> UserToken {
+    let secret_token = model.secret_token.clone();
+    UserToken {
+        secret_token,
+        ..model.into()
+    }
+}
+
+/// error_status converts core service error to rpc status
+fn error_status(e: CoreError) -> Status {
+    match e {
+        CoreError::RecordNotFound | CoreError::TokenNotFound | CoreError::ResourceNotFound(_) => {
+            Status::not_found(e.to_string())
+        }
+        CoreError::ResourceNotEntitled(_) => Status::permission_denied(e.to_string()),
+        CoreError::TokenInactive | CoreError::TokenExpired | CoreError::UserInactive => {
+            Status::failed_precondition(e.to_string())
+        }
+        CoreError::LoginFailed => Status::unauthenticated(e.to_string()),
+        CoreError::RecordExists(_, _) => Status::already_exists(e.to_string()),
+        CoreError::RecordStatusInvalid(_) => Status::invalid_argument(e.to_string()),
+        CoreError::SecretKeyMissing => Status::failed_precondition(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
 
 #[tonic::async_trait]
 impl MossRpcService for MossRpcImpl {
@@ -14,16 +126,22 @@ impl MossRpcService for MossRpcImpl {
         &self,
         req: Request<BundleUploadRequest>,
     ) -> Result<Response<BundleUploadResponse>, Status> {
-        let token_model = crate::auth::verify_rpc_call_token(&req).await?;
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "UploadBundle")
+            .await?;
         let req = req.into_inner();
+        let resource = moss_core_service::resource::resolve(token_model.user_id, &req.resource)
+            .await
+            .map_err(error_status)?;
         let now = chrono::Utc::now();
         let function_info = FunctionInfoModel {
             id: 0,
             user_id: token_model.user_id,
             name: req.name,
-            uuid: "uuid".to_string(),
-            resource: 1,
-            status: "active".to_string(),
+            uuid: uuid::Uuid::new_v4().to_string(),
+            resource: resource.id,
+            status: FUNCTION_STATUS_ACTIVE.to_string(),
             function_type: req.function_type,
             storage_path: "/tmp".to_string(),
             storage_size: req.bundle_size as i32,
@@ -47,7 +165,7 @@ impl MossRpcService for MossRpcImpl {
         &self,
         request: Request<TokenRequest>,
     ) -> Result<Response<TokenResponse>, Status> {
-        crate::auth::verify_auth_token(&request).await?;
+        self.verifier.verify_auth_token(&request, "CreateToken")?;
         let token_str = request.into_inner().token;
         match moss_core_service::user_token::get(token_str, "moss-cli").await {
             Ok(token) => {
@@ -61,14 +179,259 @@ impl MossRpcService for MossRpcImpl {
             Err(e) => Err(Status::internal(e.to_string())),
         }
     }
+
+    async fn list_functions(
+        &self,
+        req: Request<ListFunctionsRequest>,
+    ) -> Result<Response<ListFunctionsResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "ListFunctions")
+            .await?;
+        let functions = moss_core_service::function::list(token_model.user_id)
+            .await
+            .map_err(error_status)?;
+        let resp = ListFunctionsResponse {
+            functions: functions.into_iter().map(FunctionInfo::from).collect(),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn get_function(
+        &self,
+        req: Request<FunctionRequest>,
+    ) -> Result<Response<FunctionResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "GetFunction")
+            .await?;
+        let name = req.into_inner().name;
+        let model = moss_core_service::function::get(token_model.user_id, &name)
+            .await
+            .map_err(error_status)?;
+        let resource = moss_core_service::resource::get(model.resource)
+            .await
+            .map_err(error_status)?;
+        let mut function = FunctionInfo::from(model);
+        function.resource = Some(resource.into());
+        let resp = FunctionResponse {
+            function: Some(function),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn delete_function(
+        &self,
+        req: Request<FunctionRequest>,
+    ) -> Result<Response<FunctionResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "DeleteFunction")
+            .await?;
+        let name = req.into_inner().name;
+        let model = moss_core_service::function::delete(token_model.user_id, &name)
+            .await
+            .map_err(error_status)?;
+        info!("function deleted: {:?}", model);
+        let resp = FunctionResponse {
+            function: Some(model.into()),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn set_function_status(
+        &self,
+        req: Request<FunctionStatusRequest>,
+    ) -> Result<Response<FunctionResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "SetFunctionStatus")
+            .await?;
+        let req = req.into_inner();
+        let model =
+            moss_core_service::function::set_status(token_model.user_id, &req.name, &req.status)
+                .await
+                .map_err(error_status)?;
+        info!("function status changed: {:?}", model);
+        let resp = FunctionResponse {
+            function: Some(model.into()),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn set_function_env(
+        &self,
+        req: Request<SetFunctionEnvRequest>,
+    ) -> Result<Response<FunctionEnv>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "SetFunctionEnv")
+            .await?;
+        let req = req.into_inner();
+        let function = moss_core_service::function::get(token_model.user_id, &req.function)
+            .await
+            .map_err(error_status)?;
+        let conf_type = if req.secret {
+            CONF_TYPE_SECRET
+        } else {
+            CONF_TYPE_ENV
+        };
+        let model =
+            moss_core_service::function_conf::set(function.id, &req.name, &req.value, conf_type)
+                .await
+                .map_err(error_status)?;
+        info!("function env set: {}, {}", function.name, model.name);
+        Ok(Response::new(model.into()))
+    }
+
+    async fn unset_function_env(
+        &self,
+        req: Request<UnsetFunctionEnvRequest>,
+    ) -> Result<Response<FunctionEnv>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "UnsetFunctionEnv")
+            .await?;
+        let req = req.into_inner();
+        let function = moss_core_service::function::get(token_model.user_id, &req.function)
+            .await
+            .map_err(error_status)?;
+        let model = moss_core_service::function_conf::unset(function.id, &req.name)
+            .await
+            .map_err(error_status)?;
+        info!("function env unset: {}, {}", function.name, model.name);
+        Ok(Response::new(model.into()))
+    }
+
+    async fn list_function_env(
+        &self,
+        req: Request<ListFunctionEnvRequest>,
+    ) -> Result<Response<ListFunctionEnvResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "ListFunctionEnv")
+            .await?;
+        let req = req.into_inner();
+        let function = moss_core_service::function::get(token_model.user_id, &req.function)
+            .await
+            .map_err(error_status)?;
+        let confs = moss_core_service::function_conf::list(function.id)
+            .await
+            .map_err(error_status)?;
+        let resp = ListFunctionEnvResponse {
+            envs: confs.into_iter().map(FunctionEnv::from).collect(),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn issue_token(
+        &self,
+        req: Request<IssueTokenRequest>,
+    ) -> Result<Response<UserToken>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "IssueToken")
+            .await?;
+        let req = req.into_inner();
+        let model =
+            moss_core_service::user_token::issue(token_model.user_id, &req.usage, req.expires_in)
+                .await
+                .map_err(error_status)?;
+        info!("token issued: {}, {}", model.id, model.from);
+        Ok(Response::new(with_secret(model)))
+    }
+
+    async fn list_tokens(
+        &self,
+        req: Request<ListTokensRequest>,
+    ) -> Result<Response<ListTokensResponse>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "ListTokens")
+            .await?;
+        let tokens = moss_core_service::user_token::list(token_model.user_id)
+            .await
+            .map_err(error_status)?;
+        let resp = ListTokensResponse {
+            tokens: tokens.into_iter().map(UserToken::from).collect(),
+        };
+        Ok(Response::new(resp))
+    }
+
+    async fn revoke_token(
+        &self,
+        req: Request<UserTokenRequest>,
+    ) -> Result<Response<UserToken>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "RevokeToken")
+            .await?;
+        let access_token = req.into_inner().access_token;
+        let model = moss_core_service::user_token::revoke(token_model.user_id, &access_token)
+            .await
+            .map_err(error_status)?;
+        info!("token revoked: {}", model.id);
+        Ok(Response::new(model.into()))
+    }
+
+    async fn rotate_token(
+        &self,
+        req: Request<UserTokenRequest>,
+    ) -> Result<Response<UserToken>, Status> {
+        let token_model = self
+            .verifier
+            .verify_rpc_call_token(&req, "RotateToken")
+            .await?;
+        let access_token = req.into_inner().access_token;
+        let model = moss_core_service::user_token::rotate(token_model.user_id, &access_token)
+            .await
+            .map_err(error_status)?;
+        info!("token rotated: {}", model.id);
+        Ok(Response::new(with_secret(model)))
+    }
+
+    async fn login(&self, req: Request<LoginRequest>) -> Result<Response<TokenResponse>, Status> {
+        let key = req.get_ref().key.clone();
+        let account = if key.is_empty() {
+            req.get_ref().username.as_str()
+        } else {
+            key.as_str()
+        };
+        self.verifier.limit_login(&req, account)?;
+        let token = if key.is_empty() {
+            let req = req.into_inner();
+            moss_core_service::user::login(&req.username, &req.password, self.login_token_ttl).await
+        } else {
+            let key_token = self.verifier.verify_login_key(&req, "Login", &key).await?;
+            moss_core_service::user::login_with_key(&key_token, self.login_token_ttl).await
+        }
+        .map_err(error_status)?;
+        info!("user login: {}, token: {}", token.user_id, token.id);
+        let resp = TokenResponse {
+            access_token: token.access_token,
+            secret_token: token.secret_token,
+            expiration: token.expired_at,
+        };
+        Ok(Response::new(resp))
+    }
 }
 
-/// start startes rpc server
-pub async fn start(addr: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
-    let rpc_impl = MossRpcImpl::default();
+/// start serves rpc until shutdown future resolves, in-flight requests are finished before return
+pub async fn start(
+    addr: SocketAddr,
+    auth_config: AuthConfig,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if auth_config.bootstrap_secret.is_empty() {
+        warn!("auth bootstrap secret is not configured, CreateToken is disabled");
+    }
+    let rpc_impl = MossRpcImpl::new(auth_config);
     let svc = MossRpcServiceServer::new(rpc_impl);
     info!("MossRpcServer listening on {addr}");
 
-    Server::builder().add_service(svc).serve(addr).await?;
+    Server::builder()
+        .add_service(svc)
+        .serve_with_shutdown(addr, shutdown)
+        .await?;
     Ok(())
 }
@@ -0,0 +1,271 @@
+use crate::metadata::MetadataEnv;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// DEFAULT_PROFILE is the profile used when no profile is given
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// DEFAULT_CREDENTIALS_DIR is the credentials dir name in home path
+pub const DEFAULT_CREDENTIALS_DIR: &str = ".moss_cli";
+
+/// LEGACY_ENV_FILE is the bincode env file name used by old versions
+const LEGACY_ENV_FILE: &str = ".moss_cli.env";
+
+/// ENCRYPTED_HEADER is the first line of passphrase encrypted profile
+const ENCRYPTED_HEADER: &str = "moss-encrypted-v1";
+
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+
+/// get credentials dir from home path
+pub fn get_credentials_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(DEFAULT_CREDENTIALS_DIR)
+}
+
+/// CredentialStore saves credentials of named profiles in a private dir
+pub struct CredentialStore {
+    dir: PathBuf,
+}
+
+impl Default for CredentialStore {
+    fn default() -> Self {
+        Self::new(get_credentials_dir())
+    }
+}
+
+impl CredentialStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn profile_file(&self, profile: &str) -> Result<PathBuf> {
+        let valid = !profile.is_empty()
+            && profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!("profile name '{profile}' is invalid, only letters, digits, - and _ are allowed");
+        }
+        Ok(self.dir.join(format!("{profile}.toml")))
+    }
+
+    /// exists checks if the profile is saved
+    pub fn exists(&self, profile: &str) -> Result<bool> {
+        Ok(self.profile_file(profile)?.exists())
+    }
+
+    /// is_encrypted checks if the profile is saved with passphrase
+    pub fn is_encrypted(&self, profile: &str) -> Result<bool> {
+        let content = std::fs::read_to_string(self.profile_file(profile)?)?;
+        Ok(content.starts_with(ENCRYPTED_HEADER))
+    }
+
+    /// save writes the credential of profile, encrypted if passphrase is given
+    pub fn save(&self, profile: &str, env: &MetadataEnv, passphrase: Option<&str>) -> Result<()> {
+        let file = self.profile_file(profile)?;
+        let mut content = toml::to_string(env)?;
+        if let Some(passphrase) = passphrase {
+            content = format!("{ENCRYPTED_HEADER}\n{}\n", encrypt(&content, passphrase)?);
+        }
+        create_private_dir(&self.dir)?;
+        write_private_file(&file, content.as_bytes())
+    }
+
+    /// load reads the credential of profile, passphrase is required if encrypted
+    pub fn load(&self, profile: &str, passphrase: Option<&str>) -> Result<MetadataEnv> {
+        let file = self.profile_file(profile)?;
+        if !file.exists() && profile == DEFAULT_PROFILE {
+            self.import_legacy()?;
+        }
+        let content = std::fs::read_to_string(&file)?;
+        let content = match content.strip_prefix(ENCRYPTED_HEADER) {
+            Some(data) => {
+                let passphrase =
+                    passphrase.ok_or_else(|| anyhow!("profile '{profile}' is encrypted"))?;
+                decrypt(data.trim(), passphrase)?
+            }
+            None => content,
+        };
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// remove deletes the credential of profile, returns false if not saved
+    pub fn remove(&self, profile: &str) -> Result<bool> {
+        let file = self.profile_file(profile)?;
+        if !file.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(file)?;
+        Ok(true)
+    }
+
+    /// list returns names of saved profiles
+    pub fn list(&self) -> Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut profiles = vec![];
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension() == Some(OsStr::new("toml")) {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    profiles.push(name.to_string());
+                }
+            }
+        }
+        profiles.sort();
+        Ok(profiles)
+    }
+
+    /// import_legacy moves bincode env file of old versions to default profile
+    fn import_legacy(&self) -> Result<()> {
+        let legacy_file = match self.dir.parent() {
+            Some(parent) => parent.join(LEGACY_ENV_FILE),
+            None => return Ok(()),
+        };
+        if !legacy_file.exists() {
+            return Ok(());
+        }
+        let env: MetadataEnv = bincode::deserialize(&std::fs::read(&legacy_file)?)?;
+        self.save(DEFAULT_PROFILE, &env, None)?;
+        std::fs::remove_file(legacy_file)?;
+        Ok(())
+    }
+}
+
+/// derive_key derives aes-256 key from passphrase by argon2
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("derive key failed: {e}"))?;
+    Ok(Aes256Gcm::new_from_slice(&key)?)
+}
+
+/// encrypt encrypts content to base64 encoded salt, nonce and ciphertext
+fn encrypt(content: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = derive_key(passphrase, &salt)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, content.as_bytes())
+        .map_err(|_| anyhow!("encrypt credential failed"))?;
+
+    let mut data = salt.to_vec();
+    data.extend(nonce);
+    data.extend(ciphertext);
+    Ok(BASE64.encode(data))
+}
+
+/// decrypt decrypts value generated by encrypt
+fn decrypt(value: &str, passphrase: &str) -> Result<String> {
+    let data = BASE64.decode(value.as_bytes())?;
+    if data.len() < SALT_SIZE + NONCE_SIZE {
+        bail!("encrypted credential is invalid");
+    }
+    let (salt, data) = data.split_at(SALT_SIZE);
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    let cipher = derive_key(passphrase, salt)?;
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("passphrase is incorrect"))?;
+    Ok(String::from_utf8(plain)?)
+}
+
+/// create_private_dir creates dir only accessible by current user
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)?;
+    Ok(())
+}
+
+/// write_private_file writes file only readable and writable by current user
+fn write_private_file(file: &Path, content: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // mode only applies to new file, fix permissions of existing file
+        if file.exists() {
+            std::fs::set_permissions(file, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut f = options.open(file)?;
+    f.write_all(content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_env() -> MetadataEnv {
+        MetadataEnv {
+            api_key: "api_key".to_string(),
+            api_secret: "api_secret".to_string(),
+            api_secret_expires: 123456789,
+            api_host: "api_host".to_string(),
+            created_at: 123456789,
+            login_key: "login_key:login_secret".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// test save and load profiles
+    #[test]
+    fn save_and_load() {
+        let store = CredentialStore::new(PathBuf::from("../tests/data/credentials"));
+        let env_data = create_env();
+        store.save("default", &env_data, None).unwrap();
+        store
+            .save("staging", &env_data, Some("passphrase"))
+            .unwrap();
+        assert_eq!(store.list().unwrap(), vec!["default", "staging"]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let meta = std::fs::metadata("../tests/data/credentials/default.toml").unwrap();
+            assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        }
+
+        let env_data2 = store.load("default", None).unwrap();
+        assert_eq!(env_data.api_key, env_data2.api_key);
+        assert_eq!(env_data.api_secret, env_data2.api_secret);
+        assert_eq!(env_data.api_secret_expires, env_data2.api_secret_expires);
+        assert_eq!(env_data.api_host, env_data2.api_host);
+        assert_eq!(env_data.created_at, env_data2.created_at);
+        assert_eq!(env_data.login_key, env_data2.login_key);
+        assert!(env_data2.username.is_empty());
+
+        assert!(store.is_encrypted("staging").unwrap());
+        assert!(store.load("staging", None).is_err());
+        assert!(store.load("staging", Some("wrong")).is_err());
+        let env_data3 = store.load("staging", Some("passphrase")).unwrap();
+        assert_eq!(env_data.api_secret, env_data3.api_secret);
+
+        assert!(store.remove("staging").unwrap());
+        assert!(!store.remove("staging").unwrap());
+        assert!(store.load("../default", None).is_err());
+
+        std::fs::remove_dir_all("../tests/data/credentials").unwrap();
+    }
+}
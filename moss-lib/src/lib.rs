@@ -1,3 +1,6 @@
 pub mod tracing;
 pub mod version;
-pub mod metadata;
\ No newline at end of file
+pub mod metadata;
+pub mod credential;
+pub mod metrics;
+pub mod shutdown;
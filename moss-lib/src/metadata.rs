@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// DEFAULT_METADATA_FILE is the default metadata file name
@@ -17,6 +18,21 @@ pub struct Metadata {
     pub build: Option<MetadataBuild>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deploy: Option<MetadataDeploy>,
+    /// env is environment variables for local serve
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch: Option<MetadataFetch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<MetadataCompression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<MetadataLimits>,
+    /// error_pages are custom html pages of gateway errors by error code, relative to project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_pages: Option<BTreeMap<String, String>>,
+    /// functions are other function projects served together by local serve
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub functions: Option<Vec<MetadataFunction>>,
 }
 
 /// MetadataBuild is the build section of the Metadata
@@ -30,6 +46,127 @@ pub struct MetadataBuild {
 pub struct MetadataDeploy {
     pub trigger: String,
     pub route_base: Option<String>,
+    /// resource is the resource profile name, the user must be granted to use it
+    pub resource: Option<String>,
+    /// routes of the function, route_base is used if it is empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<MetadataRoute>,
+    /// static assets served before requests are passed to function
+    #[serde(rename = "static", skip_serializing_if = "Option::is_none")]
+    pub static_assets: Option<MetadataStatic>,
+}
+
+/// MetadataStatic is the static assets section in deploy section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataStatic {
+    /// dir is the directory of assets, relative to project
+    pub dir: String,
+    /// prefix is the url path prefix of assets, default is "/"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// index is the file served for directory paths, default is "index.html"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+}
+
+/// MetadataRoute is a route of the function in deploy section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataRoute {
+    /// path pattern like "/api/*path" or "/users/:id"
+    pub path: String,
+    /// methods allowed by the route, empty allows all methods
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    /// host of request, exact name or wildcard like "*.example.com", none matches all hosts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// strip_prefix is removed from request path before it is passed to function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strip_prefix: Option<String>,
+}
+
+/// MetadataFunction is other function project in functions table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataFunction {
+    /// path of project directory with metadata.toml, relative to current project
+    pub path: String,
+}
+
+/// MetadataFetch is the fetch section of the Metadata
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetadataFetch {
+    /// cache is the storage of fetch response cache, "memory" or "kv", none disables cache.
+    /// Both are separated from kv of guests, "kv" keeps entries of functions in their own namespace
+    pub cache: Option<String>,
+}
+
+/// MetadataCompression is the compression section of the Metadata, all switches are on by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataCompression {
+    /// response compresses response body by Accept-Encoding of request
+    #[serde(default = "default_true")]
+    pub response: bool,
+    /// request decompresses request body by Content-Encoding before it is passed to function
+    #[serde(default = "default_true")]
+    pub request: bool,
+    /// max_body_size is the max bytes of decompressed request body, larger bodies are rejected
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
+}
+
+/// DEFAULT_MAX_BODY_SIZE is the default max size of decompressed request body, 10 MiB
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_body_size() -> usize {
+    DEFAULT_MAX_BODY_SIZE
+}
+
+impl Default for MetadataCompression {
+    fn default() -> Self {
+        Self {
+            response: true,
+            request: true,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+/// MetadataLimits is the limits section of the Metadata, times are in milliseconds and zero means no limit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataLimits {
+    /// timeout is the max execution time of a request, it is also the deadline passed to function
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// pool_wait_timeout is the max time of a request waiting for an idle worker
+    #[serde(default = "default_pool_wait_timeout")]
+    pub pool_wait_timeout: u64,
+}
+
+/// DEFAULT_TIMEOUT is the default max execution time of a request, 30 seconds
+pub const DEFAULT_TIMEOUT: u64 = 30_000;
+
+/// DEFAULT_POOL_WAIT_TIMEOUT is the default max time of waiting for an idle worker, 10 seconds
+pub const DEFAULT_POOL_WAIT_TIMEOUT: u64 = 10_000;
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+fn default_pool_wait_timeout() -> u64 {
+    DEFAULT_POOL_WAIT_TIMEOUT
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            pool_wait_timeout: DEFAULT_POOL_WAIT_TIMEOUT,
+        }
+    }
 }
 
 impl Default for MetadataDeploy {
@@ -37,6 +174,9 @@ impl Default for MetadataDeploy {
         Self {
             trigger: "http".to_string(),
             route_base: Some("/*path".to_string()),
+            resource: None,
+            routes: vec![],
+            static_assets: None,
         }
     }
 }
@@ -58,6 +198,31 @@ impl Metadata {
         Ok(manifest)
     }
 
+    /// read Metadata from metadata.toml in project directory,
+    /// target directory is resolved relative to the project directory
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let file = dir.join(DEFAULT_METADATA_FILE);
+        let mut manifest = Self::from_file(file.to_str().unwrap())?;
+        let build = manifest.build.get_or_insert_with(MetadataBuild::default);
+        let target_dir = build.rust_target_dir.as_deref().unwrap_or("target");
+        build.rust_target_dir = Some(dir.join(target_dir).to_str().unwrap().to_string());
+        if let Some(assets) = manifest
+            .deploy
+            .as_mut()
+            .and_then(|d| d.static_assets.as_mut())
+        {
+            assets.dir = dir.join(&assets.dir).to_str().unwrap().to_string();
+        }
+        for page in manifest
+            .error_pages
+            .iter_mut()
+            .flat_map(|pages| pages.values_mut())
+        {
+            *page = dir.join(&*page).to_str().unwrap().to_string();
+        }
+        Ok(manifest)
+    }
+
     /// read Metadata from binary
     pub fn from_binary(data: &[u8]) -> Result<Self> {
         let manifest: Metadata = toml::from_str(std::str::from_utf8(data)?)?;
@@ -116,47 +281,122 @@ impl Metadata {
             .route_base
             .unwrap_or_else(|| "/*path".to_string())
     }
-}
 
-/// DEFAULT_ENV_FILE is the default env file name
-pub const DEFAULT_ENV_FILE: &str = ".moss_cli.env";
+    /// get routes, route_base is the only route if routes are not set
+    pub fn get_routes(&self) -> Vec<MetadataRoute> {
+        let deploy = self.deploy.clone().unwrap_or_default();
+        if !deploy.routes.is_empty() {
+            return deploy.routes;
+        }
+        vec![MetadataRoute {
+            path: self.get_route_base(),
+            ..Default::default()
+        }]
+    }
 
-/// get metadata env file from home path
-pub fn get_metadata_env_file() -> String {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    Path::new(&home)
-        .join(DEFAULT_ENV_FILE)
-        .to_str()
-        .unwrap()
-        .to_string()
+    /// get static assets config, None if function has no static assets
+    pub fn get_static(&self) -> Option<MetadataStatic> {
+        self.deploy.clone().unwrap_or_default().static_assets
+    }
+
+    /// get paths of other function projects served together
+    pub fn get_functions(&self) -> Vec<String> {
+        self.functions
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| f.path)
+            .collect()
+    }
+
+    /// get resource profile name, empty means default profile
+    pub fn get_resource(&self) -> String {
+        self.deploy
+            .clone()
+            .unwrap_or_default()
+            .resource
+            .unwrap_or_default()
+    }
+
+    /// get environment variables
+    pub fn get_envs(&self) -> Vec<(String, String)> {
+        self.env.clone().unwrap_or_default().into_iter().collect()
+    }
+
+    /// get custom error pages as pairs of error code and page file
+    pub fn get_error_pages(&self) -> Vec<(String, String)> {
+        self.error_pages
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// get compression switches
+    pub fn get_compression(&self) -> MetadataCompression {
+        self.compression.clone().unwrap_or_default()
+    }
+
+    /// get execution limits
+    pub fn get_limits(&self) -> MetadataLimits {
+        self.limits.clone().unwrap_or_default()
+    }
+
+    /// get storage of fetch response cache, empty means cache is disabled
+    pub fn get_fetch_cache(&self) -> String {
+        self.fetch
+            .clone()
+            .unwrap_or_default()
+            .cache
+            .unwrap_or_default()
+    }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// MetadataEnv is the credential of the cloud saved by `moss-cli auth`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MetadataEnv {
     pub api_key: String,
     pub api_secret: String,
     pub api_secret_expires: u64,
     pub api_host: String,
     pub created_at: u64,
+    /// login key '<access_token>:<secret_token>' to login again when api secret expires
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub login_key: String,
+    /// username or email of password login, password is prompted to login again
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub username: String,
 }
 
 impl MetadataEnv {
-    pub fn to_file(&self, path: &str) -> Result<()> {
-        // use bincode
-        let content = bincode::serialize(&self)?;
-        std::fs::write(path, content)?;
-        Ok(())
-    }
-    pub fn from_file(path: &str) -> Result<Self> {
-        let content = std::fs::read(path)?;
-        let env: MetadataEnv = bincode::deserialize(&content)?;
-        Ok(env)
+    /// is_expired checks if api secret is expired, zero expires means never expired
+    pub fn is_expired(&self) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.api_secret_expires > 0 && self.api_secret_expires < now
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// parse parses metadata with the given sections after required fields
+    fn parse(sections: &str) -> Metadata {
+        let content = format!(
+            r#"
+manifest = "v1"
+name = "rust-basic"
+description = ""
+authors = []
+language = "rust"
+{sections}"#
+        );
+        Metadata::from_binary(content.as_bytes()).unwrap()
+    }
+
     /// test manifest from_file
     #[test]
     fn from_file() {
@@ -170,44 +410,143 @@ mod tests {
             manifest.build.as_ref().unwrap().rust_target_dir,
             Some("./target".to_string())
         );
+
+        let manifest = Metadata::from_dir(Path::new("../tests/data")).unwrap();
+        assert_eq!(
+            manifest.build.as_ref().unwrap().rust_target_dir,
+            Some("../tests/data/./target".to_string())
+        );
+    }
+
+    /// test deploy section
+    #[test]
+    fn deploy() {
+        let manifest = parse(
+            r#"
+[deploy]
+trigger = "http"
+resource = "large"
+
+[[deploy.routes]]
+path = "/api/*path"
+methods = ["GET", "POST"]
+strip_prefix = "/api"
+
+[[deploy.routes]]
+path = "/*path"
+host = "*.example.com"
+"#,
+        );
+        assert_eq!(manifest.get_resource(), "large");
+        let routes = manifest.get_routes();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].methods, ["GET", "POST"]);
+        assert_eq!(routes[0].strip_prefix.as_deref(), Some("/api"));
+        assert_eq!(routes[1].host.as_deref(), Some("*.example.com"));
+    }
+
+    /// test env section
+    #[test]
+    fn envs() {
+        let manifest = parse(
+            r#"
+[env]
+APP_MODE = "test"
+"#,
+        );
+        assert_eq!(
+            manifest.get_envs(),
+            vec![("APP_MODE".to_string(), "test".to_string())]
+        );
+    }
+
+    /// test fetch section
+    #[test]
+    fn fetch_cache() {
+        assert_eq!(parse("").get_fetch_cache(), "");
+        let manifest = parse(
+            r#"
+[fetch]
+cache = "memory"
+"#,
+        );
+        assert_eq!(manifest.get_fetch_cache(), "memory");
+    }
+
+    /// test compression section
+    #[test]
+    fn compression() {
+        let manifest = parse(
+            r#"
+[compression]
+response = false
+"#,
+        );
+        let compression = manifest.get_compression();
+        assert!(!compression.response);
+        assert!(compression.request);
+        assert_eq!(compression.max_body_size, DEFAULT_MAX_BODY_SIZE);
+    }
+
+    /// test functions section
+    #[test]
+    fn functions() {
+        assert!(parse("").get_functions().is_empty());
+        let manifest = parse(
+            r#"
+[[functions]]
+path = "../rust-kv"
+"#,
+        );
+        assert_eq!(manifest.get_functions(), ["../rust-kv"]);
+    }
+
+    /// test static assets and error pages are resolved relative to project directory
+    #[test]
+    fn assets() {
+        let dir = Path::new("../tests/data/metadata/assets");
+        let manifest = Metadata::from_dir(dir).unwrap();
+        let assets = manifest.get_static().unwrap();
+        assert_eq!(assets.prefix.as_deref(), Some("/assets"));
+        assert_eq!(assets.dir, dir.join("public").to_str().unwrap());
+        assert!(Path::new(&assets.dir).join("index.html").exists());
+
+        let pages = manifest.get_error_pages();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].0, "trap");
+        assert!(Path::new(&pages[0].1).exists());
+    }
+
+    /// test limits section
+    #[test]
+    fn limits() {
+        assert_eq!(parse("").get_limits(), MetadataLimits::default());
+
+        let manifest = parse(
+            r#"
+[limits]
+timeout = 0
+"#,
+        );
+        let limits = manifest.get_limits();
+        assert_eq!(limits.timeout, 0);
+        assert_eq!(limits.pool_wait_timeout, DEFAULT_POOL_WAIT_TIMEOUT);
     }
 
     /// test manifest to file
     #[test]
     fn to_file() {
-        let manifest = Metadata::from_file("../tests/data/metadata.toml").unwrap();
-        manifest.to_file("../tests/data/metadata2.toml").unwrap();
-        let manifest2 = Metadata::from_file("../tests/data/metadata2.toml").unwrap();
+        let manifest = Metadata::from_file("../tests/data/metadata/assets/metadata.toml").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("metadata.toml");
+        manifest.to_file(file.to_str().unwrap()).unwrap();
+        let manifest2 = Metadata::from_file(file.to_str().unwrap()).unwrap();
         assert_eq!(manifest.manifest, manifest2.manifest);
         assert_eq!(manifest.name, manifest2.name);
         assert_eq!(manifest.description, manifest2.description);
         assert_eq!(manifest.authors, manifest2.authors);
         assert_eq!(manifest.language, manifest2.language);
-        assert_eq!(
-            manifest.build.as_ref().unwrap().rust_target_dir,
-            manifest2.build.as_ref().unwrap().rust_target_dir
-        );
-        std::fs::remove_file("../tests/data/metadata2.toml").unwrap();
-    }
-
-    /// test metadata env
-    #[test]
-    fn metadata_env() {
-        let env_data = MetadataEnv {
-            api_key: "api_key".to_string(),
-            api_secret: "api_secret".to_string(),
-            api_secret_expires: 123456789,
-            api_host: "api_host".to_string(),
-            created_at: 123456789,
-        };
-        env_data.to_file("../tests/data/metadata.env").unwrap();
-        let env_data2 = MetadataEnv::from_file("../tests/data/metadata.env").unwrap();
-        assert_eq!(env_data.api_key, env_data2.api_key);
-        assert_eq!(env_data.api_secret, env_data2.api_secret);
-        assert_eq!(env_data.api_secret_expires, env_data2.api_secret_expires);
-        assert_eq!(env_data.api_host, env_data2.api_host);
-        assert_eq!(env_data.created_at, env_data2.created_at);
-
-        std::fs::remove_file("../tests/data/metadata.env").unwrap();
+        assert_eq!(manifest.get_static(), manifest2.get_static());
+        assert_eq!(manifest.get_error_pages(), manifest2.get_error_pages());
     }
 }
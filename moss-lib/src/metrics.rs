@@ -0,0 +1,24 @@
+use anyhow::Result;
+use prometheus::{Encoder, TextEncoder};
+
+/// CONTENT_TYPE is the content type of prometheus text format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// render encodes metrics in default registry to prometheus text format
+pub fn render() -> Result<String> {
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn render_metrics() {
+        let counter =
+            prometheus::register_int_counter!("moss_test_total", "Counter for test").unwrap();
+        counter.inc();
+        let text = super::render().unwrap();
+        assert!(text.contains("moss_test_total 1"));
+    }
+}
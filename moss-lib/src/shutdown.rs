@@ -0,0 +1,97 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// DEFAULT_TIMEOUT is seconds to drain in-flight requests after shutdown signal
+pub const DEFAULT_TIMEOUT: u64 = 30;
+
+/// Signal is notified when SIGINT or SIGTERM is received, it can be cloned and awaited by several servers
+#[derive(Clone)]
+pub struct Signal(watch::Receiver<bool>);
+
+impl Signal {
+    /// listen starts waiting for SIGINT or SIGTERM in background
+    pub fn listen() -> Self {
+        let (tx, rx) = watch::channel(false);
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            info!("shutdown signal received");
+            let _ = tx.send(true);
+        });
+        Signal(rx)
+    }
+
+    /// recv resolves after shutdown signal is received
+    pub async fn recv(mut self) {
+        while !*self.0.borrow() {
+            if self.0.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut terminate = signal(SignalKind::terminate()).expect("listen SIGTERM failed");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    tokio::signal::ctrl_c().await.expect("listen ctrl-c failed");
+}
+
+/// drain runs graceful server until it ends. After shutdown signal, server is given timeout
+/// to finish in-flight requests, None is returned if it is not finished in time.
+pub async fn drain<F: Future>(server: F, signal: Signal, timeout: Duration) -> Option<F::Output> {
+    tokio::pin!(server);
+    tokio::select! {
+        res = &mut server => return Some(res),
+        _ = signal.recv() => {},
+    }
+    info!(?timeout, "draining in-flight requests");
+    match tokio::time::timeout(timeout, server).await {
+        Ok(res) => Some(res),
+        Err(_) => {
+            warn!("drain timeout, in-flight requests are dropped");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain_in_flight() {
+        let (tx, rx) = watch::channel(false);
+        let signal = Signal(rx);
+
+        // server stops accepting by signal, then finishes in-flight request
+        let server = |cost: u64| {
+            let signal = signal.clone();
+            async move {
+                signal.recv().await;
+                tokio::time::sleep(Duration::from_millis(cost)).await;
+                cost
+            }
+        };
+        let drained = tokio::spawn(drain(server(10), signal.clone(), Duration::from_secs(1)));
+        let dropped = tokio::spawn(drain(
+            server(1000),
+            signal.clone(),
+            Duration::from_millis(10),
+        ));
+        tx.send(true).unwrap();
+
+        assert_eq!(drained.await.unwrap(), Some(10));
+        assert_eq!(dropped.await.unwrap(), None);
+    }
+}
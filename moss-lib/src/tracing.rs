@@ -1,7 +1,93 @@
+use anyhow::{bail, Result};
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::sdk::trace::{self as sdktrace, Tracer, TracerProvider};
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use time::UtcOffset;
 use tracing_subscriber::fmt::time::OffsetTime;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
 
-pub fn init_tracing() {
+/// DEFAULT_TIMEZONE is the offset of log time if MOSS_LOG_TIMEZONE is not set
+pub const DEFAULT_TIMEZONE: &str = "+08:00";
+
+/// Config is the tracing config, read from environment variables
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// service_name is service.name of exported spans, from OTEL_SERVICE_NAME
+    pub service_name: String,
+    /// timezone of log time, "utc", "local" or offset like "+08:00", from MOSS_LOG_TIMEZONE
+    pub timezone: String,
+    /// json writes log lines in json if MOSS_LOG_FORMAT is "json"
+    pub json: bool,
+    /// otlp_endpoint is OTLP/HTTP collector to export spans, from OTEL_EXPORTER_OTLP_ENDPOINT,
+    /// empty disables export
+    pub otlp_endpoint: String,
+}
+
+impl Config {
+    /// from_env reads config from environment variables
+    pub fn from_env(service_name: &str) -> Self {
+        let var = |key: &str, default: &str| std::env::var(key).unwrap_or_else(|_| default.into());
+        Self {
+            service_name: var("OTEL_SERVICE_NAME", service_name),
+            timezone: var("MOSS_LOG_TIMEZONE", DEFAULT_TIMEZONE),
+            json: var("MOSS_LOG_FORMAT", "text") == "json",
+            otlp_endpoint: var("OTEL_EXPORTER_OTLP_ENDPOINT", ""),
+        }
+    }
+}
+
+/// parse_timezone parses "utc", "local" or offset like "+08:00" and "-05:30"
+pub fn parse_timezone(timezone: &str) -> Result<UtcOffset> {
+    let timezone = timezone.to_lowercase();
+    if timezone == "utc" {
+        return Ok(UtcOffset::UTC);
+    }
+    if timezone == "local" {
+        return Ok(UtcOffset::current_local_offset()?);
+    }
+    let (sign, offset) = if let Some(offset) = timezone.strip_prefix('+') {
+        (1, offset)
+    } else if let Some(offset) = timezone.strip_prefix('-') {
+        (-1, offset)
+    } else {
+        bail!("timezone '{timezone}' is invalid, use utc, local or offset like +08:00");
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let hours: i8 = hours.parse()?;
+    let minutes: i8 = minutes.parse()?;
+    Ok(UtcOffset::from_hms(sign * hours, sign * minutes, 0)?)
+}
+
+/// create_tracer creates tracer exporting spans to OTLP endpoint,
+/// spans are only used to propagate trace context if endpoint is empty
+fn create_tracer(config: &Config) -> Result<Tracer> {
+    let trace_config = sdktrace::config().with_resource(Resource::new(vec![KeyValue::new(
+        "service.name",
+        config.service_name.clone(),
+    )]));
+    if config.otlp_endpoint.is_empty() {
+        let provider = TracerProvider::builder().with_config(trace_config).build();
+        let tracer = provider.tracer("moss");
+        global::set_tracer_provider(provider);
+        return Ok(tracer);
+    }
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(config.otlp_endpoint.clone());
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(trace_config)
+        .install_batch(opentelemetry::runtime::Tokio)?;
+    Ok(tracer)
+}
+
+/// init_tracing installs log subscriber and trace exporter configured by environment variables
+pub fn init_tracing(service_name: &str) {
     if std::env::var("RUST_LOG").ok().is_none() {
         if cfg!(debug_assertions) {
             std::env::set_var("RUST_LOG", "debug")
@@ -10,17 +96,91 @@ pub fn init_tracing() {
         }
     }
 
+    init_tracing_with_config(&Config::from_env(service_name)).expect("init tracing failed");
+}
+
+/// init_tracing_with_config installs log subscriber and trace exporter
+pub fn init_tracing_with_config(config: &Config) -> Result<()> {
     let timer = OffsetTime::new(
-        time::UtcOffset::from_hms(8, 0, 0).unwrap(),
+        parse_timezone(&config.timezone)?,
         time::format_description::parse(
             "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]",
-        )
-        .unwrap(),
+        )?,
     );
 
-    tracing_subscriber::fmt()
-        .with_timer(timer)
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_target(false)
-        .init();
+    // trace context of incoming requests is propagated by w3c traceparent header
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otel = tracing_opentelemetry::layer().with_tracer(create_tracer(config)?);
+
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(otel);
+    if config.json {
+        registry
+            .with(fmt::layer().json().with_timer(timer).with_target(false))
+            .try_init()?;
+    } else {
+        registry
+            .with(fmt::layer().with_timer(timer).with_target(false))
+            .try_init()?;
+    }
+    Ok(())
+}
+
+/// shutdown_tracing exports remaining spans before exit
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn timezone() {
+        assert_eq!(parse_timezone("UTC").unwrap(), UtcOffset::UTC);
+        assert_eq!(parse_timezone("+08:00").unwrap().whole_hours(), 8);
+        let offset = parse_timezone("-05:30").unwrap();
+        assert_eq!(offset.as_hms(), (-5, -30, 0));
+        assert!(parse_timezone("08:00").is_err());
+        assert!(parse_timezone("+30:00").is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_to_collector() {
+        // collector stand-in records paths of received requests
+        let paths = Arc::new(Mutex::new(vec![]));
+        let recorded = paths.clone();
+        let make_svc = make_service_fn(move |_| {
+            let recorded = recorded.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    recorded.lock().unwrap().push(req.uri().path().to_string());
+                    async { Ok::<_, Infallible>(Response::new(Body::empty())) }
+                }))
+            }
+        });
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_svc);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let config = Config {
+            service_name: "moss-test".to_string(),
+            timezone: "utc".to_string(),
+            json: false,
+            otlp_endpoint: format!("http://{addr}"),
+        };
+        let otel = tracing_opentelemetry::layer().with_tracer(create_tracer(&config).unwrap());
+        let subscriber = tracing_subscriber::registry().with(otel);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("[Test]").in_scope(|| {});
+        });
+        tokio::task::spawn_blocking(shutdown_tracing).await.unwrap();
+
+        assert_eq!(*paths.lock().unwrap(), ["/v1/traces"]);
+    }
 }
@@ -0,0 +1,393 @@
+use crate::fetch_impl::http_fetch::{FetchOptionsV2, Request, ResponseV2};
+use crate::kv_impl::Provider;
+use crate::metrics::FETCH_CACHE;
+use moss_kv_service::MemoryKvStorage;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// CACHE_STATUS_HEADER is added to cached responses, value is hit, miss or revalidated
+pub const CACHE_STATUS_HEADER: &str = "x-moss-cache";
+
+/// KEY_PREFIX is the prefix of cache keys in kv storage
+const KEY_PREFIX: &str = "fetch-cache:";
+
+/// STALE_TTL is seconds to keep stale entries with validators for revalidation
+const STALE_TTL: u64 = 24 * 3600;
+
+/// MEMORY_MAX_SIZE is max bytes of entries in memory cache of a function
+pub const MEMORY_MAX_SIZE: usize = 32 * 1024 * 1024;
+
+/// CacheStatus tells guest how response is served
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    Revalidated,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Miss => "miss",
+            CacheStatus::Revalidated => "revalidated",
+        }
+    }
+}
+
+/// CacheControl is the parsed Cache-Control directives used by cache
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<u64>,
+}
+
+impl CacheControl {
+    fn parse(headers: &[(String, String)]) -> Self {
+        let mut cc = CacheControl::default();
+        for value in header_values(headers, "cache-control") {
+            for directive in value.split(',') {
+                let directive = directive.trim().to_lowercase();
+                let (name, arg) = directive.split_once('=').unwrap_or((&directive, ""));
+                match name {
+                    "no-store" => cc.no_store = true,
+                    "no-cache" => cc.no_cache = true,
+                    "max-age" => cc.max_age = arg.trim_matches('"').parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+        cc
+    }
+}
+
+fn header_values<'a>(
+    headers: &'a [(String, String)],
+    name: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    headers
+        .iter()
+        .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], name: &'a str) -> Option<&'a str> {
+    header_values(headers, name).next()
+}
+
+fn get_now_unixstamp() -> u64 {
+    let now = std::time::SystemTime::now();
+    now.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+}
+
+/// Entry is a cached response with its freshness lifetime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    url: String,
+    stored_at: u64,
+    max_age: u64,
+}
+
+impl Entry {
+    /// from_response creates entry if response is storable,
+    /// only 200 responses with freshness lifetime or validators are stored
+    fn from_response(response: &ResponseV2, now: u64) -> Option<Self> {
+        let cc = CacheControl::parse(&response.headers);
+        if response.status != 200
+            || cc.no_store
+            || header_value(&response.headers, "vary").is_some()
+        {
+            return None;
+        }
+        let entry = Entry {
+            status: response.status,
+            headers: response.headers.clone(),
+            body: response.body.clone().unwrap_or_default(),
+            url: response.url.clone(),
+            stored_at: now,
+            max_age: freshness_lifetime(&response.headers),
+        };
+        if entry.max_age == 0 && !entry.has_validators() {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// is_fresh returns whether entry can be served without revalidation
+    pub(crate) fn is_fresh(&self) -> bool {
+        get_now_unixstamp() < self.stored_at + self.max_age
+    }
+
+    fn has_validators(&self) -> bool {
+        header_value(&self.headers, "etag").is_some()
+            || header_value(&self.headers, "last-modified").is_some()
+    }
+
+    /// validators returns conditional request headers to revalidate entry
+    pub(crate) fn validators(&self) -> Vec<(String, String)> {
+        let mut headers = vec![];
+        if let Some(etag) = header_value(&self.headers, "etag") {
+            headers.push(("if-none-match".to_string(), etag.to_string()));
+        }
+        if let Some(modified) = header_value(&self.headers, "last-modified") {
+            headers.push(("if-modified-since".to_string(), modified.to_string()));
+        }
+        headers
+    }
+
+    /// refresh updates stored headers by headers of 304 response and restarts freshness lifetime
+    fn refresh(&mut self, headers: &[(String, String)], now: u64) {
+        for (key, _) in headers {
+            self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+        }
+        self.headers.extend(headers.iter().cloned());
+        self.stored_at = now;
+        self.max_age = freshness_lifetime(&self.headers);
+    }
+
+    /// expire is the unix time entry can be removed from kv storage
+    fn expire(&self) -> u64 {
+        if self.has_validators() {
+            self.stored_at + self.max_age + STALE_TTL
+        } else {
+            self.stored_at + self.max_age
+        }
+    }
+
+    pub(crate) fn into_response(self, status: CacheStatus) -> ResponseV2 {
+        let mut response = ResponseV2 {
+            status: self.status,
+            headers: self.headers,
+            body: Some(self.body),
+            url: self.url,
+        };
+        set_cache_status(&mut response, status);
+        response
+    }
+}
+
+/// freshness_lifetime is max-age minus Age of response, zero if it must be revalidated
+fn freshness_lifetime(headers: &[(String, String)]) -> u64 {
+    let cc = CacheControl::parse(headers);
+    if cc.no_cache {
+        return 0;
+    }
+    let age: u64 = header_value(headers, "age")
+        .and_then(|age| age.trim().parse().ok())
+        .unwrap_or(0);
+    cc.max_age.unwrap_or(0).saturating_sub(age)
+}
+
+/// set_cache_status sets cache status header of response
+pub(crate) fn set_cache_status(response: &mut ResponseV2, status: CacheStatus) {
+    response
+        .headers
+        .retain(|(k, _)| !k.eq_ignore_ascii_case(CACHE_STATUS_HEADER));
+    response
+        .headers
+        .push((CACHE_STATUS_HEADER.to_string(), status.as_str().to_string()));
+    FETCH_CACHE.with_label_values(&[status.as_str()]).inc();
+}
+
+/// is_cacheable returns whether request can be served from cache.
+/// Only GET requests without credentials, ranges or guest conditional headers are cached.
+pub(crate) fn is_cacheable(request: &Request) -> bool {
+    const BYPASS_HEADERS: [&str; 5] = [
+        "authorization",
+        "cookie",
+        "range",
+        "if-none-match",
+        "if-modified-since",
+    ];
+    request.method.eq_ignore_ascii_case("GET")
+        && !request
+            .headers
+            .iter()
+            .any(|(k, _)| BYPASS_HEADERS.iter().any(|h| k.eq_ignore_ascii_case(h)))
+        && !CacheControl::parse(&request.headers).no_store
+}
+
+/// must_revalidate returns whether request asks to revalidate fresh entry
+pub(crate) fn must_revalidate(request: &Request) -> bool {
+    let cc = CacheControl::parse(&request.headers);
+    cc.no_cache || cc.max_age == Some(0)
+}
+
+/// FetchCache caches responses of guest fetch calls, it is shared by all requests of a function.
+/// Storage of cache must not be reachable by guests, or they can forge cached responses.
+#[derive(Debug, Clone)]
+pub struct FetchCache {
+    storage: Provider,
+    /// namespace separates entries of functions sharing the storage
+    namespace: String,
+}
+
+impl FetchCache {
+    /// new creates cache saving entries of namespace to kv storage
+    pub fn new(storage: Provider, namespace: &str) -> Self {
+        FetchCache {
+            storage,
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// memory creates cache saving entries in memory, least recently used entries over max_size bytes are evicted
+    pub fn memory(max_size: usize) -> Self {
+        Self::new(
+            Arc::new(Mutex::new(MemoryKvStorage::with_max_size(max_size))),
+            "",
+        )
+    }
+
+    /// key is the cache key of request, responses differ by redirect and decompress options
+    pub(crate) fn key(&self, request: &Request, options: &FetchOptionsV2) -> String {
+        format!(
+            "{KEY_PREFIX}{}:{}:{}:{}",
+            self.namespace, options.redirect as u8, options.decompress as u8, request.uri
+        )
+    }
+
+    /// get returns entry of key, expired entries are removed
+    pub(crate) async fn get(&self, key: &str) -> Option<Entry> {
+        let mut storage = self.storage.lock().await;
+        let value = storage.get(key.to_string()).await.ok()?;
+        if value.1 > 0 && get_now_unixstamp() > value.1 {
+            let _ = storage.delete(key.to_string()).await;
+            return None;
+        }
+        bincode::deserialize(&value.0).ok()
+    }
+
+    /// put saves response if it is storable, or removes stale entry of key
+    pub(crate) async fn put(&self, key: &str, response: &ResponseV2) {
+        match Entry::from_response(response, get_now_unixstamp()) {
+            Some(entry) => self.save(key, &entry).await,
+            None => {
+                let _ = self.storage.lock().await.delete(key.to_string()).await;
+            }
+        }
+    }
+
+    /// revalidated refreshes entry by 304 response and returns the cached response
+    pub(crate) async fn revalidated(
+        &self,
+        key: &str,
+        mut entry: Entry,
+        not_modified: &ResponseV2,
+    ) -> ResponseV2 {
+        entry.refresh(&not_modified.headers, get_now_unixstamp());
+        self.save(key, &entry).await;
+        entry.into_response(CacheStatus::Revalidated)
+    }
+
+    async fn save(&self, key: &str, entry: &Entry) {
+        let data = match bincode::serialize(entry) {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("serialize cache entry failed: {e}");
+                return;
+            }
+        };
+        // entries over size limit of storage are not cached
+        if let Err(e) = self
+            .storage
+            .lock()
+            .await
+            .set(key.to_string(), (data, entry.expire()))
+            .await
+        {
+            debug!("save cache entry failed: {e:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_cache_control() {
+        let cc = CacheControl::parse(&headers(&[
+            ("Cache-Control", "public, max-age=60"),
+            ("cache-control", "No-Cache"),
+        ]));
+        assert_eq!(
+            cc,
+            CacheControl {
+                no_store: false,
+                no_cache: true,
+                max_age: Some(60),
+            }
+        );
+        assert_eq!(freshness_lifetime(&headers(&[("age", "10")])), 0);
+        let lifetime =
+            freshness_lifetime(&headers(&[("cache-control", "max-age=60"), ("age", "10")]));
+        assert_eq!(lifetime, 50);
+
+        let response = |pairs: &[(&str, &str)]| ResponseV2 {
+            status: 200,
+            headers: headers(pairs),
+            body: None,
+            url: String::new(),
+        };
+        assert!(Entry::from_response(&response(&[("cache-control", "max-age=60")]), 0).is_some());
+        assert!(Entry::from_response(&response(&[("etag", "\"v1\"")]), 0).is_some());
+        assert!(Entry::from_response(&response(&[]), 0).is_none());
+        assert!(Entry::from_response(&response(&[("cache-control", "no-store")]), 0).is_none());
+        let vary = response(&[("cache-control", "max-age=60"), ("vary", "accept")]);
+        assert!(Entry::from_response(&vary, 0).is_none());
+    }
+
+    #[tokio::test]
+    async fn isolate_and_expire_entries() {
+        let storage: Provider = Arc::new(Mutex::new(MemoryKvStorage::new()));
+        let (a, b) = (
+            FetchCache::new(storage.clone(), "a"),
+            FetchCache::new(storage.clone(), "b"),
+        );
+        let request = Request {
+            method: "GET".to_string(),
+            uri: "http://example.com/".to_string(),
+            headers: vec![],
+            body: None,
+        };
+        let options = FetchOptionsV2::default();
+        let response = ResponseV2 {
+            status: 200,
+            headers: headers(&[("cache-control", "max-age=60")]),
+            body: Some(b"a".to_vec()),
+            url: request.uri.clone(),
+        };
+
+        // functions sharing storage do not read entries of each other
+        let key = a.key(&request, &options);
+        assert_ne!(key, b.key(&request, &options));
+        a.put(&key, &response).await;
+        assert!(a.get(&key).await.is_some());
+        assert!(b.get(&b.key(&request, &options)).await.is_none());
+
+        // expired entry is removed from storage on read
+        let value = storage.lock().await.get(key.clone()).await.unwrap();
+        storage
+            .lock()
+            .await
+            .set(key.clone(), (value.0, 1))
+            .await
+            .unwrap();
+        assert!(a.get(&key).await.is_none());
+        assert!(storage.lock().await.get(key).await.is_err());
+    }
+}
@@ -4,16 +4,32 @@ wasmtime::component::bindgen!({
     async: true,
 });
 
-use http_fetch::{FetchError, FetchOptions, RedirectPolicy, Request, Response};
-use reqwest::redirect;
-use std::str::FromStr;
+use crate::fetch_cache::{self, CacheStatus, FetchCache};
+use crate::metrics::FETCH_CALLS;
+use futures::{stream, StreamExt};
+use http_fetch::{
+    FetchError, FetchOptions, FetchOptionsV2, RedirectPolicy, Request, Response, ResponseV2,
+};
+use once_cell::sync::OnceCell;
+use opentelemetry::global;
+use opentelemetry_http::HeaderInjector;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{redirect, Client, Method, Proxy, Url};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
 use tracing::{debug, instrument, warn};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
-impl Default for FetchOptions {
+/// DEFAULT_TIMEOUT is the fetch timeout in milliseconds if guest sets 0
+pub const DEFAULT_TIMEOUT: u32 = 30_000;
+
+impl Default for FetchOptionsV2 {
     fn default() -> Self {
-        FetchOptions {
-            timeout: 30,
+        FetchOptionsV2 {
+            timeout: DEFAULT_TIMEOUT,
             redirect: RedirectPolicy::Follow,
+            decompress: false,
         }
     }
 }
@@ -21,99 +37,780 @@ impl Default for FetchOptions {
 pub struct FetchImpl {
     pub req_id: u64,
     pub counter: u16,
+    /// cache of responses, None disables caching
+    pub cache: Option<FetchCache>,
 }
 
 impl FetchImpl {
     pub fn new(req_id: u64) -> Self {
-        FetchImpl { req_id, counter: 0 }
+        FetchImpl {
+            req_id,
+            counter: 0,
+            cache: None,
+        }
     }
 }
 
-impl TryFrom<http_fetch::RedirectPolicy> for redirect::Policy {
-    type Error = anyhow::Error;
-    fn try_from(value: http_fetch::RedirectPolicy) -> Result<Self, Self::Error> {
+/// fetch-options of previous bindings has timeout in seconds and never decompresses.
+/// This is the only place seconds are converted, the host handles milliseconds after it.
+impl From<FetchOptions> for FetchOptionsV2 {
+    fn from(options: FetchOptions) -> Self {
+        FetchOptionsV2 {
+            timeout: options.timeout.saturating_mul(1000),
+            redirect: options.redirect,
+            decompress: false,
+        }
+    }
+}
+
+impl From<ResponseV2> for Response {
+    fn from(response: ResponseV2) -> Self {
+        Response {
+            status: response.status,
+            headers: response.headers,
+            body: response.body,
+        }
+    }
+}
+
+impl From<RedirectPolicy> for redirect::Policy {
+    fn from(value: RedirectPolicy) -> Self {
         match value {
-            http_fetch::RedirectPolicy::Follow => Ok(redirect::Policy::default()),
-            http_fetch::RedirectPolicy::Error => Ok(redirect::Policy::custom(|attempt| {
+            RedirectPolicy::Follow => redirect::Policy::default(),
+            RedirectPolicy::Error => redirect::Policy::custom(|attempt| {
                 attempt.error(anyhow::anyhow!("redirect policy is error"))
-            })),
-            http_fetch::RedirectPolicy::Manual => Ok(redirect::Policy::none()),
+            }),
+            RedirectPolicy::Manual => redirect::Policy::none(),
         }
     }
 }
 
-#[async_trait::async_trait]
-impl http_fetch::HttpFetch for FetchImpl {
-    #[instrument(skip_all, name = "[Fetch]", level = "debug", fields(req_id = self.req_id, counter = self.counter))]
-    async fn fetch(
-        &mut self,
+/// DEFAULT_MAX_IDLE_PER_HOST is the max idle connections kept alive for each host
+pub const DEFAULT_MAX_IDLE_PER_HOST: usize = 32;
+
+/// DEFAULT_FETCH_ALL_CONCURRENCY is the max requests of a fetch-all call in flight at once
+pub const DEFAULT_FETCH_ALL_CONCURRENCY: usize = 8;
+
+/// ClientConfig configures http clients shared by all fetch calls
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// max idle connections kept alive for each host
+    pub max_idle_per_host: usize,
+    /// proxy url of all outbound requests, empty to use system proxy settings
+    pub proxy: String,
+    /// user-agent header of requests not setting it
+    pub user_agent: String,
+    /// max requests of a fetch-all call in flight at once, others wait for them
+    pub fetch_all_concurrency: usize,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            max_idle_per_host: DEFAULT_MAX_IDLE_PER_HOST,
+            proxy: String::new(),
+            user_agent: format!("moss/{}", env!("CARGO_PKG_VERSION")),
+            fetch_all_concurrency: DEFAULT_FETCH_ALL_CONCURRENCY,
+        }
+    }
+}
+
+/// ClientPool holds one client for each redirect policy and decompress option,
+/// connections, dns results and tls sessions are reused by requests with same options
+pub struct ClientPool {
+    config: ClientConfig,
+    proxy: Option<Proxy>,
+    clients: Mutex<HashMap<(u8, bool), Client>>,
+}
+
+impl ClientPool {
+    pub fn new(config: ClientConfig) -> anyhow::Result<Self> {
+        let proxy = if config.proxy.is_empty() {
+            None
+        } else {
+            Some(Proxy::all(&config.proxy)?)
+        };
+        Ok(ClientPool {
+            config,
+            proxy,
+            clients: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// get returns the shared client of options, it is created on first use
+    pub fn get(&self, redirect: RedirectPolicy, decompress: bool) -> reqwest::Result<Client> {
+        let key = (redirect as u8, decompress);
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let mut builder = Client::builder()
+            .redirect(redirect.into())
+            .gzip(decompress)
+            .deflate(decompress)
+            .brotli(decompress)
+            .pool_max_idle_per_host(self.config.max_idle_per_host)
+            .user_agent(&self.config.user_agent);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        let client = builder.build()?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+}
+
+static CLIENT_POOL: OnceCell<ClientPool> = OnceCell::new();
+
+/// init_client_pool sets config of shared clients, it must be called before first fetch
+pub fn init_client_pool(config: ClientConfig) -> anyhow::Result<()> {
+    CLIENT_POOL
+        .set(ClientPool::new(config)?)
+        .map_err(|_| anyhow::anyhow!("client pool is already initialized"))
+}
+
+fn client_pool() -> &'static ClientPool {
+    CLIENT_POOL.get_or_init(|| ClientPool::new(ClientConfig::default()).unwrap())
+}
+
+/// observe counts fetch call by its outcome
+fn observe(result: &std::result::Result<ResponseV2, FetchError>) {
+    let outcome = match result {
+        Ok(_) => "ok",
+        Err(FetchError::Timeout) => "timeout",
+        Err(_) => "error",
+    };
+    FETCH_CALLS.with_label_values(&[outcome]).inc();
+}
+
+/// fetch_error converts reqwest error to FetchError
+fn fetch_error(e: &reqwest::Error) -> FetchError {
+    if e.is_timeout() {
+        FetchError::Timeout
+    } else if e.is_redirect() || e.is_builder() {
+        FetchError::InvalidRequest
+    } else {
+        FetchError::NetworkError
+    }
+}
+
+impl FetchImpl {
+    /// send serves cacheable requests from cache if enabled, others are sent to remote
+    async fn send(
+        &self,
+        mut request: Request,
+        options: FetchOptionsV2,
+    ) -> std::result::Result<ResponseV2, FetchError> {
+        let cache = match &self.cache {
+            Some(cache) if fetch_cache::is_cacheable(&request) => cache,
+            _ => return self.send_remote(request, options).await,
+        };
+        let key = cache.key(&request, &options);
+        let entry = cache.get(&key).await;
+        if let Some(entry) = &entry {
+            if entry.is_fresh() && !fetch_cache::must_revalidate(&request) {
+                debug!("cache hit");
+                return Ok(entry.clone().into_response(CacheStatus::Hit));
+            }
+            request.headers.extend(entry.validators());
+        }
+
+        let mut response = self.send_remote(request, options).await?;
+        match entry {
+            Some(entry) if response.status == 304 => {
+                debug!("cache revalidated");
+                return Ok(cache.revalidated(&key, entry, &response).await);
+            }
+            _ => cache.put(&key, &response).await,
+        }
+        fetch_cache::set_cache_status(&mut response, CacheStatus::Miss);
+        Ok(response)
+    }
+
+    /// send_all sends requests with at most concurrency of them in flight,
+    /// results keep the order of requests
+    async fn send_all(
+        &self,
+        requests: Vec<Request>,
+        options: FetchOptionsV2,
+        concurrency: usize,
+    ) -> Vec<std::result::Result<ResponseV2, FetchError>> {
+        stream::iter(requests)
+            .map(|request| self.send(request, options))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    async fn send_remote(
+        &self,
         request: Request,
-        options: FetchOptions,
-    ) -> anyhow::Result<std::result::Result<Response, FetchError>> {
-        debug!("{} {}", request.method, request.uri);
+        options: FetchOptionsV2,
+    ) -> std::result::Result<ResponseV2, FetchError> {
+        let url = match Url::parse(&request.uri) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url,
+            _ => return Err(FetchError::InvalidUrl),
+        };
+        let method = Method::from_bytes(request.method.as_bytes())
+            .map_err(|_| FetchError::InvalidRequest)?;
 
-        self.counter += 1;
+        let mut headers = HeaderMap::new();
+        for (key, value) in request.headers {
+            let key = HeaderName::from_bytes(key.as_bytes());
+            let value = HeaderValue::from_str(&value);
+            match (key, value) {
+                (Ok(key), Ok(value)) => headers.append(key, value),
+                _ => return Err(FetchError::InvalidRequest),
+            };
+        }
+
+        // propagate trace context of current request to remote service
+        let context = tracing::Span::current().context();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+        });
 
-        let fetch_body = match request.body {
-            Some(b) => b,
-            None => vec![],
-        };
-
-        let client = reqwest::Client::builder()
-            .redirect(options.redirect.try_into()?)
-            .build()?;
-        let fetch_response = match client
-            .request(
-                reqwest::Method::from_str(request.method.as_str()).unwrap(),
-                request.uri.clone(),
-            )
-            .timeout(std::time::Duration::from_secs(options.timeout as u64))
-            .body(reqwest::Body::from(fetch_body))
+        let timeout = if options.timeout == 0 {
+            DEFAULT_TIMEOUT
+        } else {
+            options.timeout
+        };
+        let client = client_pool()
+            .get(options.redirect, options.decompress)
+            .map_err(|e| fetch_error(&e))?;
+        let fetch_response = client
+            .request(method, url)
+            .timeout(Duration::from_millis(timeout as u64))
+            .headers(headers)
+            .body(request.body.unwrap_or_default())
             .send()
             .await
-        {
-            Ok(r) => r,
-            Err(e) => {
+            .map_err(|e| {
                 warn!("failed: {e}");
-                return Ok(Err(FetchError::InvalidRequest));
-            }
-        };
+                fetch_error(&e)
+            })?;
 
+        // each value of multi-valued headers is a separate pair
         let mut resp_headers = vec![];
         for (key, value) in fetch_response.headers() {
-            resp_headers.push((key.to_string(), value.to_str().unwrap().to_string()));
+            resp_headers.push((
+                key.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            ));
         }
         let status = fetch_response.status().as_u16();
-        let body = fetch_response.bytes().await?;
-        let resp = Response {
+        let url = fetch_response.url().to_string();
+        let body = fetch_response.bytes().await.map_err(|e| {
+            warn!("read body failed: {e}");
+            fetch_error(&e)
+        })?;
+        debug!("response: {}, len={}", status, body.len());
+        Ok(ResponseV2 {
             status,
             headers: resp_headers,
             body: Some(body.to_vec()),
-        };
-        debug!("response: {}, len={}", resp.status, body.len());
-        Ok(Ok(resp))
+            url,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl http_fetch::HttpFetch for FetchImpl {
+    async fn fetch(
+        &mut self,
+        request: Request,
+        options: FetchOptions,
+    ) -> anyhow::Result<std::result::Result<Response, FetchError>> {
+        let result = self.fetch_v2(request, options.into()).await?;
+        Ok(result.map(Response::from))
+    }
+
+    #[instrument(skip_all, name = "[Fetch]", level = "debug", fields(req_id = self.req_id, counter = self.counter))]
+    async fn fetch_v2(
+        &mut self,
+        request: Request,
+        options: FetchOptionsV2,
+    ) -> anyhow::Result<std::result::Result<ResponseV2, FetchError>> {
+        debug!("{} {}", request.method, request.uri);
+
+        self.counter += 1;
+
+        let result = self.send(request, options).await;
+        observe(&result);
+        Ok(result)
+    }
+
+    #[instrument(skip_all, name = "[Fetch]", level = "debug", fields(req_id = self.req_id, counter = self.counter))]
+    async fn fetch_all(
+        &mut self,
+        requests: Vec<Request>,
+        options: FetchOptionsV2,
+    ) -> anyhow::Result<Vec<std::result::Result<ResponseV2, FetchError>>> {
+        debug!("fetch all, count={}", requests.len());
+
+        self.counter = self.counter.saturating_add(requests.len() as u16);
+
+        let concurrency = client_pool().config.fetch_all_concurrency;
+        let results = self.send_all(requests, options, concurrency).await;
+        results.iter().for_each(observe);
+        Ok(results)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use http_fetch::HttpFetch;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, StatusCode};
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// GZIP_HELLO is "hello" in gzip
+    const GZIP_HELLO: [u8; 25] = [
+        31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 72, 205, 201, 201, 7, 0, 134, 166, 16, 54, 5, 0, 0, 0,
+    ];
+
+    async fn mock_response(req: hyper::Request<Body>) -> Result<hyper::Response<Body>, Infallible> {
+        let builder = hyper::Response::builder();
+        let resp = match req.uri().path() {
+            "/ok" => builder
+                .header("set-cookie", "a=1")
+                .header("set-cookie", "b=2")
+                .header("x-echo", req.headers()["x-test"].clone())
+                .body(Body::from("ok")),
+            "/agent" => builder.body(Body::from(
+                req.headers()["user-agent"].to_str().unwrap().to_string(),
+            )),
+            "/redirect" => builder
+                .status(StatusCode::FOUND)
+                .header("location", "/ok")
+                .body(Body::empty()),
+            "/slow" => {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                builder.body(Body::empty())
+            }
+            "/cache" => builder
+                .header("cache-control", "max-age=60")
+                .body(Body::from("cache")),
+            "/etag" if req.headers().get("if-none-match").is_some() => builder
+                .status(StatusCode::NOT_MODIFIED)
+                .header("etag", "\"v1\"")
+                .body(Body::empty()),
+            "/etag" => builder
+                .header("cache-control", "no-cache")
+                .header("etag", "\"v1\"")
+                .body(Body::from("etag")),
+            "/gzip" => builder
+                .header("content-encoding", "gzip")
+                .body(Body::from(GZIP_HELLO.to_vec())),
+            _ => builder.status(StatusCode::NOT_FOUND).body(Body::empty()),
+        };
+        Ok(resp.unwrap())
+    }
+
+    /// start_mock_server returns base url of local server and counter of accepted connections
+    fn start_mock_server() -> (String, Arc<AtomicUsize>) {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        let make_svc = make_service_fn(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok::<_, Infallible>(service_fn(mock_response)) }
+        });
+        let server = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_svc);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (format!("http://{addr}"), connections)
+    }
+
+    async fn fetch(
+        method: &str,
+        uri: String,
+        headers: Vec<(String, String)>,
+        options: FetchOptionsV2,
+    ) -> std::result::Result<ResponseV2, FetchError> {
+        let req = Request {
+            method: method.to_string(),
+            uri,
+            headers,
+            body: None,
+        };
+        FetchImpl::new(0).fetch_v2(req, options).await.unwrap()
+    }
 
     #[tokio::test]
-    async fn run_fetch_impl() {
-        use http_fetch::HttpFetch;
-        let mut fetch_impl = FetchImpl::new(0);
+    async fn fetch_response() {
+        let (base, _) = start_mock_server();
+        let headers = vec![("x-test".to_string(), "moss".to_string())];
+        let resp = fetch(
+            "GET",
+            format!("{base}/ok"),
+            headers,
+            FetchOptionsV2::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, Some(b"ok".to_vec()));
+        assert_eq!(resp.url, format!("{base}/ok"));
+        let values = |key: &str| -> Vec<String> {
+            resp.headers
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .collect()
+        };
+        assert_eq!(values("set-cookie"), ["a=1", "b=2"]);
+        assert_eq!(values("x-echo"), ["moss"]);
+    }
+
+    #[tokio::test]
+    async fn fetch_with_previous_bindings() {
+        let (base, _) = start_mock_server();
         let req = Request {
             method: "GET".to_string(),
-            uri: "https://www.rust-lang.org".to_string(),
+            uri: format!("{base}/gzip"),
             headers: vec![],
             body: None,
         };
-        let resp = fetch_impl
-            .fetch(req, FetchOptions::default())
+        let options = FetchOptions {
+            timeout: 1,
+            redirect: RedirectPolicy::Follow,
+        };
+        let resp = FetchImpl::new(0)
+            .fetch(req, options)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, Some(GZIP_HELLO.to_vec()));
+
+        let options = FetchOptionsV2::from(options);
+        assert_eq!(options.timeout, 1000);
+        assert!(!options.decompress);
+    }
+
+    #[tokio::test]
+    async fn fetch_timeout_in_seconds() {
+        let (base, _) = start_mock_server();
+        let req = || Request {
+            method: "GET".to_string(),
+            uri: format!("{base}/slow"),
+            headers: vec![],
+            body: None,
+        };
+        // 1 second of previous bindings outlasts the 500ms response, as 1ms would not
+        let options = FetchOptions {
+            timeout: 1,
+            redirect: RedirectPolicy::Follow,
+        };
+        let resp = FetchImpl::new(0)
+            .fetch(req(), options)
             .await
             .unwrap()
             .unwrap();
         assert_eq!(resp.status, 200);
+
+        let options = FetchOptionsV2 {
+            timeout: 1,
+            ..Default::default()
+        };
+        let result = FetchImpl::new(0).fetch_v2(req(), options).await.unwrap();
+        assert!(matches!(result, Err(FetchError::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn fetch_redirect() {
+        let (base, _) = start_mock_server();
+        let uri = format!("{base}/redirect");
+        let options = |redirect| FetchOptionsV2 {
+            redirect,
+            ..Default::default()
+        };
+        let headers = || vec![("x-test".to_string(), "moss".to_string())];
+
+        let resp = fetch(
+            "GET",
+            uri.clone(),
+            headers(),
+            options(RedirectPolicy::Follow),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.url, format!("{base}/ok"));
+
+        let resp = fetch(
+            "GET",
+            uri.clone(),
+            headers(),
+            options(RedirectPolicy::Manual),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status, 302);
+        assert_eq!(resp.url, uri);
+
+        let result = fetch("GET", uri, headers(), options(RedirectPolicy::Error)).await;
+        assert!(matches!(result, Err(FetchError::InvalidRequest)));
+    }
+
+    #[tokio::test]
+    async fn fetch_decompress() {
+        let (base, _) = start_mock_server();
+        for (decompress, body) in [(true, b"hello".to_vec()), (false, GZIP_HELLO.to_vec())] {
+            let options = FetchOptionsV2 {
+                decompress,
+                ..Default::default()
+            };
+            let resp = fetch("GET", format!("{base}/gzip"), vec![], options)
+                .await
+                .unwrap();
+            assert_eq!(resp.body, Some(body));
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_errors() {
+        let (base, _) = start_mock_server();
+        let options = FetchOptionsV2 {
+            timeout: 50,
+            ..Default::default()
+        };
+        let result = fetch("GET", format!("{base}/slow"), vec![], options).await;
+        assert!(matches!(result, Err(FetchError::Timeout)));
+
+        for uri in ["not a url", "ftp://127.0.0.1/file"] {
+            let result = fetch("GET", uri.to_string(), vec![], FetchOptionsV2::default()).await;
+            assert!(matches!(result, Err(FetchError::InvalidUrl)));
+        }
+
+        let result = fetch(
+            "BAD METHOD",
+            base.clone(),
+            vec![],
+            FetchOptionsV2::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(FetchError::InvalidRequest)));
+        let headers = vec![("bad key".to_string(), "value".to_string())];
+        let result = fetch("GET", base, headers, FetchOptionsV2::default()).await;
+        assert!(matches!(result, Err(FetchError::InvalidRequest)));
+
+        // nothing is listening on the port after listener is dropped
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let result = fetch(
+            "GET",
+            format!("http://{addr}"),
+            vec![],
+            FetchOptionsV2::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(FetchError::NetworkError)));
+    }
+
+    #[tokio::test]
+    async fn shared_client_pool() {
+        let (base, connections) = start_mock_server();
+        let config = ClientConfig {
+            user_agent: "moss-test".to_string(),
+            ..Default::default()
+        };
+        let pool = ClientPool::new(config).unwrap();
+        for _ in 0..3 {
+            let client = pool.get(RedirectPolicy::Follow, false).unwrap();
+            let resp = client.get(format!("{base}/agent")).send().await.unwrap();
+            assert_eq!(resp.text().await.unwrap(), "moss-test");
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // requests with other options are sent by another client
+        let client = pool.get(RedirectPolicy::Manual, false).unwrap();
+        client.get(format!("{base}/agent")).send().await.unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        let config = ClientConfig {
+            proxy: "not a url".to_string(),
+            ..Default::default()
+        };
+        assert!(ClientPool::new(config).is_err());
+    }
+
+    /// start_inflight_server returns base url of local server and peak number of requests in flight,
+    /// each request is held for a while so requests sent concurrently overlap
+    fn start_inflight_server() -> (String, Arc<AtomicUsize>) {
+        let inflight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let counters = (inflight, peak.clone());
+        let make_svc = make_service_fn(move |_| {
+            let (inflight, peak) = counters.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_| {
+                    let (inflight, peak) = (inflight.clone(), peak.clone());
+                    async move {
+                        let current = inflight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        inflight.fetch_sub(1, Ordering::SeqCst);
+                        Ok::<_, Infallible>(hyper::Response::new(Body::from("ok")))
+                    }
+                }))
+            }
+        });
+        let server = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_svc);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (format!("http://{addr}"), peak)
+    }
+
+    fn get_request(uri: String) -> Request {
+        Request {
+            method: "GET".to_string(),
+            uri,
+            headers: vec![],
+            body: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_all_results() {
+        let (base, _) = start_mock_server();
+        let requests = vec![
+            get_request(format!("{base}/cache")),
+            get_request("not a url".to_string()),
+            get_request(format!("{base}/gzip")),
+            get_request(format!("{base}/agent")),
+        ];
+
+        let mut fetch_impl = FetchImpl::new(0);
+        let results = fetch_impl
+            .fetch_all(requests, FetchOptionsV2::default())
+            .await
+            .unwrap();
+        assert_eq!(fetch_impl.counter, 4);
+
+        // results keep the order of requests
+        assert_eq!(results.len(), 4);
+        assert!(matches!(results[1], Err(FetchError::InvalidUrl)));
+        for i in [0, 2, 3] {
+            assert_eq!(results[i].as_ref().unwrap().status, 200);
+        }
+        assert_eq!(results[0].as_ref().unwrap().body, Some(b"cache".to_vec()));
+        assert_eq!(results[2].as_ref().unwrap().url, format!("{base}/gzip"));
+        let agent = results[3].as_ref().unwrap().body.clone().unwrap();
+        assert!(String::from_utf8(agent).unwrap().starts_with("moss/"));
+    }
+
+    #[tokio::test]
+    async fn fetch_all_concurrently() {
+        let (base, peak) = start_inflight_server();
+        let requests = |count: usize| -> Vec<Request> {
+            (0..count).map(|_| get_request(base.clone())).collect()
+        };
+
+        // whole batch is in flight at once under the default limit
+        let mut fetch_impl = FetchImpl::new(0);
+        let results = fetch_impl
+            .fetch_all(requests(4), FetchOptionsV2::default())
+            .await
+            .unwrap();
+        assert!(results.iter().all(|r| r.as_ref().unwrap().status == 200));
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+
+        // requests over the limit wait for others in flight
+        peak.store(0, Ordering::SeqCst);
+        let results = fetch_impl
+            .send_all(requests(6), FetchOptionsV2::default(), 2)
+            .await;
+        assert_eq!(results.len(), 6);
+        assert!(results.iter().all(|r| r.as_ref().unwrap().status == 200));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    /// cached_fetch returns status, body and cache status of response
+    async fn cached_fetch(
+        fetch_impl: &mut FetchImpl,
+        method: &str,
+        uri: String,
+        headers: Vec<(String, String)>,
+    ) -> (u16, Vec<u8>, Option<String>) {
+        let req = Request {
+            method: method.to_string(),
+            uri,
+            headers,
+            body: None,
+        };
+        let resp = fetch_impl
+            .fetch_v2(req, FetchOptionsV2::default())
+            .await
+            .unwrap()
+            .unwrap();
+        let status = resp
+            .headers
+            .iter()
+            .find(|(k, _)| k == fetch_cache::CACHE_STATUS_HEADER)
+            .map(|(_, v)| v.clone());
+        (resp.status, resp.body.unwrap(), status)
+    }
+
+    #[tokio::test]
+    async fn fetch_with_cache() {
+        let (base, _) = start_mock_server();
+        let mut fetch_impl = FetchImpl::new(0);
+        fetch_impl.cache = Some(FetchCache::memory(fetch_cache::MEMORY_MAX_SIZE));
+        let uri = |path: &str| format!("{base}{path}");
+        let status = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/cache"), vec![]).await,
+            (200, b"cache".to_vec(), status("miss"))
+        );
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/cache"), vec![]).await,
+            (200, b"cache".to_vec(), status("hit"))
+        );
+        let no_cache = vec![("cache-control".to_string(), "no-cache".to_string())];
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/cache"), no_cache)
+                .await
+                .2,
+            status("miss")
+        );
+
+        // entry without freshness is revalidated by etag
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/etag"), vec![])
+                .await
+                .2,
+            status("miss")
+        );
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/etag"), vec![]).await,
+            (200, b"etag".to_vec(), status("revalidated"))
+        );
+
+        // responses without cache headers and other methods are not cached
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/agent"), vec![])
+                .await
+                .2,
+            status("miss")
+        );
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "GET", uri("/agent"), vec![])
+                .await
+                .2,
+            status("miss")
+        );
+        assert_eq!(
+            cached_fetch(&mut fetch_impl, "POST", uri("/cache"), vec![])
+                .await
+                .2,
+            None
+        );
     }
 }
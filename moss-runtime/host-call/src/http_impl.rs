@@ -3,3 +3,57 @@ wasmtime::component::bindgen!({
     path: "../../wit/http-handler.wit",
     async:true,
 });
+
+use http_context::RequestContext;
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        RequestContext {
+            remote_addr: String::new(),
+            request_id: String::new(),
+            tls: false,
+            function_name: String::new(),
+            function_version: String::new(),
+            deadline: 0,
+        }
+    }
+}
+
+/// HttpContextImpl returns the request context of current invocation to guest
+#[derive(Default)]
+pub struct HttpContextImpl {
+    context: RequestContext,
+}
+
+impl HttpContextImpl {
+    pub fn new(context: RequestContext) -> Self {
+        HttpContextImpl { context }
+    }
+}
+
+#[async_trait::async_trait]
+impl http_context::HttpContext for HttpContextImpl {
+    async fn get_request_context(&mut self) -> anyhow::Result<RequestContext> {
+        Ok(self.context.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_request_context() {
+        use http_context::HttpContext;
+        let mut context_impl = HttpContextImpl::new(RequestContext {
+            remote_addr: "127.0.0.1:1234".to_string(),
+            request_id: "1".to_string(),
+            ..Default::default()
+        });
+        let context = context_impl.get_request_context().await.unwrap();
+        assert_eq!(context.remote_addr, "127.0.0.1:1234");
+        assert_eq!(context.request_id, "1");
+        assert!(!context.tls);
+        assert_eq!(context.deadline, 0);
+    }
+}
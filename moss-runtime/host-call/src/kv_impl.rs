@@ -4,6 +4,7 @@ wasmtime::component::bindgen!({
     async: true,
 });
 
+use crate::metrics::KV_OPS;
 use kv_storage::{Key, KvError, Pair, Value};
 use moss_kv_service as kv;
 use std::sync::Arc;
@@ -44,18 +45,27 @@ impl From<kv::KvError> for kv_storage::KvError {
     }
 }
 
+/// observe counts kv operation by its outcome
+fn observe<T>(op: &str, result: &Result<T, KvError>) {
+    let outcome = match result {
+        Ok(_) => "ok",
+        Err(KvError::KeyNotFound) => "not_found",
+        Err(_) => "error",
+    };
+    KV_OPS.with_label_values(&[op, outcome]).inc();
+}
+
 #[async_trait::async_trait]
 impl kv_storage::KvStorage for KvStorageImpl {
     async fn get(&mut self, k: Key) -> anyhow::Result<Result<Value, KvError>> {
         let mut store = self.storage.lock().await;
-        let value = match store.get(k).await {
-            Ok(v) => v,
-            Err(e) => return Ok(Err(e.into())),
+        let result: Result<Value, KvError> = match store.get(k).await {
+            Ok(value) if self.is_expired(value.1) => Err(KvError::KeyNotFound),
+            Ok(value) => Ok(value.0),
+            Err(e) => Err(e.into()),
         };
-        if self.is_expired(value.1) {
-            return Ok(Err(KvError::KeyNotFound));
-        }
-        Ok(Ok(value.0))
+        observe("get", &result);
+        Ok(result)
     }
     async fn set(&mut self, k: Key, v: Value, expire: u64) -> anyhow::Result<Result<(), KvError>> {
         let expire = if expire > 0 {
@@ -64,32 +74,31 @@ impl kv_storage::KvStorage for KvStorageImpl {
             0
         };
         let mut store = self.storage.lock().await;
-        match store.set(k, (v, expire)).await {
-            Ok(_) => return Ok(Ok(())),
-            Err(e) => return Ok(Err(e.into())),
-        }
+        let result = store.set(k, (v, expire)).await.map_err(KvError::from);
+        observe("set", &result);
+        Ok(result)
     }
     async fn delete(&mut self, k: Key) -> anyhow::Result<Result<(), KvError>> {
         let mut store = self.storage.lock().await;
-        match store.delete(k).await {
-            Ok(_) => return Ok(Ok(())),
-            Err(e) => return Ok(Err(e.into())),
-        }
+        let result = store.delete(k).await.map_err(KvError::from);
+        observe("delete", &result);
+        Ok(result)
     }
     async fn get_all(&mut self) -> anyhow::Result<Result<Vec<Pair>, KvError>> {
         let mut store = self.storage.lock().await;
-        let values = match store.get_all().await {
-            Ok(v) => v,
-            Err(e) => return Ok(Err(e.into())),
-        };
-        let mut pairs = Vec::new();
-        for (k, (v, expire)) in values {
-            if self.is_expired(expire) {
-                continue;
-            }
-            pairs.push((k, v));
-        }
-        Ok(Ok(pairs))
+        let result: Result<Vec<Pair>, KvError> = store
+            .get_all()
+            .await
+            .map(|values| {
+                values
+                    .into_iter()
+                    .filter(|(_, (_, expire))| !self.is_expired(*expire))
+                    .map(|(k, (v, _))| (k, v))
+                    .collect()
+            })
+            .map_err(KvError::from);
+        observe("get_all", &result);
+        Ok(result)
     }
 }
 
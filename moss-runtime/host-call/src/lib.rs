@@ -1,3 +1,6 @@
+pub mod fetch_cache;
 pub mod fetch_impl;
 pub mod http_impl;
 pub mod kv_impl;
+pub mod logging_impl;
+pub mod metrics;
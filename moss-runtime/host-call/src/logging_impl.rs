@@ -0,0 +1,185 @@
+wasmtime::component::bindgen!({
+    world:"logging",
+    path: "../../wit/logging.wit",
+    async: true,
+});
+
+use logging::Level;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info, instrument, trace, warn};
+
+/// OUTPUT_MAX_SIZE is the max bytes of stdout or stderr kept for one invocation
+pub const OUTPUT_MAX_SIZE: usize = 64 * 1024;
+
+#[derive(Default)]
+struct Output {
+    data: Vec<u8>,
+    /// dropped is bytes written over OUTPUT_MAX_SIZE
+    dropped: usize,
+}
+
+/// OutputBuffer collects guest stdout or stderr of one invocation, output over limit is dropped
+#[derive(Clone, Default)]
+pub struct OutputBuffer(Arc<Mutex<Output>>);
+
+impl OutputBuffer {
+    /// take returns collected bytes with count of dropped bytes and clears the buffer
+    pub fn take(&self) -> (Vec<u8>, usize) {
+        let output = std::mem::take(&mut *self.0.lock().unwrap());
+        (output.data, output.dropped)
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut output = self.0.lock().unwrap();
+        let kept = buf.len().min(OUTPUT_MAX_SIZE - output.data.len());
+        output.data.extend_from_slice(&buf[..kept]);
+        output.dropped += buf.len() - kept;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// LoggingImpl writes guest logs and captured stdio to host tracing, tagged with request id
+#[derive(Default)]
+pub struct LoggingImpl {
+    pub req_id: String,
+    stdout: OutputBuffer,
+    stderr: OutputBuffer,
+}
+
+impl LoggingImpl {
+    pub fn new(req_id: String) -> Self {
+        LoggingImpl {
+            req_id,
+            ..Default::default()
+        }
+    }
+
+    /// stdout returns the buffer to capture guest stdout
+    pub fn stdout(&self) -> OutputBuffer {
+        self.stdout.clone()
+    }
+
+    /// stderr returns the buffer to capture guest stderr
+    pub fn stderr(&self) -> OutputBuffer {
+        self.stderr.clone()
+    }
+
+    /// flush_output writes captured stdout as info and stderr as warn logs
+    #[instrument(skip_all, name = "[Guest]", fields(req_id = %self.req_id))]
+    pub fn flush_output(&self) {
+        let (stdout, dropped) = self.stdout.take();
+        for line in output_lines(&stdout) {
+            info!(stream = "stdout", "{line}");
+        }
+        if dropped > 0 {
+            warn!(stream = "stdout", dropped, "output over limit is dropped");
+        }
+        let (stderr, dropped) = self.stderr.take();
+        for line in output_lines(&stderr) {
+            warn!(stream = "stderr", "{line}");
+        }
+        if dropped > 0 {
+            warn!(stream = "stderr", dropped, "output over limit is dropped");
+        }
+    }
+}
+
+fn output_lines(output: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// GuestFields formats key/value fields of guest log as a json object,
+/// recorded as `fields` value of the log event so they stay queryable in json output
+struct GuestFields<'a>(&'a [(String, String)]);
+
+impl Serialize for GuestFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, value)))
+    }
+}
+
+impl fmt::Display for GuestFields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+#[async_trait::async_trait]
+impl logging::Logging for LoggingImpl {
+    #[instrument(skip_all, name = "[Guest]", fields(req_id = %self.req_id))]
+    async fn log(
+        &mut self,
+        level: Level,
+        message: String,
+        fields: Vec<(String, String)>,
+    ) -> anyhow::Result<()> {
+        let fields = (!fields.is_empty()).then(|| GuestFields(&fields).to_string());
+        let fields = fields.as_deref();
+        match level {
+            Level::Trace => trace!(fields, "{message}"),
+            Level::Debug => debug!(fields, "{message}"),
+            Level::Info => info!(fields, "{message}"),
+            Level::Warn => warn!(fields, "{message}"),
+            Level::Error => error!(fields, "{message}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn log_and_capture_output() {
+        use logging::Logging;
+        let mut logging_impl = LoggingImpl::new("1".to_string());
+        logging_impl
+            .log(
+                Level::Info,
+                "hello".to_string(),
+                vec![("user".to_string(), "moss".to_string())],
+            )
+            .await
+            .unwrap();
+        let fields = [
+            ("user".to_string(), "moss".to_string()),
+            ("quote".to_string(), "\"a\"".to_string()),
+        ];
+        assert_eq!(
+            GuestFields(&fields).to_string(),
+            r#"{"user":"moss","quote":"\"a\""}"#
+        );
+
+        let mut stdout = logging_impl.stdout();
+        stdout.write_all(b"line1\n\nline2").unwrap();
+        let (output, dropped) = logging_impl.stdout.take();
+        assert_eq!(output_lines(&output), ["line1", "line2"]);
+        assert_eq!(dropped, 0);
+        assert!(logging_impl.stdout.take().0.is_empty());
+
+        // output over limit is dropped and counted
+        let mut stderr = logging_impl.stderr();
+        for _ in 0..3 {
+            stderr.write_all(&[b'x'; OUTPUT_MAX_SIZE / 2 + 1]).unwrap();
+        }
+        let (output, dropped) = logging_impl.stderr.take();
+        assert_eq!(output.len(), OUTPUT_MAX_SIZE);
+        assert_eq!(dropped, OUTPUT_MAX_SIZE / 2 + 3);
+        logging_impl.flush_output();
+    }
+}
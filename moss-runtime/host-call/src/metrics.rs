@@ -0,0 +1,32 @@
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+
+/// FETCH_CALLS counts guest fetch calls by outcome
+pub static FETCH_CALLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moss_fetch_calls_total",
+        "Guest fetch calls by outcome",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+/// KV_OPS counts guest kv operations by operation and outcome
+pub static KV_OPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moss_kv_ops_total",
+        "Guest kv operations by operation and outcome",
+        &["op", "outcome"]
+    )
+    .unwrap()
+});
+
+/// FETCH_CACHE counts cacheable guest fetch calls by cache status
+pub static FETCH_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moss_fetch_cache_total",
+        "Cacheable guest fetch calls by cache status",
+        &["status"]
+    )
+    .unwrap()
+});
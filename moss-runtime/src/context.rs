@@ -1,12 +1,19 @@
+use moss_host_call::fetch_cache::FetchCache;
 use moss_host_call::fetch_impl::FetchImpl;
+use moss_host_call::http_impl::http_context::RequestContext;
+use moss_host_call::http_impl::HttpContextImpl;
 use moss_host_call::kv_impl::{KvStorageImpl, Provider};
+use moss_host_call::logging_impl::LoggingImpl;
 use wasi_cap_std_sync::WasiCtxBuilder;
+use wasi_common::pipe::WritePipe;
 use wasi_host::WasiCtx;
 
 pub struct Context {
     wasi: WasiCtx,
     fetch_impl: FetchImpl,
     kv_storage: KvStorageImpl,
+    http_context: HttpContextImpl,
+    logging: LoggingImpl,
 }
 
 impl Default for Context {
@@ -17,13 +24,38 @@ impl Default for Context {
 
 impl Context {
     pub fn new(kv_provider: Option<Provider>) -> Self {
+        Self::new_with_envs(kv_provider, &[])
+    }
+    /// create context with environment variables passed to wasi
+    pub fn new_with_envs(kv_provider: Option<Provider>, envs: &[(String, String)]) -> Self {
         let provider = kv_provider.unwrap_or_else(|| super::KV_STORAGE.clone());
+        // guest stdout and stderr are captured per invocation, not mixed with other requests
+        let logging = LoggingImpl::default();
+        let wasi = WasiCtxBuilder::new()
+            .stdout(Box::new(WritePipe::new(logging.stdout())))
+            .stderr(Box::new(WritePipe::new(logging.stderr())))
+            .envs(envs)
+            .build();
         Context {
-            wasi: WasiCtxBuilder::new().inherit_stdio().build(),
+            wasi,
             fetch_impl: FetchImpl::new(1),
             kv_storage: KvStorageImpl::new(provider),
+            http_context: HttpContextImpl::default(),
+            logging,
         }
     }
+    /// set request context returned to guest, logs and fetch calls are tagged with its request id
+    pub fn with_request_context(mut self, context: RequestContext) -> Self {
+        self.fetch_impl.req_id = context.request_id.parse().unwrap_or_default();
+        self.logging.req_id = context.request_id.clone();
+        self.http_context = HttpContextImpl::new(context);
+        self
+    }
+    /// set cache of fetch responses, None disables cache
+    pub fn with_fetch_cache(mut self, cache: Option<FetchCache>) -> Self {
+        self.fetch_impl.cache = cache;
+        self
+    }
     /// get wasi
     pub fn wasi(&mut self) -> &mut WasiCtx {
         &mut self.wasi
@@ -36,4 +68,12 @@ impl Context {
     pub fn kv_storage(&mut self) -> &mut KvStorageImpl {
         &mut self.kv_storage
     }
+    /// get http context impl
+    pub fn http_context(&mut self) -> &mut HttpContextImpl {
+        &mut self.http_context
+    }
+    /// get logging impl
+    pub fn logging(&mut self) -> &mut LoggingImpl {
+        &mut self.logging
+    }
 }
@@ -1,14 +1,41 @@
 pub mod compiler;
 pub mod context;
+pub mod metrics;
 pub mod pool;
 pub mod worker;
 
+use anyhow::{bail, Result};
+use moss_host_call::fetch_cache::{self, FetchCache};
 /// create global kv provider
 use moss_host_call::kv_impl::Provider;
-use moss_kv_service::MemoryKvStorage;
+use moss_kv_service::{KvError, MemoryKvStorage};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 // KV_STORAGE is a global kv
 static KV_STORAGE: Lazy<Provider> = Lazy::new(|| Arc::new(Mutex::new(MemoryKvStorage::new())));
+
+// FETCH_CACHE_STORAGE is the kv of fetch caches, it is not reachable by guests
+static FETCH_CACHE_STORAGE: Lazy<Provider> = Lazy::new(|| {
+    Arc::new(Mutex::new(MemoryKvStorage::with_max_size(
+        fetch_cache::MEMORY_MAX_SIZE,
+    )))
+});
+
+/// flush_kv persists pending writes of global kv before shutdown
+pub async fn flush_kv() -> Result<(), KvError> {
+    KV_STORAGE.lock().await.flush().await
+}
+
+/// create_fetch_cache creates fetch response cache of function by storage name,
+/// "memory" keeps responses in memory of function, "kv" saves them to fetch cache kv
+/// in namespace of function, empty disables cache
+pub fn create_fetch_cache(storage: &str, function: &str) -> Result<Option<FetchCache>> {
+    match storage {
+        "" => Ok(None),
+        "memory" => Ok(Some(FetchCache::memory(fetch_cache::MEMORY_MAX_SIZE))),
+        "kv" => Ok(Some(FetchCache::new(FETCH_CACHE_STORAGE.clone(), function))),
+        _ => bail!("fetch cache '{storage}' is invalid, use memory or kv"),
+    }
+}
@@ -0,0 +1,47 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram, register_int_counter_vec, register_int_gauge, Histogram, IntCounterVec,
+    IntGauge,
+};
+
+/// HTTP_REQUESTS counts function requests by response status
+pub static HTTP_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moss_http_requests_total",
+        "Function requests by response status",
+        &["status"]
+    )
+    .unwrap()
+});
+
+/// INSTANTIATE_SECONDS is the time to instantiate component for each request
+pub static INSTANTIATE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "moss_instantiate_seconds",
+        "Time to instantiate component for each request"
+    )
+    .unwrap()
+});
+
+/// EXECUTION_SECONDS is the time of guest handling each request
+pub static EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "moss_execution_seconds",
+        "Time of guest handling each request"
+    )
+    .unwrap()
+});
+
+/// POOL_SIZE is the number of workers created in pool
+pub static POOL_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("moss_pool_size", "Number of workers created in pool").unwrap()
+});
+
+/// POOL_WAITERS is the number of requests waiting for an available worker
+pub static POOL_WAITERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "moss_pool_waiters",
+        "Number of requests waiting for an available worker"
+    )
+    .unwrap()
+});
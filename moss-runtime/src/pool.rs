@@ -1,35 +1,96 @@
-use crate::worker::Worker;
+use crate::metrics::{POOL_SIZE, POOL_WAITERS};
+use crate::worker::{Options, Worker};
 use anyhow::Result;
 use async_trait::async_trait;
-use deadpool::managed;
+use deadpool::{managed, Runtime};
+use prometheus::IntGauge;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 use tokio::time::Instant;
 use tracing::{debug, debug_span};
 
+/// PoolMetrics are gauges of pool, workers of all pools are summed in global gauges
+#[derive(Debug, Clone)]
+pub struct PoolMetrics {
+    size: IntGauge,
+    waiters: IntGauge,
+}
+
+impl Default for PoolMetrics {
+    fn default() -> Self {
+        Self {
+            size: POOL_SIZE.clone(),
+            waiters: POOL_WAITERS.clone(),
+        }
+    }
+}
+
+/// PooledWorker is a worker counted in pool size until it is dropped,
+/// including when pool is closed or dropped with the worker
+pub struct PooledWorker {
+    worker: Worker,
+    size: IntGauge,
+}
+
+impl std::fmt::Debug for PooledWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledWorker").finish_non_exhaustive()
+    }
+}
+
+impl Deref for PooledWorker {
+    type Target = Worker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.worker
+    }
+}
+
+impl DerefMut for PooledWorker {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.worker
+    }
+}
+
+impl Drop for PooledWorker {
+    fn drop(&mut self) {
+        self.size.dec();
+    }
+}
+
 #[derive(Debug)]
 pub struct Manager {
     path: String,
+    options: Options,
+    metrics: PoolMetrics,
 }
 
 impl Manager {
-    pub fn new(path: &str) -> Self {
+    pub fn new(path: &str, options: Options) -> Self {
         Self {
             path: String::from(path),
+            options,
+            metrics: PoolMetrics::default(),
         }
     }
 }
 
 #[async_trait]
 impl managed::Manager for Manager {
-    type Type = Worker;
+    type Type = PooledWorker;
     type Error = anyhow::Error;
 
     async fn create(&self) -> Result<Self::Type, Self::Error> {
         let start_time = Instant::now();
-        let worker = Worker::new(&self.path).await?;
+        let worker = Worker::new_with_options(&self.path, self.options.clone()).await?;
         debug_span!("[Worker]", path = &self.path).in_scope(|| {
             debug!(eplased = ?start_time.elapsed(), "create, ok");
         });
-        Ok(worker)
+        self.metrics.size.inc();
+        Ok(PooledWorker {
+            worker,
+            size: self.metrics.size.clone(),
+        })
     }
 
     async fn recycle(&self, _obj: &mut Self::Type) -> managed::RecycleResult<Self::Error> {
@@ -39,16 +100,61 @@ impl managed::Manager for Manager {
 
 pub type WorkerPool = managed::Pool<Manager>;
 
+/// PoolError is the error of getting worker from pool
+pub type PoolError = managed::PoolError<anyhow::Error>;
+
 /// create a pool
 pub fn create(path: &str) -> Result<WorkerPool> {
-    let mgr = Manager::new(path);
-    Ok(managed::Pool::builder(mgr).build().unwrap())
+    create_with_options(path, Options::default(), None)
+}
+
+/// create a pool with function options,
+/// getting worker fails with PoolError::Timeout after waiting wait_timeout, None waits forever
+pub fn create_with_options(
+    path: &str,
+    options: Options,
+    wait_timeout: Option<Duration>,
+) -> Result<WorkerPool> {
+    let mgr = Manager::new(path, options);
+    Ok(managed::Pool::builder(mgr)
+        .wait_timeout(wait_timeout)
+        .runtime(Runtime::Tokio1)
+        .build()
+        .unwrap())
+}
+
+/// Waiter counts a request waiting for worker in waiters gauge until it is dropped,
+/// including when waiting is cancelled
+struct Waiter(IntGauge);
+
+impl Waiter {
+    fn new(waiters: &IntGauge) -> Self {
+        waiters.inc();
+        Self(waiters.clone())
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// get gets a worker from pool, counting the request in waiters gauge while waiting
+pub async fn get(pool: &WorkerPool) -> Result<managed::Object<Manager>, PoolError> {
+    let _waiter = Waiter::new(&pool.manager().metrics.waiters);
+    pool.get().await
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{Manager, Options, PoolError, PoolMetrics, WorkerPool};
+    use deadpool::managed::TimeoutType;
+    use deadpool::Runtime;
     use moss_host_call::http_impl::http_handler::Request;
-    
+    use prometheus::IntGauge;
+    use std::time::Duration;
+
     #[tokio::test]
     async fn run_worker_pool_test() {
         let wasm_file = "../tests/data/rust_basic.component.wasm";
@@ -89,4 +195,67 @@ mod tests {
         assert_eq!(status.size, 1);
         assert_eq!(status.available, 1);
     }
+
+    #[tokio::test]
+    async fn wait_timeout() {
+        let wasm_file = "../tests/data/rust_basic.component.wasm";
+        let pool = WorkerPool::builder(Manager::new(wasm_file, Options::default()))
+            .max_size(1)
+            .wait_timeout(Some(Duration::from_millis(50)))
+            .runtime(Runtime::Tokio1)
+            .build()
+            .unwrap();
+
+        let worker = pool.get().await.unwrap();
+        let err = pool.get().await.unwrap_err();
+        assert!(matches!(err, PoolError::Timeout(TimeoutType::Wait)));
+        drop(worker);
+        assert!(pool.get().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn pool_metrics() {
+        let wasm_file = "../tests/data/rust_basic.component.wasm";
+        let metrics = PoolMetrics {
+            size: IntGauge::new("size", "size").unwrap(),
+            waiters: IntGauge::new("waiters", "waiters").unwrap(),
+        };
+        let mgr = Manager {
+            metrics: metrics.clone(),
+            ..Manager::new(wasm_file, Options::default())
+        };
+        let pool = WorkerPool::builder(mgr)
+            .max_size(1)
+            .runtime(Runtime::Tokio1)
+            .build()
+            .unwrap();
+
+        let worker = super::get(&pool).await.unwrap();
+        assert_eq!(metrics.size.get(), 1);
+        let waiting = super::get(&pool);
+        tokio::pin!(waiting);
+        let timeout = Duration::from_millis(50);
+        assert!(tokio::time::timeout(timeout, &mut waiting).await.is_err());
+        assert_eq!(metrics.waiters.get(), 1);
+
+        drop(worker);
+        let worker = waiting.await.unwrap();
+        assert_eq!(metrics.waiters.get(), 0);
+
+        // workers are uncounted when pool is closed or dropped
+        drop(worker);
+        pool.close();
+        assert_eq!(metrics.size.get(), 0);
+
+        let mgr = Manager {
+            metrics: metrics.clone(),
+            ..Manager::new(wasm_file, Options::default())
+        };
+        let pool = WorkerPool::builder(mgr).build().unwrap();
+        let worker = super::get(&pool).await.unwrap();
+        assert_eq!(metrics.size.get(), 1);
+        drop(pool);
+        drop(worker);
+        assert_eq!(metrics.size.get(), 0);
+    }
 }
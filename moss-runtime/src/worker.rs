@@ -1,29 +1,76 @@
 use crate::context::Context;
+use crate::metrics::{EXECUTION_SECONDS, INSTANTIATE_SECONDS};
 use anyhow::Result;
+use moss_host_call::fetch_cache::FetchCache;
 use moss_host_call::fetch_impl;
 use moss_host_call::http_impl;
+use moss_host_call::http_impl::http_context::RequestContext;
 use moss_host_call::kv_impl;
+use moss_host_call::logging_impl;
+use std::time::Duration;
+use tracing::instrument;
 use wasmtime::component::{Component, InstancePre, Linker};
-use wasmtime::{Config, Engine, Store};
+use wasmtime::{Config, Engine, Store, Trap};
 
-fn create_wasmtime_config() -> Config {
+/// EPOCH_TICK is the interval of checking execution deadline in guest code
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+fn create_wasmtime_config(options: &Options) -> Config {
     let mut config = Config::new();
     config.wasm_component_model(true);
     config.async_support(true);
+    config.epoch_interruption(options.timeout.is_some());
     config
 }
 
+/// Options is the function level options applied to each request
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// envs are passed to wasi environment variables
+    pub envs: Vec<(String, String)>,
+    /// fetch_cache is shared by all requests to cache fetch responses, None disables cache
+    pub fetch_cache: Option<FetchCache>,
+    /// timeout is the max execution time of a request, None means no limit
+    pub timeout: Option<Duration>,
+}
+
+/// ErrorKind is the category of error returned by handle_request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Trap is guest trap or other failure of execution
+    Trap,
+    /// Timeout is execution interrupted by deadline
+    Timeout,
+    /// LimitExceeded is execution running out of fuel or stack
+    LimitExceeded,
+}
+
+/// error_kind returns category of error returned by handle_request
+pub fn error_kind(err: &anyhow::Error) -> ErrorKind {
+    match err.downcast_ref::<Trap>() {
+        Some(Trap::Interrupt) => ErrorKind::Timeout,
+        Some(Trap::OutOfFuel) | Some(Trap::StackOverflow) => ErrorKind::LimitExceeded,
+        _ if err.is::<tokio::time::error::Elapsed>() => ErrorKind::Timeout,
+        _ => ErrorKind::Trap,
+    }
+}
+
 pub struct Worker {
     _path: String,
     engine: Engine,
     // component: Component,
     instance_pre: InstancePre<Context>,
+    options: Options,
 }
 
 impl Worker {
     pub async fn new(path: &str) -> Result<Self> {
+        Self::new_with_options(path, Options::default()).await
+    }
+
+    pub async fn new_with_options(path: &str, options: Options) -> Result<Self> {
         // create component
-        let config = create_wasmtime_config();
+        let config = create_wasmtime_config(&options);
         let engine = Engine::new(&config)?;
         let component = Component::from_file(&engine, path)?;
 
@@ -32,6 +79,8 @@ impl Worker {
         wasi_host::add_to_linker(&mut linker, Context::wasi)?;
         fetch_impl::http_fetch::add_to_linker(&mut linker, Context::fetch_impl)?;
         kv_impl::kv_storage::add_to_linker(&mut linker, Context::kv_storage)?;
+        http_impl::http_context::add_to_linker(&mut linker, Context::http_context)?;
+        logging_impl::logging::add_to_linker(&mut linker, Context::logging)?;
 
         // create instance_pre
         let instance_pre = linker.instantiate_pre(&component)?;
@@ -40,6 +89,7 @@ impl Worker {
             _path: path.to_string(),
             engine,
             instance_pre,
+            options,
         };
 
         Ok(worker)
@@ -48,25 +98,72 @@ impl Worker {
     pub async fn handle_request(
         &mut self,
         req: http_impl::http_handler::Request<'_>,
+    ) -> Result<http_impl::http_handler::Response> {
+        self.handle_request_with_context(req, RequestContext::default())
+            .await
+    }
+
+    /// handle request with request context, guest can read it by http-context import
+    #[instrument(skip_all, name = "[Worker]")]
+    pub async fn handle_request_with_context(
+        &mut self,
+        req: http_impl::http_handler::Request<'_>,
+        request_context: RequestContext,
     ) -> Result<http_impl::http_handler::Response> {
         // create store
-        let mut store = Store::new(&self.engine, Context::new(None));
+        let context = Context::new_with_envs(None, &self.options.envs)
+            .with_fetch_cache(self.options.fetch_cache.clone())
+            .with_request_context(request_context);
+        let mut store = Store::new(&self.engine, context);
 
         // get exports and call handle_request
+        let resp = match self.options.timeout {
+            Some(timeout) => {
+                // guest code traps by epoch deadline, waiting in host calls by tokio timeout
+                store.set_epoch_deadline((timeout.as_millis() / EPOCH_TICK.as_millis()) as u64);
+                let engine = self.engine.clone();
+                let ticker = tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(EPOCH_TICK);
+                    interval.tick().await;
+                    loop {
+                        interval.tick().await;
+                        engine.increment_epoch();
+                    }
+                });
+                let call = Self::call_handle_request(&mut store, &self.instance_pre, req);
+                let resp = tokio::time::timeout(timeout, call).await;
+                ticker.abort();
+                resp.map_err(anyhow::Error::from).and_then(|resp| resp)
+            }
+            None => Self::call_handle_request(&mut store, &self.instance_pre, req).await,
+        };
+        // write captured guest output even if instantiating or the call fails
+        store.data_mut().logging().flush_output();
+        resp
+    }
+
+    async fn call_handle_request(
+        store: &mut Store<Context>,
+        instance_pre: &InstancePre<Context>,
+        req: http_impl::http_handler::Request<'_>,
+    ) -> Result<http_impl::http_handler::Response> {
+        let timer = INSTANTIATE_SECONDS.start_timer();
         let (exports, _instance) =
-            http_impl::HttpHandler::instantiate_pre(&mut store, &self.instance_pre).await?;
-        let resp = exports
-            .http_handler()
-            .call_handle_request(&mut store, req)
-            .await?;
-        Ok(resp)
+            http_impl::HttpHandler::instantiate_pre(&mut *store, instance_pre).await?;
+        timer.observe_duration();
+
+        let timer = EXECUTION_SECONDS.start_timer();
+        let resp = exports.http_handler().call_handle_request(store, req).await;
+        timer.observe_duration();
+        resp
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Worker;
+    use super::{error_kind, ErrorKind, Options, RequestContext, Worker};
     use moss_host_call::http_impl::http_handler::Request;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn run_wasm() {
@@ -97,4 +194,78 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn run_wasm_with_context() {
+        // component not importing http-context still works
+        let wasm_file = "../tests/data/rust_basic.component.wasm";
+        let mut worker = Worker::new(wasm_file).await.unwrap();
+        let headers: Vec<(&str, &str)> = vec![];
+        let req = Request {
+            method: "GET",
+            uri: "/abc",
+            headers: &headers,
+            body: None,
+        };
+        let context = RequestContext {
+            remote_addr: "127.0.0.1:1234".to_string(),
+            request_id: "1".to_string(),
+            ..Default::default()
+        };
+        let resp = worker
+            .handle_request_with_context(req, context)
+            .await
+            .unwrap();
+        assert_eq!(resp.status, 200);
+    }
+
+    #[tokio::test]
+    async fn link_previous_fetch_bindings() {
+        // component importing http-fetch of previous bindings,
+        // whose fetch-options has no decompress and response has no url
+        let wasm_file = "../tests/data/http_fetch_v1.component.wasm";
+        assert!(Worker::new(wasm_file).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn run_wasm_with_timeout() {
+        let wasm_file = "../tests/data/rust_basic.component.wasm";
+        let headers: Vec<(&str, &str)> = vec![];
+        let req = || Request {
+            method: "GET",
+            uri: "/abc",
+            headers: &headers,
+            body: None,
+        };
+
+        let options = Options {
+            timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let mut worker = Worker::new_with_options(wasm_file, options).await.unwrap();
+        let resp = worker.handle_request(req()).await.unwrap();
+        assert_eq!(resp.status, 200);
+
+        // deadline shorter than one tick is reached when guest code starts
+        let options = Options {
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let mut worker = Worker::new_with_options(wasm_file, options).await.unwrap();
+        for _ in 0..2 {
+            let err = worker.handle_request(req()).await.unwrap_err();
+            assert_eq!(error_kind(&err), ErrorKind::Timeout);
+        }
+    }
+
+    #[test]
+    fn classify_error() {
+        let err = anyhow::Error::from(wasmtime::Trap::Interrupt);
+        assert_eq!(error_kind(&err), ErrorKind::Timeout);
+        let err = anyhow::Error::from(wasmtime::Trap::OutOfFuel).context("call handle_request");
+        assert_eq!(error_kind(&err), ErrorKind::LimitExceeded);
+        let err = anyhow::Error::from(wasmtime::Trap::UnreachableCodeReached);
+        assert_eq!(error_kind(&err), ErrorKind::Trap);
+        assert_eq!(error_kind(&anyhow::anyhow!("link failed")), ErrorKind::Trap);
+    }
 }
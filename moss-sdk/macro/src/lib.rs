@@ -15,7 +15,7 @@ pub fn http_main(_attr: TokenStream, item: TokenStream) -> TokenStream {
             fn handle_request(req: http_handler::Request) -> http_handler::Response {
                 #func
 
-                let http_req: Request = match req.try_into() {
+                let mut http_req: Request = match req.try_into() {
                     Ok(r) => r,
                     Err(e) => {
                         return http_handler::Response {
@@ -25,6 +25,17 @@ pub fn http_main(_attr: TokenStream, item: TokenStream) -> TokenStream {
                         }
                     }
                 };
+                let ctx = http_context::get_request_context();
+                http_req
+                    .extensions_mut()
+                    .insert(moss_sdk::http::RequestContext::from_host(
+                        &ctx.remote_addr,
+                        ctx.request_id,
+                        ctx.tls,
+                        ctx.function_name,
+                        ctx.function_version,
+                        ctx.deadline,
+                    ));
                 let http_resp = #func_name(http_req);
                 match http_resp.try_into() {
                     Ok(r) => r,
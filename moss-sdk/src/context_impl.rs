@@ -0,0 +1,66 @@
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// RequestContext is the metadata of current request provided by host,
+/// it is inserted into extensions of the Request
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// client address
+    pub remote_addr: Option<SocketAddr>,
+    /// unique id of the request, same as the id in host logs
+    pub request_id: String,
+    /// request is received over tls
+    pub tls: bool,
+    /// name of the function handling the request
+    pub function_name: String,
+    /// version of the function handling the request
+    pub function_version: String,
+    /// time the request must finish before
+    pub deadline: Option<SystemTime>,
+}
+
+impl RequestContext {
+    #[doc(hidden)]
+    pub fn from_host(
+        remote_addr: &str,
+        request_id: String,
+        tls: bool,
+        function_name: String,
+        function_version: String,
+        deadline: u64,
+    ) -> Self {
+        RequestContext {
+            remote_addr: remote_addr.parse().ok(),
+            request_id,
+            tls,
+            function_name,
+            function_version,
+            deadline: if deadline > 0 {
+                Some(UNIX_EPOCH + Duration::from_millis(deadline))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// remaining returns time left before deadline, None means no deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| {
+            deadline
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// RequestExt reads request context from Request
+pub trait RequestExt {
+    /// context returns the request context provided by host
+    fn context(&self) -> Option<&RequestContext>;
+}
+
+impl RequestExt for super::http::Request {
+    fn context(&self) -> Option<&RequestContext> {
+        self.extensions().get::<RequestContext>()
+    }
+}
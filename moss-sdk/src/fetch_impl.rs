@@ -5,52 +5,115 @@ use super::http::{Error, Request, Response};
 
 include!("../../wit/http-fetch.rs");
 
+/// FetchOptions controls how host sends the request
 pub struct FetchOptions {
+    /// timeout of the request in milliseconds, 0 uses host default 30 seconds
     pub timeout: u32,
+    /// decompress gzip, deflate and brotli response body, content-encoding header is removed
     pub decompress: bool,
+    /// redirect policy, follow redirects by default
+    pub redirect: RedirectPolicy,
 }
 
 impl Default for FetchOptions {
     fn default() -> Self {
         FetchOptions {
-            timeout: 30,
+            timeout: 30_000,
             decompress: false,
+            redirect: RedirectPolicy::Follow,
         }
     }
 }
 
-pub fn fetch(req: Request, options: FetchOptions) -> Result<Response, Error> {
-    // convert leaf_http::Request to http_fetch::Request
-    let mut headers = vec![];
-    for (key, value) in req.headers() {
-        headers.push((key.as_str(), value.to_str().unwrap()));
+/// FetchUrl is the final URL after redirects,
+/// it is inserted into extensions of the Response
+#[derive(Debug, Clone)]
+pub struct FetchUrl(pub String);
+
+/// ResponseExt reads fetch metadata from Response
+pub trait ResponseExt {
+    /// url returns the final URL after redirects
+    fn url(&self) -> Option<&str>;
+}
+
+impl ResponseExt for Response {
+    fn url(&self) -> Option<&str> {
+        self.extensions()
+            .get::<FetchUrl>()
+            .map(|url| url.0.as_str())
     }
-    let body = req.body().to_vec();
-    let uri = req.uri().clone().to_string();
-    let fetch_req = http_fetch::Request {
-        method: req.method().as_str(),
-        uri: uri.as_str(),
-        headers: &headers,
-        body: Some(body.as_slice()),
-    };
-
-    // set options
-    let opts = http_fetch::FetchOptions {
-        timeout: options.timeout,
-        redirect: http_fetch::RedirectPolicy::Follow,
-    };
-
-    // call host function to fetch
-    let fetch_resp = http_fetch::fetch(fetch_req, opts)?;
-
-    // convert http_fetch::Response to leaf_http::Response
+}
+
+impl From<FetchOptions> for http_fetch::FetchOptionsV2 {
+    fn from(options: FetchOptions) -> Self {
+        http_fetch::FetchOptionsV2 {
+            timeout: options.timeout,
+            redirect: options.redirect,
+            decompress: options.decompress,
+        }
+    }
+}
+
+/// FetchParts holds fields of Request borrowed by http_fetch::Request
+struct FetchParts<'a> {
+    method: &'a str,
+    uri: String,
+    headers: Vec<(&'a str, &'a str)>,
+    body: &'a [u8],
+}
+
+impl<'a> FetchParts<'a> {
+    fn new(req: &'a Request) -> Self {
+        let mut headers = vec![];
+        for (key, value) in req.headers() {
+            headers.push((key.as_str(), value.to_str().unwrap()));
+        }
+        FetchParts {
+            method: req.method().as_str(),
+            uri: req.uri().to_string(),
+            headers,
+            body: req.body(),
+        }
+    }
+
+    fn request(&self) -> http_fetch::Request<'_> {
+        http_fetch::Request {
+            method: self.method,
+            uri: self.uri.as_str(),
+            headers: &self.headers,
+            body: Some(self.body),
+        }
+    }
+}
+
+/// convert http_fetch::ResponseV2 to Response
+fn create_response(fetch_resp: http_fetch::ResponseV2) -> Response {
     let body = Bytes::from(fetch_resp.body.unwrap_or(vec![]));
-    let mut builder = httpResponse::builder().status(fetch_resp.status);
-    for (key, value) in headers {
+    let mut builder = httpResponse::builder()
+        .status(fetch_resp.status)
+        .extension(FetchUrl(fetch_resp.url));
+    // header appends value, so multi-valued headers like set-cookie are kept
+    for (key, value) in fetch_resp.headers {
         builder = builder.header(key, value);
     }
-    let resp = builder.body(body).unwrap();
-    Ok(resp)
+    builder.body(body).unwrap()
+}
+
+pub fn fetch(req: Request, options: FetchOptions) -> Result<Response, Error> {
+    let parts = FetchParts::new(&req);
+    let fetch_resp = http_fetch::fetch_v2(parts.request(), options.into())?;
+    Ok(create_response(fetch_resp))
+}
+
+/// fetch_all sends requests concurrently with same options,
+/// it returns result of each request in the order of requests
+pub fn fetch_all(reqs: Vec<Request>, options: FetchOptions) -> Vec<Result<Response, Error>> {
+    let parts: Vec<FetchParts> = reqs.iter().map(FetchParts::new).collect();
+    let fetch_reqs: Vec<http_fetch::Request> = parts.iter().map(FetchParts::request).collect();
+    http_fetch::fetch_all(&fetch_reqs, options.into())
+        .into_iter()
+        .map(|result| result.map(create_response))
+        .collect()
 }
 
 pub use http_fetch::FetchError;
@@ -1,3 +1,4 @@
+mod context_impl;
 mod fetch_impl;
 mod router_impl;
 
@@ -13,9 +14,12 @@ pub mod http {
         response
     }
 
+    pub use super::context_impl::{RequestContext, RequestExt};
     pub use super::fetch_impl::fetch;
+    pub use super::fetch_impl::fetch_all;
     pub use super::fetch_impl::FetchOptions;
     pub use super::fetch_impl::RedirectPolicy;
+    pub use super::fetch_impl::{FetchUrl, ResponseExt};
     pub type Error = super::fetch_impl::FetchError;
 
     pub mod router {
@@ -32,3 +36,14 @@ mod kv_impl;
 pub mod kv {
     pub use crate::kv_impl::*;
 }
+
+mod log_impl;
+
+/// Re-export log_impl as log, macros accept `info!("fmt", args)` or `info!(key = value; "fmt", args)`
+pub mod log {
+    pub use crate::log_impl::*;
+    pub use crate::{
+        __log_debug as debug, __log_error as error, __log_info as info, __log_trace as trace,
+        __log_warn as warn,
+    };
+}
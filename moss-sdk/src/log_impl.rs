@@ -0,0 +1,54 @@
+include!("../../wit/logging.rs");
+
+/// Level is the level of log record.
+pub use logging::Level;
+
+/// log writes a record with key-value fields to host logs, tagged with current request id.
+pub fn log(level: Level, message: &str, fields: &[(&str, &str)]) {
+    logging::log(level, message, fields)
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {
+        $crate::log::log(
+            $level,
+            &format!($($arg)+),
+            &[$((stringify!($key), $value.to_string().as_str())),+],
+        )
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log::log($level, &format!($($arg)+), &[])
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_trace {
+    ($($arg:tt)+) => { $crate::__log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_debug {
+    ($($arg:tt)+) => { $crate::__log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_info {
+    ($($arg:tt)+) => { $crate::__log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_warn {
+    ($($arg:tt)+) => { $crate::__log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_error {
+    ($($arg:tt)+) => { $crate::__log!($crate::log::Level::Error, $($arg)+) };
+}
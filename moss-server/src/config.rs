@@ -1,16 +1,22 @@
 use anyhow::Result;
-use moss_core_service::{DbConfig, StoreConfig};
+use moss_admin_service::AdminConfig;
+use moss_core_service::{DbConfig, SecretConfig, StoreConfig};
+use moss_rpc_service::AuthConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HttpConfig {
     pub addr: String,
+    /// shutdown_timeout is seconds to drain in-flight requests after SIGINT or SIGTERM
+    pub shutdown_timeout: u64,
 }
 
 impl Default for HttpConfig {
     fn default() -> Self {
         Self {
             addr: "127.0.0.1:8679".to_string(),
+            shutdown_timeout: moss_lib::shutdown::DEFAULT_TIMEOUT,
         }
     }
 }
@@ -20,6 +26,12 @@ pub struct Config {
     pub db: DbConfig,
     pub http: HttpConfig,
     pub store: StoreConfig,
+    #[serde(default)]
+    pub secret: SecretConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
 }
 
 impl Config {
@@ -1,18 +1,55 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use moss_lib::shutdown;
+use moss_migration::{Migrator, MigratorTrait};
 use std::path::Path;
-use tracing::{debug, error};
+use std::time::Duration;
+use tracing::{debug, error, info};
 
 mod config;
 
+/// moss-server serves rpc and admin api of functions.
+///
+/// Prometheus metrics are exposed at /metrics of admin address,
+/// admin api is enabled only when [admin] token is set in config file.
 #[derive(Parser, Debug)]
 struct CliArgs {
+    /// Config file, see etc/moss-server.toml
     #[clap(long, default_value("moss-server.toml"))]
     pub config: String,
+    #[clap(subcommand)]
+    pub cmd: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Migrate manages database schema
+    Migrate {
+        #[clap(subcommand)]
+        cmd: MigrateCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MigrateCommands {
+    /// Apply pending migrations
+    Up {
+        /// Number of pending migrations to apply, default is all
+        #[clap(long)]
+        steps: Option<u32>,
+    },
+    /// Rollback applied migrations
+    Down {
+        /// Number of applied migrations to rollback
+        #[clap(long, default_value("1"))]
+        steps: u32,
+    },
+    /// Show status of all migrations
+    Status,
 }
 
 #[tokio::main]
 async fn main() {
-    moss_lib::tracing::init_tracing();
+    moss_lib::tracing::init_tracing("moss-server");
 
     let args = CliArgs::parse();
     println!("{args:?}");
@@ -29,12 +66,57 @@ async fn main() {
 
     // init database
     moss_core_service::init_db(&config.db).await.unwrap();
+    let db = moss_core_service::DB.get().unwrap();
+
+    if let Some(Commands::Migrate { cmd }) = args.cmd {
+        let result = match cmd {
+            MigrateCommands::Up { steps } => Migrator::up(db, steps).await,
+            MigrateCommands::Down { steps } => Migrator::down(db, Some(steps)).await,
+            MigrateCommands::Status => Migrator::status(db).await,
+        };
+        if let Err(e) = result {
+            error!("Migrate failed: {}", e);
+        }
+        return;
+    }
+
+    // apply pending migrations
+    if config.db.auto_migrate {
+        Migrator::up(db, None).await.unwrap();
+        info!("database migrations are applied");
+    }
 
     // init function store
     moss_core_service::init_store(&config.store).unwrap();
 
+    // init secret cipher for function secrets
+    moss_core_service::init_secret(&config.secret).unwrap();
+
+    // servers stop accepting on SIGINT or SIGTERM, and drain in-flight requests until timeout
+    let signal = shutdown::Signal::listen();
+    let shutdown_timeout = Duration::from_secs(config.http.shutdown_timeout);
+
+    // start admin api server
+    let admin_config = config.admin;
+    let admin_signal = signal.clone();
+    let admin = tokio::spawn(async move {
+        let server = moss_admin_service::start(admin_config, admin_signal.clone().recv());
+        if let Some(Err(e)) = shutdown::drain(server, admin_signal, shutdown_timeout).await {
+            error!("Admin server failed: {}", e);
+        }
+    });
+
     // start rpc server
-    moss_rpc_service::start(config.http.addr.parse().unwrap())
-        .await
-        .unwrap();
+    let server = moss_rpc_service::start(
+        config.http.addr.parse().unwrap(),
+        config.auth,
+        signal.clone().recv(),
+    );
+    if let Some(result) = shutdown::drain(server, signal, shutdown_timeout).await {
+        result.unwrap();
+    }
+    let _ = admin.await;
+    info!("shutdown");
+
+    moss_lib::tracing::shutdown_tracing();
 }
@@ -44,6 +44,23 @@ pub mod http_fetch {
         }
     }
     #[derive(Clone)]
+    pub struct ResponseV2 {
+        pub status: HttpStatus,
+        pub headers: HttpHeadersResult,
+        pub body: Option<HttpBodyResult>,
+        pub url: wit_bindgen::rt::string::String,
+    }
+    impl core::fmt::Debug for ResponseV2 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("ResponseV2")
+                .field("status", &self.status)
+                .field("headers", &self.headers)
+                .field("body", &self.body)
+                .field("url", &self.url)
+                .finish()
+        }
+    }
+    #[derive(Clone)]
     pub struct Request<'a> {
         pub method: HttpMethod<'a>,
         pub uri: HttpUri<'a>,
@@ -74,6 +91,22 @@ pub mod http_fetch {
                 .finish()
         }
     }
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct FetchOptionsV2 {
+        pub timeout: u32,
+        pub redirect: RedirectPolicy,
+        pub decompress: bool,
+    }
+    impl core::fmt::Debug for FetchOptionsV2 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("FetchOptionsV2")
+                .field("timeout", &self.timeout)
+                .field("redirect", &self.redirect)
+                .field("decompress", &self.decompress)
+                .finish()
+        }
+    }
     #[repr(u8)]
     #[derive(Clone, Copy, PartialEq, Eq)]
     pub enum FetchError {
@@ -328,15 +361,504 @@ pub mod http_fetch {
             }
         }
     }
+    #[allow(clippy::all)]
+    pub fn fetch_v2(req: Request<'_>, options: FetchOptionsV2) -> Result<ResponseV2, FetchError> {
+        #[allow(unused_imports)]
+        use wit_bindgen::rt::{alloc, string::String, vec::Vec};
+        unsafe {
+            #[repr(align(4))]
+            struct RetArea([u8; 36]);
+            let mut ret_area = core::mem::MaybeUninit::<RetArea>::uninit();
+            let Request {
+                method: method0,
+                uri: uri0,
+                headers: headers0,
+                body: body0,
+            } = req;
+            let vec1 = method0;
+            let ptr1 = vec1.as_ptr() as i32;
+            let len1 = vec1.len() as i32;
+            let vec2 = uri0;
+            let ptr2 = vec2.as_ptr() as i32;
+            let len2 = vec2.len() as i32;
+            let vec6 = headers0;
+            let len6 = vec6.len() as i32;
+            let layout6 = alloc::Layout::from_size_align_unchecked(vec6.len() * 16, 4);
+            let result6 = if layout6.size() != 0 {
+                let ptr = alloc::alloc(layout6);
+                if ptr.is_null() {
+                    alloc::handle_alloc_error(layout6);
+                }
+                ptr
+            } else {
+                core::ptr::null_mut()
+            };
+            for (i, e) in vec6.into_iter().enumerate() {
+                let base = result6 as i32 + (i as i32) * 16;
+                {
+                    let (t3_0, t3_1) = e;
+                    let vec4 = t3_0;
+                    let ptr4 = vec4.as_ptr() as i32;
+                    let len4 = vec4.len() as i32;
+                    *((base + 4) as *mut i32) = len4;
+                    *((base + 0) as *mut i32) = ptr4;
+                    let vec5 = t3_1;
+                    let ptr5 = vec5.as_ptr() as i32;
+                    let len5 = vec5.len() as i32;
+                    *((base + 12) as *mut i32) = len5;
+                    *((base + 8) as *mut i32) = ptr5;
+                }
+            }
+            let (result8_0, result8_1, result8_2) = match body0 {
+                Some(e) => {
+                    let vec7 = e;
+                    let ptr7 = vec7.as_ptr() as i32;
+                    let len7 = vec7.len() as i32;
+
+                    (1i32, ptr7, len7)
+                }
+                None => (0i32, 0i32, 0i32),
+            };
+            let FetchOptionsV2 {
+                timeout: timeout9,
+                redirect: redirect9,
+                decompress: decompress9,
+            } = options;
+            let ptr10 = ret_area.as_mut_ptr() as i32;
+            #[link(wasm_import_module = "http-fetch")]
+            extern "C" {
+                #[cfg_attr(target_arch = "wasm32", link_name = "fetch-v2")]
+                #[cfg_attr(not(target_arch = "wasm32"), link_name = "http-fetch_fetch-v2")]
+                fn wit_import(
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                    _: i32,
+                );
+            }
+            wit_import(
+                ptr1,
+                len1,
+                ptr2,
+                len2,
+                result6 as i32,
+                len6,
+                result8_0,
+                result8_1,
+                result8_2,
+                wit_bindgen::rt::as_i32(timeout9),
+                match redirect9 {
+                    RedirectPolicy::Follow => 0,
+                    RedirectPolicy::Error => 1,
+                    RedirectPolicy::Manual => 2,
+                },
+                match decompress9 {
+                    true => 1,
+                    false => 0,
+                },
+                ptr10,
+            );
+            if layout6.size() != 0 {
+                alloc::dealloc(result6, layout6);
+            }
+            match i32::from(*((ptr10 + 0) as *const u8)) {
+                0 => Ok({
+                    let base13 = *((ptr10 + 8) as *const i32);
+                    let len13 = *((ptr10 + 12) as *const i32);
+                    let mut result13 = Vec::with_capacity(len13 as usize);
+                    for i in 0..len13 {
+                        let base = base13 + i * 16;
+                        result13.push({
+                            let len11 = *((base + 4) as *const i32) as usize;
+                            let len12 = *((base + 12) as *const i32) as usize;
+
+                            (
+                                {
+                                    #[cfg(not(debug_assertions))]
+                                    {
+                                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                                            *((base + 0) as *const i32) as *mut _,
+                                            len11,
+                                            len11,
+                                        ))
+                                    }
+                                    #[cfg(debug_assertions)]
+                                    {
+                                        String::from_utf8(Vec::from_raw_parts(
+                                            *((base + 0) as *const i32) as *mut _,
+                                            len11,
+                                            len11,
+                                        ))
+                                        .unwrap()
+                                    }
+                                },
+                                {
+                                    #[cfg(not(debug_assertions))]
+                                    {
+                                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                                            *((base + 8) as *const i32) as *mut _,
+                                            len12,
+                                            len12,
+                                        ))
+                                    }
+                                    #[cfg(debug_assertions)]
+                                    {
+                                        String::from_utf8(Vec::from_raw_parts(
+                                            *((base + 8) as *const i32) as *mut _,
+                                            len12,
+                                            len12,
+                                        ))
+                                        .unwrap()
+                                    }
+                                },
+                            )
+                        });
+                    }
+                    wit_bindgen::rt::dealloc(base13, (len13 as usize) * 16, 4);
+
+                    ResponseV2 {
+                        status: i32::from(*((ptr10 + 4) as *const u16)) as u16,
+                        headers: result13,
+                        body: match i32::from(*((ptr10 + 16) as *const u8)) {
+                            0 => None,
+                            1 => Some({
+                                let len14 = *((ptr10 + 24) as *const i32) as usize;
+
+                                Vec::from_raw_parts(
+                                    *((ptr10 + 20) as *const i32) as *mut _,
+                                    len14,
+                                    len14,
+                                )
+                            }),
+                            #[cfg(not(debug_assertions))]
+                            _ => core::hint::unreachable_unchecked(),
+                            #[cfg(debug_assertions)]
+                            _ => panic!("invalid enum discriminant"),
+                        },
+                        url: {
+                            let len15 = *((ptr10 + 32) as *const i32) as usize;
+                            {
+                                #[cfg(not(debug_assertions))]
+                                {
+                                    String::from_utf8_unchecked(Vec::from_raw_parts(
+                                        *((ptr10 + 28) as *const i32) as *mut _,
+                                        len15,
+                                        len15,
+                                    ))
+                                }
+                                #[cfg(debug_assertions)]
+                                {
+                                    String::from_utf8(Vec::from_raw_parts(
+                                        *((ptr10 + 28) as *const i32) as *mut _,
+                                        len15,
+                                        len15,
+                                    ))
+                                    .unwrap()
+                                }
+                            }
+                        },
+                    }
+                }),
+                1 => Err({
+                    #[cfg(debug_assertions)]
+                    {
+                        match i32::from(*((ptr10 + 4) as *const u8)) {
+                            0 => FetchError::NetworkError,
+                            1 => FetchError::Timeout,
+                            2 => FetchError::InvalidUrl,
+                            3 => FetchError::DestinationNotAllowed,
+                            4 => FetchError::TooManyRequests,
+                            5 => FetchError::InvalidRequest,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                    #[cfg(not(debug_assertions))]
+                    {
+                        core::mem::transmute::<_, FetchError>(
+                            i32::from(*((ptr10 + 4) as *const u8)) as u8,
+                        )
+                    }
+                }),
+                #[cfg(not(debug_assertions))]
+                _ => core::hint::unreachable_unchecked(),
+                #[cfg(debug_assertions)]
+                _ => panic!("invalid enum discriminant"),
+            }
+        }
+    }
+    #[allow(clippy::all)]
+    pub fn fetch_all(
+        reqs: &[Request<'_>],
+        options: FetchOptionsV2,
+    ) -> wit_bindgen::rt::vec::Vec<Result<ResponseV2, FetchError>> {
+        #[allow(unused_imports)]
+        use wit_bindgen::rt::{alloc, string::String, vec::Vec};
+        unsafe {
+            let mut cleanup_list = Vec::new();
+            #[repr(align(4))]
+            struct RetArea([u8; 8]);
+            let mut ret_area = core::mem::MaybeUninit::<RetArea>::uninit();
+            let vec9 = reqs;
+            let len9 = vec9.len() as i32;
+            let layout9 = alloc::Layout::from_size_align_unchecked(vec9.len() * 36, 4);
+            let result9 = if layout9.size() != 0 {
+                let ptr = alloc::alloc(layout9);
+                if ptr.is_null() {
+                    alloc::handle_alloc_error(layout9);
+                }
+                ptr
+            } else {
+                core::ptr::null_mut()
+            };
+            for (i, e) in vec9.into_iter().enumerate() {
+                let base = result9 as i32 + (i as i32) * 36;
+                {
+                    let Request {
+                        method: method0,
+                        uri: uri0,
+                        headers: headers0,
+                        body: body0,
+                    } = e;
+                    let vec1 = method0;
+                    let ptr1 = vec1.as_ptr() as i32;
+                    let len1 = vec1.len() as i32;
+                    *((base + 4) as *mut i32) = len1;
+                    *((base + 0) as *mut i32) = ptr1;
+                    let vec2 = uri0;
+                    let ptr2 = vec2.as_ptr() as i32;
+                    let len2 = vec2.len() as i32;
+                    *((base + 12) as *mut i32) = len2;
+                    *((base + 8) as *mut i32) = ptr2;
+                    let vec6 = headers0;
+                    let len6 = vec6.len() as i32;
+                    let layout6 = alloc::Layout::from_size_align_unchecked(vec6.len() * 16, 4);
+                    let result6 = if layout6.size() != 0 {
+                        let ptr = alloc::alloc(layout6);
+                        if ptr.is_null() {
+                            alloc::handle_alloc_error(layout6);
+                        }
+                        ptr
+                    } else {
+                        core::ptr::null_mut()
+                    };
+                    for (i, e) in vec6.into_iter().enumerate() {
+                        let base = result6 as i32 + (i as i32) * 16;
+                        {
+                            let (t3_0, t3_1) = e;
+                            let vec4 = t3_0;
+                            let ptr4 = vec4.as_ptr() as i32;
+                            let len4 = vec4.len() as i32;
+                            *((base + 4) as *mut i32) = len4;
+                            *((base + 0) as *mut i32) = ptr4;
+                            let vec5 = t3_1;
+                            let ptr5 = vec5.as_ptr() as i32;
+                            let len5 = vec5.len() as i32;
+                            *((base + 12) as *mut i32) = len5;
+                            *((base + 8) as *mut i32) = ptr5;
+                        }
+                    }
+                    *((base + 20) as *mut i32) = len6;
+                    *((base + 16) as *mut i32) = result6 as i32;
+                    match body0 {
+                        Some(e) => {
+                            *((base + 24) as *mut u8) = (1i32) as u8;
+                            let vec7 = e;
+                            let ptr7 = vec7.as_ptr() as i32;
+                            let len7 = vec7.len() as i32;
+                            *((base + 32) as *mut i32) = len7;
+                            *((base + 28) as *mut i32) = ptr7;
+                        }
+                        None => {
+                            *((base + 24) as *mut u8) = (0i32) as u8;
+                        }
+                    };
+                    cleanup_list.extend_from_slice(&[(result6, layout6)]);
+                }
+            }
+            let FetchOptionsV2 {
+                timeout: timeout10,
+                redirect: redirect10,
+                decompress: decompress10,
+            } = options;
+            let ptr11 = ret_area.as_mut_ptr() as i32;
+            #[link(wasm_import_module = "http-fetch")]
+            extern "C" {
+                #[cfg_attr(target_arch = "wasm32", link_name = "fetch-all")]
+                #[cfg_attr(not(target_arch = "wasm32"), link_name = "http-fetch_fetch-all")]
+                fn wit_import(_: i32, _: i32, _: i32, _: i32, _: i32, _: i32);
+            }
+            wit_import(
+                result9 as i32,
+                len9,
+                wit_bindgen::rt::as_i32(timeout10),
+                match redirect10 {
+                    RedirectPolicy::Follow => 0,
+                    RedirectPolicy::Error => 1,
+                    RedirectPolicy::Manual => 2,
+                },
+                match decompress10 {
+                    true => 1,
+                    false => 0,
+                },
+                ptr11,
+            );
+            let base18 = *((ptr11 + 0) as *const i32);
+            let len18 = *((ptr11 + 4) as *const i32);
+            let mut result18 = Vec::with_capacity(len18 as usize);
+            for i in 0..len18 {
+                let base = base18 + i * 36;
+                result18.push(match i32::from(*((base + 0) as *const u8)) {
+                    0 => Ok({
+                        let base14 = *((base + 8) as *const i32);
+                        let len14 = *((base + 12) as *const i32);
+                        let mut result14 = Vec::with_capacity(len14 as usize);
+                        for i in 0..len14 {
+                            let base = base14 + i * 16;
+                            result14.push({
+                                let len12 = *((base + 4) as *const i32) as usize;
+                                let len13 = *((base + 12) as *const i32) as usize;
+                                (
+                                    {
+                                        #[cfg(not(debug_assertions))]
+                                        {
+                                            String::from_utf8_unchecked(Vec::from_raw_parts(
+                                                *((base + 0) as *const i32) as *mut _,
+                                                len12,
+                                                len12,
+                                            ))
+                                        }
+                                        #[cfg(debug_assertions)]
+                                        {
+                                            String::from_utf8(Vec::from_raw_parts(
+                                                *((base + 0) as *const i32) as *mut _,
+                                                len12,
+                                                len12,
+                                            ))
+                                            .unwrap()
+                                        }
+                                    },
+                                    {
+                                        #[cfg(not(debug_assertions))]
+                                        {
+                                            String::from_utf8_unchecked(Vec::from_raw_parts(
+                                                *((base + 8) as *const i32) as *mut _,
+                                                len13,
+                                                len13,
+                                            ))
+                                        }
+                                        #[cfg(debug_assertions)]
+                                        {
+                                            String::from_utf8(Vec::from_raw_parts(
+                                                *((base + 8) as *const i32) as *mut _,
+                                                len13,
+                                                len13,
+                                            ))
+                                            .unwrap()
+                                        }
+                                    },
+                                )
+                            });
+                        }
+                        wit_bindgen::rt::dealloc(base14, (len14 as usize) * 16, 4);
+                        ResponseV2 {
+                            status: i32::from(*((base + 4) as *const u16)) as u16,
+                            headers: result14,
+                            body: match i32::from(*((base + 16) as *const u8)) {
+                                0 => None,
+                                1 => Some({
+                                    let len15 = *((base + 24) as *const i32) as usize;
+                                    Vec::from_raw_parts(
+                                        *((base + 20) as *const i32) as *mut _,
+                                        len15,
+                                        len15,
+                                    )
+                                }),
+                                #[cfg(not(debug_assertions))]
+                                _ => core::hint::unreachable_unchecked(),
+                                #[cfg(debug_assertions)]
+                                _ => panic!("invalid enum discriminant"),
+                            },
+                            url: {
+                                let len16 = *((base + 32) as *const i32) as usize;
+                                {
+                                    #[cfg(not(debug_assertions))]
+                                    {
+                                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                                            *((base + 28) as *const i32) as *mut _,
+                                            len16,
+                                            len16,
+                                        ))
+                                    }
+                                    #[cfg(debug_assertions)]
+                                    {
+                                        String::from_utf8(Vec::from_raw_parts(
+                                            *((base + 28) as *const i32) as *mut _,
+                                            len16,
+                                            len16,
+                                        ))
+                                        .unwrap()
+                                    }
+                                }
+                            },
+                        }
+                    }),
+                    1 => Err({
+                        #[cfg(debug_assertions)]
+                        {
+                            match i32::from(*((base + 4) as *const u8)) {
+                                0 => FetchError::NetworkError,
+                                1 => FetchError::Timeout,
+                                2 => FetchError::InvalidUrl,
+                                3 => FetchError::DestinationNotAllowed,
+                                4 => FetchError::TooManyRequests,
+                                5 => FetchError::InvalidRequest,
+                                _ => panic!("invalid enum discriminant"),
+                            }
+                        }
+                        #[cfg(not(debug_assertions))]
+                        {
+                            core::mem::transmute::<_, FetchError>(i32::from(
+                                *((base + 4) as *const u8),
+                            )
+                                as u8)
+                        }
+                    }),
+                    #[cfg(not(debug_assertions))]
+                    _ => core::hint::unreachable_unchecked(),
+                    #[cfg(debug_assertions)]
+                    _ => panic!("invalid enum discriminant"),
+                });
+            }
+            wit_bindgen::rt::dealloc(base18, (len18 as usize) * 36, 4);
+            if layout9.size() != 0 {
+                alloc::dealloc(result9, layout9);
+            }
+            for (ptr, layout) in cleanup_list {
+                if layout.size() != 0 {
+                    alloc::dealloc(ptr, layout);
+                }
+            }
+            result18
+        }
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
 #[link_section = "component-type:http-fetch"]
 #[doc(hidden)]
-pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 1142] = [
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 1506] = [
     2, 0, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 10, 104, 116, 116, 112, 45, 102, 101,
     116, 99, 104, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 0, 97, 115, 109, 12, 0, 1, 0,
-    7, 225, 7, 1, 65, 4, 1, 66, 25, 1, 109, 3, 6, 102, 111, 108, 108, 111, 119, 5, 101, 114, 114,
+    7, 205, 10, 1, 65, 4, 1, 66, 36, 1, 109, 3, 6, 102, 111, 108, 108, 111, 119, 5, 101, 114, 114,
     111, 114, 6, 109, 97, 110, 117, 97, 108, 4, 15, 114, 101, 100, 105, 114, 101, 99, 116, 45, 112,
     111, 108, 105, 99, 121, 0, 3, 0, 0, 1, 115, 4, 8, 104, 116, 116, 112, 45, 117, 114, 105, 0, 3,
     0, 2, 1, 123, 4, 11, 104, 116, 116, 112, 45, 115, 116, 97, 116, 117, 115, 0, 3, 0, 4, 1, 115,
@@ -344,50 +866,66 @@ pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 1142] = [
     1, 112, 8, 4, 12, 104, 116, 116, 112, 45, 104, 101, 97, 100, 101, 114, 115, 0, 3, 0, 9, 1, 112,
     125, 4, 9, 104, 116, 116, 112, 45, 98, 111, 100, 121, 0, 3, 0, 11, 1, 107, 12, 1, 114, 3, 6,
     115, 116, 97, 116, 117, 115, 5, 7, 104, 101, 97, 100, 101, 114, 115, 10, 4, 98, 111, 100, 121,
-    13, 4, 8, 114, 101, 115, 112, 111, 110, 115, 101, 0, 3, 0, 14, 1, 114, 4, 6, 109, 101, 116,
-    104, 111, 100, 7, 3, 117, 114, 105, 3, 7, 104, 101, 97, 100, 101, 114, 115, 10, 4, 98, 111,
-    100, 121, 13, 4, 7, 114, 101, 113, 117, 101, 115, 116, 0, 3, 0, 16, 1, 114, 2, 7, 116, 105,
-    109, 101, 111, 117, 116, 121, 8, 114, 101, 100, 105, 114, 101, 99, 116, 1, 4, 13, 102, 101,
-    116, 99, 104, 45, 111, 112, 116, 105, 111, 110, 115, 0, 3, 0, 18, 1, 109, 6, 13, 110, 101, 116,
-    119, 111, 114, 107, 45, 101, 114, 114, 111, 114, 7, 116, 105, 109, 101, 111, 117, 116, 11, 105,
-    110, 118, 97, 108, 105, 100, 45, 117, 114, 108, 23, 100, 101, 115, 116, 105, 110, 97, 116, 105,
-    111, 110, 45, 110, 111, 116, 45, 97, 108, 108, 111, 119, 101, 100, 17, 116, 111, 111, 45, 109,
-    97, 110, 121, 45, 114, 101, 113, 117, 101, 115, 116, 115, 15, 105, 110, 118, 97, 108, 105, 100,
-    45, 114, 101, 113, 117, 101, 115, 116, 4, 11, 102, 101, 116, 99, 104, 45, 101, 114, 114, 111,
-    114, 0, 3, 0, 20, 1, 106, 1, 15, 1, 21, 1, 64, 2, 3, 114, 101, 113, 17, 7, 111, 112, 116, 105,
-    111, 110, 115, 19, 0, 22, 4, 5, 102, 101, 116, 99, 104, 0, 1, 23, 4, 18, 104, 116, 116, 112,
-    45, 102, 101, 116, 99, 104, 45, 105, 109, 112, 111, 114, 116, 115, 34, 112, 107, 103, 58, 47,
-    104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99,
-    104, 45, 105, 109, 112, 111, 114, 116, 115, 5, 0, 1, 65, 2, 1, 66, 25, 1, 109, 3, 6, 102, 111,
-    108, 108, 111, 119, 5, 101, 114, 114, 111, 114, 6, 109, 97, 110, 117, 97, 108, 4, 15, 114, 101,
-    100, 105, 114, 101, 99, 116, 45, 112, 111, 108, 105, 99, 121, 0, 3, 0, 0, 1, 115, 4, 8, 104,
-    116, 116, 112, 45, 117, 114, 105, 0, 3, 0, 2, 1, 123, 4, 11, 104, 116, 116, 112, 45, 115, 116,
-    97, 116, 117, 115, 0, 3, 0, 4, 1, 115, 4, 11, 104, 116, 116, 112, 45, 109, 101, 116, 104, 111,
-    100, 0, 3, 0, 6, 1, 111, 2, 115, 115, 1, 112, 8, 4, 12, 104, 116, 116, 112, 45, 104, 101, 97,
-    100, 101, 114, 115, 0, 3, 0, 9, 1, 112, 125, 4, 9, 104, 116, 116, 112, 45, 98, 111, 100, 121,
-    0, 3, 0, 11, 1, 107, 12, 1, 114, 3, 6, 115, 116, 97, 116, 117, 115, 5, 7, 104, 101, 97, 100,
-    101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 4, 8, 114, 101, 115, 112, 111, 110, 115, 101, 0,
-    3, 0, 14, 1, 114, 4, 6, 109, 101, 116, 104, 111, 100, 7, 3, 117, 114, 105, 3, 7, 104, 101, 97,
-    100, 101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 4, 7, 114, 101, 113, 117, 101, 115, 116, 0,
-    3, 0, 16, 1, 114, 2, 7, 116, 105, 109, 101, 111, 117, 116, 121, 8, 114, 101, 100, 105, 114,
-    101, 99, 116, 1, 4, 13, 102, 101, 116, 99, 104, 45, 111, 112, 116, 105, 111, 110, 115, 0, 3, 0,
-    18, 1, 109, 6, 13, 110, 101, 116, 119, 111, 114, 107, 45, 101, 114, 114, 111, 114, 7, 116, 105,
-    109, 101, 111, 117, 116, 11, 105, 110, 118, 97, 108, 105, 100, 45, 117, 114, 108, 23, 100, 101,
-    115, 116, 105, 110, 97, 116, 105, 111, 110, 45, 110, 111, 116, 45, 97, 108, 108, 111, 119, 101,
-    100, 17, 116, 111, 111, 45, 109, 97, 110, 121, 45, 114, 101, 113, 117, 101, 115, 116, 115, 15,
-    105, 110, 118, 97, 108, 105, 100, 45, 114, 101, 113, 117, 101, 115, 116, 4, 11, 102, 101, 116,
-    99, 104, 45, 101, 114, 114, 111, 114, 0, 3, 0, 20, 1, 106, 1, 15, 1, 21, 1, 64, 2, 3, 114, 101,
-    113, 17, 7, 111, 112, 116, 105, 111, 110, 115, 19, 0, 22, 4, 5, 102, 101, 116, 99, 104, 0, 1,
-    23, 3, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 34, 112, 107, 103, 58, 47, 104, 116,
-    116, 112, 45, 102, 101, 116, 99, 104, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 45,
-    105, 109, 112, 111, 114, 116, 115, 5, 0, 4, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104,
-    26, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 47, 104, 116, 116,
-    112, 45, 102, 101, 116, 99, 104, 4, 1, 0, 68, 9, 112, 114, 111, 100, 117, 99, 101, 114, 115, 1,
-    12, 112, 114, 111, 99, 101, 115, 115, 101, 100, 45, 98, 121, 2, 13, 119, 105, 116, 45, 99, 111,
-    109, 112, 111, 110, 101, 110, 116, 5, 48, 46, 55, 46, 49, 16, 119, 105, 116, 45, 98, 105, 110,
-    100, 103, 101, 110, 45, 114, 117, 115, 116, 5, 48, 46, 51, 46, 48, 11, 31, 1, 10, 104, 116,
-    116, 112, 45, 102, 101, 116, 99, 104, 15, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 102,
-    101, 116, 99, 104, 3, 0, 0,
+    13, 4, 8, 114, 101, 115, 112, 111, 110, 115, 101, 0, 3, 0, 14, 1, 114, 4, 6, 115, 116, 97, 116,
+    117, 115, 5, 7, 104, 101, 97, 100, 101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 3, 117, 114,
+    108, 3, 4, 11, 114, 101, 115, 112, 111, 110, 115, 101, 45, 118, 50, 0, 3, 0, 16, 1, 114, 4, 6,
+    109, 101, 116, 104, 111, 100, 7, 3, 117, 114, 105, 3, 7, 104, 101, 97, 100, 101, 114, 115, 10,
+    4, 98, 111, 100, 121, 13, 4, 7, 114, 101, 113, 117, 101, 115, 116, 0, 3, 0, 18, 1, 114, 2, 7,
+    116, 105, 109, 101, 111, 117, 116, 121, 8, 114, 101, 100, 105, 114, 101, 99, 116, 1, 4, 13,
+    102, 101, 116, 99, 104, 45, 111, 112, 116, 105, 111, 110, 115, 0, 3, 0, 20, 1, 114, 3, 7, 116,
+    105, 109, 101, 111, 117, 116, 121, 8, 114, 101, 100, 105, 114, 101, 99, 116, 1, 10, 100, 101,
+    99, 111, 109, 112, 114, 101, 115, 115, 127, 4, 16, 102, 101, 116, 99, 104, 45, 111, 112, 116,
+    105, 111, 110, 115, 45, 118, 50, 0, 3, 0, 22, 1, 109, 6, 13, 110, 101, 116, 119, 111, 114, 107,
+    45, 101, 114, 114, 111, 114, 7, 116, 105, 109, 101, 111, 117, 116, 11, 105, 110, 118, 97, 108,
+    105, 100, 45, 117, 114, 108, 23, 100, 101, 115, 116, 105, 110, 97, 116, 105, 111, 110, 45, 110,
+    111, 116, 45, 97, 108, 108, 111, 119, 101, 100, 17, 116, 111, 111, 45, 109, 97, 110, 121, 45,
+    114, 101, 113, 117, 101, 115, 116, 115, 15, 105, 110, 118, 97, 108, 105, 100, 45, 114, 101,
+    113, 117, 101, 115, 116, 4, 11, 102, 101, 116, 99, 104, 45, 101, 114, 114, 111, 114, 0, 3, 0,
+    24, 1, 106, 1, 15, 1, 25, 1, 64, 2, 3, 114, 101, 113, 19, 7, 111, 112, 116, 105, 111, 110, 115,
+    21, 0, 26, 4, 5, 102, 101, 116, 99, 104, 0, 1, 27, 1, 106, 1, 17, 1, 25, 1, 64, 2, 3, 114, 101,
+    113, 19, 7, 111, 112, 116, 105, 111, 110, 115, 23, 0, 28, 4, 8, 102, 101, 116, 99, 104, 45,
+    118, 50, 0, 1, 29, 1, 112, 19, 1, 112, 28, 1, 64, 2, 4, 114, 101, 113, 115, 30, 7, 111, 112,
+    116, 105, 111, 110, 115, 23, 0, 31, 4, 9, 102, 101, 116, 99, 104, 45, 97, 108, 108, 0, 1, 32,
+    4, 18, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 45, 105, 109, 112, 111, 114, 116, 115,
+    34, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 47, 104, 116, 116,
+    112, 45, 102, 101, 116, 99, 104, 45, 105, 109, 112, 111, 114, 116, 115, 5, 0, 1, 65, 2, 1, 66,
+    36, 1, 109, 3, 6, 102, 111, 108, 108, 111, 119, 5, 101, 114, 114, 111, 114, 6, 109, 97, 110,
+    117, 97, 108, 4, 15, 114, 101, 100, 105, 114, 101, 99, 116, 45, 112, 111, 108, 105, 99, 121, 0,
+    3, 0, 0, 1, 115, 4, 8, 104, 116, 116, 112, 45, 117, 114, 105, 0, 3, 0, 2, 1, 123, 4, 11, 104,
+    116, 116, 112, 45, 115, 116, 97, 116, 117, 115, 0, 3, 0, 4, 1, 115, 4, 11, 104, 116, 116, 112,
+    45, 109, 101, 116, 104, 111, 100, 0, 3, 0, 6, 1, 111, 2, 115, 115, 1, 112, 8, 4, 12, 104, 116,
+    116, 112, 45, 104, 101, 97, 100, 101, 114, 115, 0, 3, 0, 9, 1, 112, 125, 4, 9, 104, 116, 116,
+    112, 45, 98, 111, 100, 121, 0, 3, 0, 11, 1, 107, 12, 1, 114, 3, 6, 115, 116, 97, 116, 117, 115,
+    5, 7, 104, 101, 97, 100, 101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 4, 8, 114, 101, 115, 112,
+    111, 110, 115, 101, 0, 3, 0, 14, 1, 114, 4, 6, 115, 116, 97, 116, 117, 115, 5, 7, 104, 101, 97,
+    100, 101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 3, 117, 114, 108, 3, 4, 11, 114, 101, 115,
+    112, 111, 110, 115, 101, 45, 118, 50, 0, 3, 0, 16, 1, 114, 4, 6, 109, 101, 116, 104, 111, 100,
+    7, 3, 117, 114, 105, 3, 7, 104, 101, 97, 100, 101, 114, 115, 10, 4, 98, 111, 100, 121, 13, 4,
+    7, 114, 101, 113, 117, 101, 115, 116, 0, 3, 0, 18, 1, 114, 2, 7, 116, 105, 109, 101, 111, 117,
+    116, 121, 8, 114, 101, 100, 105, 114, 101, 99, 116, 1, 4, 13, 102, 101, 116, 99, 104, 45, 111,
+    112, 116, 105, 111, 110, 115, 0, 3, 0, 20, 1, 114, 3, 7, 116, 105, 109, 101, 111, 117, 116,
+    121, 8, 114, 101, 100, 105, 114, 101, 99, 116, 1, 10, 100, 101, 99, 111, 109, 112, 114, 101,
+    115, 115, 127, 4, 16, 102, 101, 116, 99, 104, 45, 111, 112, 116, 105, 111, 110, 115, 45, 118,
+    50, 0, 3, 0, 22, 1, 109, 6, 13, 110, 101, 116, 119, 111, 114, 107, 45, 101, 114, 114, 111, 114,
+    7, 116, 105, 109, 101, 111, 117, 116, 11, 105, 110, 118, 97, 108, 105, 100, 45, 117, 114, 108,
+    23, 100, 101, 115, 116, 105, 110, 97, 116, 105, 111, 110, 45, 110, 111, 116, 45, 97, 108, 108,
+    111, 119, 101, 100, 17, 116, 111, 111, 45, 109, 97, 110, 121, 45, 114, 101, 113, 117, 101, 115,
+    116, 115, 15, 105, 110, 118, 97, 108, 105, 100, 45, 114, 101, 113, 117, 101, 115, 116, 4, 11,
+    102, 101, 116, 99, 104, 45, 101, 114, 114, 111, 114, 0, 3, 0, 24, 1, 106, 1, 15, 1, 25, 1, 64,
+    2, 3, 114, 101, 113, 19, 7, 111, 112, 116, 105, 111, 110, 115, 21, 0, 26, 4, 5, 102, 101, 116,
+    99, 104, 0, 1, 27, 1, 106, 1, 17, 1, 25, 1, 64, 2, 3, 114, 101, 113, 19, 7, 111, 112, 116, 105,
+    111, 110, 115, 23, 0, 28, 4, 8, 102, 101, 116, 99, 104, 45, 118, 50, 0, 1, 29, 1, 112, 19, 1,
+    112, 28, 1, 64, 2, 4, 114, 101, 113, 115, 30, 7, 111, 112, 116, 105, 111, 110, 115, 23, 0, 31,
+    4, 9, 102, 101, 116, 99, 104, 45, 97, 108, 108, 0, 1, 32, 3, 10, 104, 116, 116, 112, 45, 102,
+    101, 116, 99, 104, 34, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104,
+    47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 45, 105, 109, 112, 111, 114, 116, 115, 5,
+    0, 4, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 26, 112, 107, 103, 58, 47, 104, 116,
+    116, 112, 45, 102, 101, 116, 99, 104, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 4, 1,
+    0, 68, 9, 112, 114, 111, 100, 117, 99, 101, 114, 115, 1, 12, 112, 114, 111, 99, 101, 115, 115,
+    101, 100, 45, 98, 121, 2, 13, 119, 105, 116, 45, 99, 111, 109, 112, 111, 110, 101, 110, 116, 5,
+    48, 46, 55, 46, 49, 16, 119, 105, 116, 45, 98, 105, 110, 100, 103, 101, 110, 45, 114, 117, 115,
+    116, 5, 48, 46, 51, 46, 48, 11, 31, 1, 10, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 15,
+    112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 102, 101, 116, 99, 104, 3, 0, 0,
 ];
 
 #[inline(never)]
@@ -1,5 +1,141 @@
 // Generated by `wit-bindgen` 0.3.0. DO NOT EDIT!
 
+#[allow(clippy::all)]
+pub mod http_context {
+    #[derive(Clone)]
+    pub struct RequestContext {
+        pub remote_addr: wit_bindgen::rt::string::String,
+        pub request_id: wit_bindgen::rt::string::String,
+        pub tls: bool,
+        pub function_name: wit_bindgen::rt::string::String,
+        pub function_version: wit_bindgen::rt::string::String,
+        pub deadline: u64,
+    }
+    impl core::fmt::Debug for RequestContext {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("RequestContext")
+                .field("remote-addr", &self.remote_addr)
+                .field("request-id", &self.request_id)
+                .field("tls", &self.tls)
+                .field("function-name", &self.function_name)
+                .field("function-version", &self.function_version)
+                .field("deadline", &self.deadline)
+                .finish()
+        }
+    }
+    #[allow(clippy::all)]
+    pub fn get_request_context() -> RequestContext {
+        #[allow(unused_imports)]
+        use wit_bindgen::rt::{alloc, string::String, vec::Vec};
+        unsafe {
+            #[repr(align(8))]
+            struct RetArea([u8; 48]);
+            let mut ret_area = core::mem::MaybeUninit::<RetArea>::uninit();
+            let ptr0 = ret_area.as_mut_ptr() as i32;
+            #[link(wasm_import_module = "http-context")]
+            extern "C" {
+                #[cfg_attr(target_arch = "wasm32", link_name = "get-request-context")]
+                #[cfg_attr(
+                    not(target_arch = "wasm32"),
+                    link_name = "http-context_get-request-context"
+                )]
+                fn wit_import(_: i32);
+            }
+            wit_import(ptr0);
+            let len1 = *((ptr0 + 4) as *const i32) as usize;
+            let len2 = *((ptr0 + 12) as *const i32) as usize;
+            let len3 = *((ptr0 + 24) as *const i32) as usize;
+            let len4 = *((ptr0 + 32) as *const i32) as usize;
+            RequestContext {
+                remote_addr: {
+                    #[cfg(not(debug_assertions))]
+                    {
+                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                            *((ptr0 + 0) as *const i32) as *mut _,
+                            len1,
+                            len1,
+                        ))
+                    }
+                    #[cfg(debug_assertions)]
+                    {
+                        String::from_utf8(Vec::from_raw_parts(
+                            *((ptr0 + 0) as *const i32) as *mut _,
+                            len1,
+                            len1,
+                        ))
+                        .unwrap()
+                    }
+                },
+                request_id: {
+                    #[cfg(not(debug_assertions))]
+                    {
+                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                            *((ptr0 + 8) as *const i32) as *mut _,
+                            len2,
+                            len2,
+                        ))
+                    }
+                    #[cfg(debug_assertions)]
+                    {
+                        String::from_utf8(Vec::from_raw_parts(
+                            *((ptr0 + 8) as *const i32) as *mut _,
+                            len2,
+                            len2,
+                        ))
+                        .unwrap()
+                    }
+                },
+                tls: match i32::from(*((ptr0 + 16) as *const u8)) {
+                    0 => false,
+                    1 => true,
+                    #[cfg(not(debug_assertions))]
+                    _ => core::hint::unreachable_unchecked(),
+                    #[cfg(debug_assertions)]
+                    _ => panic!("invalid bool discriminant"),
+                },
+                function_name: {
+                    #[cfg(not(debug_assertions))]
+                    {
+                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                            *((ptr0 + 20) as *const i32) as *mut _,
+                            len3,
+                            len3,
+                        ))
+                    }
+                    #[cfg(debug_assertions)]
+                    {
+                        String::from_utf8(Vec::from_raw_parts(
+                            *((ptr0 + 20) as *const i32) as *mut _,
+                            len3,
+                            len3,
+                        ))
+                        .unwrap()
+                    }
+                },
+                function_version: {
+                    #[cfg(not(debug_assertions))]
+                    {
+                        String::from_utf8_unchecked(Vec::from_raw_parts(
+                            *((ptr0 + 28) as *const i32) as *mut _,
+                            len4,
+                            len4,
+                        ))
+                    }
+                    #[cfg(debug_assertions)]
+                    {
+                        String::from_utf8(Vec::from_raw_parts(
+                            *((ptr0 + 28) as *const i32) as *mut _,
+                            len4,
+                            len4,
+                        ))
+                        .unwrap()
+                    }
+                },
+                deadline: *((ptr0 + 40) as *const i64) as u64,
+            }
+        }
+    }
+}
 #[allow(clippy::all)]
 pub mod http_handler {
     pub type HttpUri = wit_bindgen::rt::string::String;
@@ -279,10 +415,10 @@ macro_rules! export_http_handler(($t:ident) => {
 #[cfg(target_arch = "wasm32")]
 #[link_section = "component-type:http-handler"]
 #[doc(hidden)]
-pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 764] = [
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 1140] = [
     2, 0, 12, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 12, 104, 116, 116, 112, 45,
     104, 97, 110, 100, 108, 101, 114, 12, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114,
-    0, 97, 115, 109, 12, 0, 1, 0, 7, 221, 4, 1, 65, 4, 1, 66, 18, 1, 115, 4, 8, 104, 116, 116, 112,
+    0, 97, 115, 109, 12, 0, 1, 0, 7, 213, 7, 1, 65, 6, 1, 66, 18, 1, 115, 4, 8, 104, 116, 116, 112,
     45, 117, 114, 105, 0, 3, 0, 0, 1, 123, 4, 11, 104, 116, 116, 112, 45, 115, 116, 97, 116, 117,
     115, 0, 3, 0, 2, 1, 115, 4, 11, 104, 116, 116, 112, 45, 109, 101, 116, 104, 111, 100, 0, 3, 0,
     4, 1, 111, 2, 115, 115, 1, 112, 6, 4, 12, 104, 116, 116, 112, 45, 104, 101, 97, 100, 101, 114,
@@ -295,27 +431,45 @@ pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 764] = [
     116, 0, 1, 16, 4, 20, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 45, 101, 120,
     112, 111, 114, 116, 115, 38, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 104, 97, 110, 100,
     108, 101, 114, 47, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 45, 101, 120, 112,
-    111, 114, 116, 115, 5, 0, 1, 65, 2, 1, 66, 18, 1, 115, 4, 8, 104, 116, 116, 112, 45, 117, 114,
-    105, 0, 3, 0, 0, 1, 123, 4, 11, 104, 116, 116, 112, 45, 115, 116, 97, 116, 117, 115, 0, 3, 0,
-    2, 1, 115, 4, 11, 104, 116, 116, 112, 45, 109, 101, 116, 104, 111, 100, 0, 3, 0, 4, 1, 111, 2,
-    115, 115, 1, 112, 6, 4, 12, 104, 116, 116, 112, 45, 104, 101, 97, 100, 101, 114, 115, 0, 3, 0,
-    7, 1, 112, 125, 4, 9, 104, 116, 116, 112, 45, 98, 111, 100, 121, 0, 3, 0, 9, 1, 107, 10, 1,
-    114, 3, 6, 115, 116, 97, 116, 117, 115, 3, 7, 104, 101, 97, 100, 101, 114, 115, 8, 4, 98, 111,
-    100, 121, 11, 4, 8, 114, 101, 115, 112, 111, 110, 115, 101, 0, 3, 0, 12, 1, 114, 4, 6, 109,
-    101, 116, 104, 111, 100, 5, 3, 117, 114, 105, 1, 7, 104, 101, 97, 100, 101, 114, 115, 8, 4, 98,
-    111, 100, 121, 11, 4, 7, 114, 101, 113, 117, 101, 115, 116, 0, 3, 0, 14, 1, 64, 1, 3, 114, 101,
-    113, 15, 0, 13, 4, 14, 104, 97, 110, 100, 108, 101, 45, 114, 101, 113, 117, 101, 115, 116, 0,
-    1, 16, 4, 12, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 38, 112, 107, 103, 58,
-    47, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 47, 104, 116, 116, 112, 45, 104,
-    97, 110, 100, 108, 101, 114, 45, 101, 120, 112, 111, 114, 116, 115, 5, 0, 4, 12, 104, 116, 116,
-    112, 45, 104, 97, 110, 100, 108, 101, 114, 30, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45,
-    104, 97, 110, 100, 108, 101, 114, 47, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114,
-    4, 1, 0, 68, 9, 112, 114, 111, 100, 117, 99, 101, 114, 115, 1, 12, 112, 114, 111, 99, 101, 115,
-    115, 101, 100, 45, 98, 121, 2, 13, 119, 105, 116, 45, 99, 111, 109, 112, 111, 110, 101, 110,
-    116, 5, 48, 46, 55, 46, 49, 16, 119, 105, 116, 45, 98, 105, 110, 100, 103, 101, 110, 45, 114,
-    117, 115, 116, 5, 48, 46, 51, 46, 48, 11, 35, 1, 12, 104, 116, 116, 112, 45, 104, 97, 110, 100,
-    108, 101, 114, 17, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101,
-    114, 3, 0, 0,
+    111, 114, 116, 115, 5, 0, 1, 66, 4, 1, 114, 6, 11, 114, 101, 109, 111, 116, 101, 45, 97, 100,
+    100, 114, 115, 10, 114, 101, 113, 117, 101, 115, 116, 45, 105, 100, 115, 3, 116, 108, 115, 127,
+    13, 102, 117, 110, 99, 116, 105, 111, 110, 45, 110, 97, 109, 101, 115, 16, 102, 117, 110, 99,
+    116, 105, 111, 110, 45, 118, 101, 114, 115, 105, 111, 110, 115, 8, 100, 101, 97, 100, 108, 105,
+    110, 101, 119, 4, 15, 114, 101, 113, 117, 101, 115, 116, 45, 99, 111, 110, 116, 101, 120, 116,
+    0, 3, 0, 0, 1, 64, 0, 0, 1, 4, 19, 103, 101, 116, 45, 114, 101, 113, 117, 101, 115, 116, 45,
+    99, 111, 110, 116, 101, 120, 116, 0, 1, 2, 4, 20, 104, 116, 116, 112, 45, 99, 111, 110, 116,
+    101, 120, 116, 45, 105, 109, 112, 111, 114, 116, 115, 38, 112, 107, 103, 58, 47, 104, 116, 116,
+    112, 45, 104, 97, 110, 100, 108, 101, 114, 47, 104, 116, 116, 112, 45, 99, 111, 110, 116, 101,
+    120, 116, 45, 105, 109, 112, 111, 114, 116, 115, 5, 1, 1, 65, 4, 1, 66, 4, 1, 114, 6, 11, 114,
+    101, 109, 111, 116, 101, 45, 97, 100, 100, 114, 115, 10, 114, 101, 113, 117, 101, 115, 116, 45,
+    105, 100, 115, 3, 116, 108, 115, 127, 13, 102, 117, 110, 99, 116, 105, 111, 110, 45, 110, 97,
+    109, 101, 115, 16, 102, 117, 110, 99, 116, 105, 111, 110, 45, 118, 101, 114, 115, 105, 111,
+    110, 115, 8, 100, 101, 97, 100, 108, 105, 110, 101, 119, 4, 15, 114, 101, 113, 117, 101, 115,
+    116, 45, 99, 111, 110, 116, 101, 120, 116, 0, 3, 0, 0, 1, 64, 0, 0, 1, 4, 19, 103, 101, 116,
+    45, 114, 101, 113, 117, 101, 115, 116, 45, 99, 111, 110, 116, 101, 120, 116, 0, 1, 2, 3, 12,
+    104, 116, 116, 112, 45, 99, 111, 110, 116, 101, 120, 116, 38, 112, 107, 103, 58, 47, 104, 116,
+    116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 47, 104, 116, 116, 112, 45, 99, 111, 110, 116,
+    101, 120, 116, 45, 105, 109, 112, 111, 114, 116, 115, 5, 0, 1, 66, 18, 1, 115, 4, 8, 104, 116,
+    116, 112, 45, 117, 114, 105, 0, 3, 0, 0, 1, 123, 4, 11, 104, 116, 116, 112, 45, 115, 116, 97,
+    116, 117, 115, 0, 3, 0, 2, 1, 115, 4, 11, 104, 116, 116, 112, 45, 109, 101, 116, 104, 111, 100,
+    0, 3, 0, 4, 1, 111, 2, 115, 115, 1, 112, 6, 4, 12, 104, 116, 116, 112, 45, 104, 101, 97, 100,
+    101, 114, 115, 0, 3, 0, 7, 1, 112, 125, 4, 9, 104, 116, 116, 112, 45, 98, 111, 100, 121, 0, 3,
+    0, 9, 1, 107, 10, 1, 114, 3, 6, 115, 116, 97, 116, 117, 115, 3, 7, 104, 101, 97, 100, 101, 114,
+    115, 8, 4, 98, 111, 100, 121, 11, 4, 8, 114, 101, 115, 112, 111, 110, 115, 101, 0, 3, 0, 12, 1,
+    114, 4, 6, 109, 101, 116, 104, 111, 100, 5, 3, 117, 114, 105, 1, 7, 104, 101, 97, 100, 101,
+    114, 115, 8, 4, 98, 111, 100, 121, 11, 4, 7, 114, 101, 113, 117, 101, 115, 116, 0, 3, 0, 14, 1,
+    64, 1, 3, 114, 101, 113, 15, 0, 13, 4, 14, 104, 97, 110, 100, 108, 101, 45, 114, 101, 113, 117,
+    101, 115, 116, 0, 1, 16, 4, 12, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 38,
+    112, 107, 103, 58, 47, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 47, 104, 116,
+    116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 45, 101, 120, 112, 111, 114, 116, 115, 5, 1, 4,
+    12, 104, 116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 30, 112, 107, 103, 58, 47, 104,
+    116, 116, 112, 45, 104, 97, 110, 100, 108, 101, 114, 47, 104, 116, 116, 112, 45, 104, 97, 110,
+    100, 108, 101, 114, 4, 2, 0, 68, 9, 112, 114, 111, 100, 117, 99, 101, 114, 115, 1, 12, 112,
+    114, 111, 99, 101, 115, 115, 101, 100, 45, 98, 121, 2, 13, 119, 105, 116, 45, 99, 111, 109,
+    112, 111, 110, 101, 110, 116, 5, 48, 46, 55, 46, 49, 16, 119, 105, 116, 45, 98, 105, 110, 100,
+    103, 101, 110, 45, 114, 117, 115, 116, 5, 48, 46, 51, 46, 48, 11, 35, 1, 12, 104, 116, 116,
+    112, 45, 104, 97, 110, 100, 108, 101, 114, 17, 112, 107, 103, 58, 47, 104, 116, 116, 112, 45,
+    104, 97, 110, 100, 108, 101, 114, 3, 0, 0,
 ];
 
 #[inline(never)]
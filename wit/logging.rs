@@ -0,0 +1,114 @@
+// Generated by `wit-bindgen` 0.3.0. DO NOT EDIT!
+#[allow(clippy::all)]
+pub mod logging {
+    #[repr(u8)]
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub enum Level {
+        Trace,
+        Debug,
+        Info,
+        Warn,
+        Error,
+    }
+    impl core::fmt::Debug for Level {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Level::Trace => f.debug_tuple("Level::Trace").finish(),
+                Level::Debug => f.debug_tuple("Level::Debug").finish(),
+                Level::Info => f.debug_tuple("Level::Info").finish(),
+                Level::Warn => f.debug_tuple("Level::Warn").finish(),
+                Level::Error => f.debug_tuple("Level::Error").finish(),
+            }
+        }
+    }
+    #[allow(clippy::all)]
+    pub fn log(level: Level, message: &str, fields: &[(&str, &str)]) {
+        #[allow(unused_imports)]
+        use wit_bindgen::rt::{alloc, string::String, vec::Vec};
+        unsafe {
+            let vec0 = message;
+            let ptr0 = vec0.as_ptr() as i32;
+            let len0 = vec0.len() as i32;
+            let vec4 = fields;
+            let len4 = vec4.len() as i32;
+            let layout4 = alloc::Layout::from_size_align_unchecked(vec4.len() * 16, 4);
+            let result4 = if layout4.size() != 0 {
+                let ptr = alloc::alloc(layout4);
+                if ptr.is_null() {
+                    alloc::handle_alloc_error(layout4);
+                }
+                ptr
+            } else {
+                core::ptr::null_mut()
+            };
+            for (i, e) in vec4.into_iter().enumerate() {
+                let base = result4 as i32 + (i as i32) * 16;
+                {
+                    let (t1_0, t1_1) = e;
+                    let vec2 = t1_0;
+                    let ptr2 = vec2.as_ptr() as i32;
+                    let len2 = vec2.len() as i32;
+                    *((base + 4) as *mut i32) = len2;
+                    *((base + 0) as *mut i32) = ptr2;
+                    let vec3 = t1_1;
+                    let ptr3 = vec3.as_ptr() as i32;
+                    let len3 = vec3.len() as i32;
+                    *((base + 12) as *mut i32) = len3;
+                    *((base + 8) as *mut i32) = ptr3;
+                }
+            }
+            #[link(wasm_import_module = "logging")]
+            extern "C" {
+                #[cfg_attr(target_arch = "wasm32", link_name = "log")]
+                #[cfg_attr(not(target_arch = "wasm32"), link_name = "logging_log")]
+                fn wit_import(_: i32, _: i32, _: i32, _: i32, _: i32);
+            }
+            wit_import(
+                match level {
+                    Level::Trace => 0,
+                    Level::Debug => 1,
+                    Level::Info => 2,
+                    Level::Warn => 3,
+                    Level::Error => 4,
+                },
+                ptr0,
+                len0,
+                result4 as i32,
+                len4,
+            );
+            if layout4.size() != 0 {
+                alloc::dealloc(result4, layout4);
+            }
+        }
+    }
+}
+#[cfg(target_arch = "wasm32")]
+#[link_section = "component-type:logging"]
+#[doc(hidden)]
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 440] = [
+    2, 0, 7, 108, 111, 103, 103, 105, 110, 103, 7, 108, 111, 103, 103, 105, 110, 103, 7, 108, 111,
+    103, 103, 105, 110, 103, 0, 97, 115, 109, 12, 0, 1, 0, 7, 178, 2, 1, 65, 4, 1, 66, 6, 1, 109,
+    5, 5, 116, 114, 97, 99, 101, 5, 100, 101, 98, 117, 103, 4, 105, 110, 102, 111, 4, 119, 97, 114,
+    110, 5, 101, 114, 114, 111, 114, 4, 5, 108, 101, 118, 101, 108, 0, 3, 0, 0, 1, 111, 2, 115,
+    115, 1, 112, 2, 1, 64, 3, 5, 108, 101, 118, 101, 108, 1, 7, 109, 101, 115, 115, 97, 103, 101,
+    115, 6, 102, 105, 101, 108, 100, 115, 3, 1, 0, 4, 3, 108, 111, 103, 0, 1, 4, 4, 15, 108, 111,
+    103, 103, 105, 110, 103, 45, 105, 109, 112, 111, 114, 116, 115, 28, 112, 107, 103, 58, 47, 108,
+    111, 103, 103, 105, 110, 103, 47, 108, 111, 103, 103, 105, 110, 103, 45, 105, 109, 112, 111,
+    114, 116, 115, 5, 0, 1, 65, 2, 1, 66, 6, 1, 109, 5, 5, 116, 114, 97, 99, 101, 5, 100, 101, 98,
+    117, 103, 4, 105, 110, 102, 111, 4, 119, 97, 114, 110, 5, 101, 114, 114, 111, 114, 4, 5, 108,
+    101, 118, 101, 108, 0, 3, 0, 0, 1, 111, 2, 115, 115, 1, 112, 2, 1, 64, 3, 5, 108, 101, 118,
+    101, 108, 1, 7, 109, 101, 115, 115, 97, 103, 101, 115, 6, 102, 105, 101, 108, 100, 115, 3, 1,
+    0, 4, 3, 108, 111, 103, 0, 1, 4, 3, 7, 108, 111, 103, 103, 105, 110, 103, 28, 112, 107, 103,
+    58, 47, 108, 111, 103, 103, 105, 110, 103, 47, 108, 111, 103, 103, 105, 110, 103, 45, 105, 109,
+    112, 111, 114, 116, 115, 5, 0, 4, 7, 108, 111, 103, 103, 105, 110, 103, 20, 112, 107, 103, 58,
+    47, 108, 111, 103, 103, 105, 110, 103, 47, 108, 111, 103, 103, 105, 110, 103, 4, 1, 0, 68, 9,
+    112, 114, 111, 100, 117, 99, 101, 114, 115, 1, 12, 112, 114, 111, 99, 101, 115, 115, 101, 100,
+    45, 98, 121, 2, 13, 119, 105, 116, 45, 99, 111, 109, 112, 111, 110, 101, 110, 116, 5, 48, 46,
+    55, 46, 49, 16, 119, 105, 116, 45, 98, 105, 110, 100, 103, 101, 110, 45, 114, 117, 115, 116, 5,
+    48, 46, 51, 46, 48, 11, 25, 1, 7, 108, 111, 103, 103, 105, 110, 103, 12, 112, 107, 103, 58, 47,
+    108, 111, 103, 103, 105, 110, 103, 3, 0, 0,
+];
+#[inline(never)]
+#[doc(hidden)]
+#[cfg(target_arch = "wasm32")]
+pub fn __link_section() {}